@@ -0,0 +1,115 @@
+//! Texture atlases: many sprite images packed into one GL texture
+
+use crate::engine::webgl::TextureHandle;
+use crate::utils::math::Vec2;
+use std::collections::HashMap;
+
+/// Rectangle of a texture in normalized UV space
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextureRegion {
+    pub handle: TextureHandle,
+    pub uv_min: Vec2,
+    pub uv_max: Vec2,
+}
+
+impl TextureRegion {
+    pub fn new(handle: TextureHandle, uv_min: Vec2, uv_max: Vec2) -> Self {
+        Self {
+            handle,
+            uv_min,
+            uv_max,
+        }
+    }
+
+    /// The whole texture
+    pub fn full(handle: TextureHandle) -> Self {
+        Self::new(handle, Vec2::new(0.0, 0.0), Vec2::new(1.0, 1.0))
+    }
+
+    pub fn uv_size(&self) -> Vec2 {
+        self.uv_max - self.uv_min
+    }
+}
+
+/// Named regions within a single atlas texture
+pub struct Atlas {
+    handle: TextureHandle,
+    width: f32,
+    height: f32,
+    regions: HashMap<String, TextureRegion>,
+}
+
+impl Atlas {
+    pub fn new(handle: TextureHandle, width: u32, height: u32) -> Self {
+        Self {
+            handle,
+            width: width.max(1) as f32,
+            height: height.max(1) as f32,
+            regions: HashMap::new(),
+        }
+    }
+
+    /// Registers a region by its pixel rectangle within the atlas
+    pub fn add_region(&mut self, name: &str, x: u32, y: u32, width: u32, height: u32) {
+        let uv_min = Vec2::new(x as f32 / self.width, y as f32 / self.height);
+        let uv_max = Vec2::new(
+            (x + width) as f32 / self.width,
+            (y + height) as f32 / self.height,
+        );
+        self.regions.insert(
+            name.to_string(),
+            TextureRegion::new(self.handle, uv_min, uv_max),
+        );
+    }
+
+    pub fn region(&self, name: &str) -> Option<TextureRegion> {
+        self.regions.get(name).copied()
+    }
+
+    pub fn handle(&self) -> TextureHandle {
+        self.handle
+    }
+
+    pub fn len(&self) -> usize {
+        self.regions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::components::Sprite;
+
+    fn atlas() -> Atlas {
+        let mut atlas = Atlas::new(TextureHandle(7), 256, 128);
+        atlas.add_region("player", 0, 0, 64, 64);
+        atlas.add_region("bullet", 64, 32, 16, 32);
+        atlas
+    }
+
+    #[test]
+    fn test_atlas_region_uvs() {
+        let atlas = atlas();
+
+        let bullet = atlas.region("bullet").unwrap();
+        assert_eq!(bullet.uv_min, Vec2::new(0.25, 0.25));
+        assert_eq!(bullet.uv_max, Vec2::new(0.3125, 0.5));
+        assert_eq!(bullet.uv_size(), Vec2::new(0.0625, 0.25));
+        assert!(atlas.region("missing").is_none());
+    }
+
+    #[test]
+    fn test_sprites_from_same_atlas_share_texture() {
+        let atlas = atlas();
+        let player = Sprite::from_region(atlas.region("player").unwrap());
+        let bullet = Sprite::from_region(atlas.region("bullet").unwrap());
+
+        assert_eq!(player.texture(), bullet.texture());
+        assert_eq!(player.texture(), atlas.handle());
+        assert_ne!(player.region, bullet.region);
+    }
+}
@@ -0,0 +1,176 @@
+//! Sprite batching to keep WebGL draw calls down
+
+use crate::engine::webgl::TextureHandle;
+use crate::game::components::Sprite;
+use crate::utils::math::Transform;
+use std::collections::HashMap;
+
+/// How a sprite is blended into the framebuffer; switching it breaks a batch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum BlendMode {
+    #[default]
+    Alpha,
+    Additive,
+}
+
+#[derive(Debug, Clone)]
+pub struct DrawRequest {
+    pub sprite: Sprite,
+    pub transform: Transform,
+    pub blend: BlendMode,
+}
+
+/// A contiguous run of draw requests sharing texture and blend state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Batch {
+    pub texture: TextureHandle,
+    pub blend: BlendMode,
+    pub start: usize,
+    pub count: usize,
+}
+
+/// Collects sprite draws for a frame and groups them into as few batches as
+/// possible. Requests are grouped in order of first appearance and keep their
+/// submission order within a group.
+pub struct RenderBatcher {
+    requests: Vec<DrawRequest>,
+    batches: Vec<Batch>,
+}
+
+impl RenderBatcher {
+    pub fn new() -> Self {
+        Self {
+            requests: Vec::new(),
+            batches: Vec::new(),
+        }
+    }
+
+    pub fn submit(&mut self, sprite: Sprite, transform: Transform) {
+        self.submit_with_blend(sprite, transform, BlendMode::default());
+    }
+
+    pub fn submit_with_blend(&mut self, sprite: Sprite, transform: Transform, blend: BlendMode) {
+        self.requests.push(DrawRequest {
+            sprite,
+            transform,
+            blend,
+        });
+    }
+
+    /// Sorts pending requests by texture and blend state and emits one batch
+    /// per contiguous run
+    pub fn build(&mut self) -> &[Batch] {
+        let mut group_order: HashMap<(TextureHandle, BlendMode), usize> = HashMap::new();
+        for request in &self.requests {
+            let next = group_order.len();
+            group_order
+                .entry((request.sprite.texture(), request.blend))
+                .or_insert(next);
+        }
+        self.requests
+            .sort_by_key(|request| group_order[&(request.sprite.texture(), request.blend)]);
+
+        self.batches.clear();
+        for (i, request) in self.requests.iter().enumerate() {
+            match self.batches.last_mut() {
+                Some(batch)
+                    if batch.texture == request.sprite.texture()
+                        && batch.blend == request.blend =>
+                {
+                    batch.count += 1;
+                }
+                _ => self.batches.push(Batch {
+                    texture: request.sprite.texture(),
+                    blend: request.blend,
+                    start: i,
+                    count: 1,
+                }),
+            }
+        }
+
+        &self.batches
+    }
+
+    /// Requests in draw order; valid after `build`
+    pub fn requests(&self) -> &[DrawRequest] {
+        &self.requests
+    }
+
+    pub fn batches(&self) -> &[Batch] {
+        &self.batches
+    }
+
+    pub fn batch_count(&self) -> u32 {
+        self.batches.len() as u32
+    }
+
+    pub fn clear(&mut self) {
+        self.requests.clear();
+        self.batches.clear();
+    }
+}
+
+impl Default for RenderBatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::math::Vec2;
+
+    fn submit_at(batcher: &mut RenderBatcher, texture: TextureHandle, x: f32) {
+        batcher.submit(
+            Sprite::new(texture),
+            Transform::from_position(Vec2::new(x, 0.0)),
+        );
+    }
+
+    #[test]
+    fn test_interleaved_textures_form_two_batches() {
+        let a = TextureHandle(1);
+        let b = TextureHandle(2);
+        let mut batcher = RenderBatcher::new();
+        submit_at(&mut batcher, a, 0.0);
+        submit_at(&mut batcher, b, 1.0);
+        submit_at(&mut batcher, a, 2.0);
+        submit_at(&mut batcher, b, 3.0);
+
+        let batches = batcher.build().to_vec();
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batcher.batch_count(), 2);
+        assert_eq!(
+            (batches[0].texture, batches[0].start, batches[0].count),
+            (a, 0, 2)
+        );
+        assert_eq!(
+            (batches[1].texture, batches[1].start, batches[1].count),
+            (b, 2, 2)
+        );
+
+        // Submission order is kept within a batch
+        let xs: Vec<f32> = batcher
+            .requests()
+            .iter()
+            .map(|r| r.transform.position.x)
+            .collect();
+        assert_eq!(xs, vec![0.0, 2.0, 1.0, 3.0]);
+    }
+
+    #[test]
+    fn test_blend_mode_splits_batches() {
+        let a = TextureHandle(1);
+        let mut batcher = RenderBatcher::new();
+        submit_at(&mut batcher, a, 0.0);
+        batcher.submit_with_blend(Sprite::new(a), Transform::identity(), BlendMode::Additive);
+        submit_at(&mut batcher, a, 1.0);
+
+        batcher.build();
+        assert_eq!(batcher.batch_count(), 2);
+
+        batcher.clear();
+        assert_eq!(batcher.build().len(), 0);
+    }
+}
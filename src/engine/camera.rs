@@ -0,0 +1,146 @@
+//! Player-following camera
+
+use crate::engine::culling::CullingSystem;
+use crate::utils::math::{Vec2, AABB};
+
+/// Seconds of player velocity the camera looks ahead by
+pub const DEFAULT_LOOKAHEAD: f32 = 0.4;
+/// Exponential rate, per second, at which the camera closes on its goal
+pub const DEFAULT_FOLLOW_RATE: f32 = 5.0;
+
+/// Tracks the player with velocity-based lookahead, easing towards its goal
+/// and never showing anything outside the world bounds
+#[derive(Debug, Clone, Copy)]
+pub struct Camera {
+    pub position: Vec2,
+    pub zoom: f32,
+    /// Size of the view in world units at zoom 1
+    pub viewport: Vec2,
+    pub world_bounds: AABB,
+    pub lookahead: f32,
+    pub follow_rate: f32,
+}
+
+impl Camera {
+    pub fn new(viewport: Vec2, world_bounds: AABB) -> Self {
+        let mut camera = Self {
+            position: (world_bounds.min + world_bounds.max) * 0.5,
+            zoom: 1.0,
+            viewport,
+            world_bounds,
+            lookahead: DEFAULT_LOOKAHEAD,
+            follow_rate: DEFAULT_FOLLOW_RATE,
+        };
+        camera.position = camera.clamp(camera.position);
+        camera
+    }
+
+    /// Eases towards a point ahead of the target along its velocity
+    pub fn follow(&mut self, target: Vec2, target_velocity: Vec2, delta: f32) {
+        let goal = self.clamp(target + target_velocity * self.lookahead);
+        let blend = 1.0 - (-self.follow_rate * delta.max(0.0)).exp();
+        self.position = self.clamp(self.position + (goal - self.position) * blend);
+    }
+
+    /// Jumps straight to `target`, e.g. on respawn or zone change
+    pub fn snap_to(&mut self, target: Vec2) {
+        self.position = self.clamp(target);
+    }
+
+    pub fn set_zoom(&mut self, zoom: f32) {
+        self.zoom = zoom.max(f32::EPSILON);
+        self.position = self.clamp(self.position);
+    }
+
+    /// Size of the visible region in world units
+    pub fn view_size(&self) -> Vec2 {
+        self.viewport / self.zoom
+    }
+
+    pub fn view_bounds(&self) -> AABB {
+        AABB::from_center_size(self.position, self.view_size())
+    }
+
+    /// Points the culling system at the current view
+    pub fn update_culling(&self, culling: &mut CullingSystem) {
+        culling.update_view(self.view_bounds());
+    }
+
+    /// Keeps the view inside the world; axes where the view is larger than
+    /// the world are centred instead
+    fn clamp(&self, position: Vec2) -> Vec2 {
+        let half = self.view_size() * 0.5;
+        let clamp_axis = |value: f32, min: f32, max: f32, half: f32| {
+            if max - min <= half * 2.0 {
+                (min + max) * 0.5
+            } else {
+                value.clamp(min + half, max - half)
+            }
+        };
+
+        Vec2::new(
+            clamp_axis(
+                position.x,
+                self.world_bounds.min.x,
+                self.world_bounds.max.x,
+                half.x,
+            ),
+            clamp_axis(
+                position.y,
+                self.world_bounds.min.y,
+                self.world_bounds.max.y,
+                half.y,
+            ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn camera() -> Camera {
+        let world = AABB::new(Vec2::new(-1000.0, -1000.0), Vec2::new(1000.0, 1000.0));
+        Camera::new(Vec2::new(400.0, 300.0), world)
+    }
+
+    #[test]
+    fn test_camera_leads_movement() {
+        let mut camera = camera();
+        for _ in 0..300 {
+            camera.follow(Vec2::new(0.0, 0.0), Vec2::new(100.0, 0.0), 1.0 / 60.0);
+        }
+
+        // Settles ahead of the player, not on it
+        assert!((camera.position.x - 100.0 * DEFAULT_LOOKAHEAD).abs() < 0.1);
+        assert!(camera.position.y.abs() < 0.001);
+    }
+
+    #[test]
+    fn test_camera_eases_towards_target() {
+        let mut camera = camera();
+        camera.follow(Vec2::new(200.0, 0.0), Vec2::new(0.0, 0.0), 1.0 / 60.0);
+        let first = camera.position.x;
+        assert!(first > 0.0 && first < 200.0);
+
+        camera.follow(Vec2::new(200.0, 0.0), Vec2::new(0.0, 0.0), 1.0 / 60.0);
+        assert!(camera.position.x > first && camera.position.x < 200.0);
+    }
+
+    #[test]
+    fn test_camera_clamps_to_world() {
+        let mut camera = camera();
+        camera.snap_to(Vec2::new(5000.0, -5000.0));
+        let view = camera.view_bounds();
+        assert_eq!(view.max.x, 1000.0);
+        assert_eq!(view.min.y, -1000.0);
+
+        // Zoomed far out, the view is wider than the world and centres on it
+        camera.set_zoom(0.1);
+        assert_eq!(camera.position, Vec2::new(0.0, 0.0));
+
+        let mut culling = CullingSystem::new(AABB::new(Vec2::new(0.0, 0.0), Vec2::new(1.0, 1.0)));
+        camera.update_culling(&mut culling);
+        assert_eq!(culling.view_bounds(), camera.view_bounds());
+    }
+}
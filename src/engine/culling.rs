@@ -0,0 +1,264 @@
+//! Visibility culling and level-of-detail selection
+
+use crate::game::components::Position;
+use crate::game::entities::Entity;
+use crate::utils::math::{Vec2, AABB};
+use cgmath::InnerSpace;
+use std::collections::HashMap;
+
+/// Culls entities against the camera's view bounds
+pub struct CullingSystem {
+    view_bounds: AABB,
+    visible_count: usize,
+    culled_count: usize,
+}
+
+impl CullingSystem {
+    pub fn new(view_bounds: AABB) -> Self {
+        Self {
+            view_bounds,
+            visible_count: 0,
+            culled_count: 0,
+        }
+    }
+
+    /// Moves or rescales the view region, e.g. when the camera pans or zooms
+    pub fn update_view(&mut self, bounds: AABB) {
+        self.view_bounds = bounds;
+    }
+
+    pub fn view_bounds(&self) -> AABB {
+        self.view_bounds
+    }
+
+    /// Returns the indices of the positions inside the view
+    pub fn cull_by_position(&mut self, positions: &[Position]) -> Vec<usize> {
+        let visible: Vec<usize> = positions
+            .iter()
+            .enumerate()
+            .filter(|(_, position)| self.view_bounds.contains(position.as_vec2()))
+            .map(|(i, _)| i)
+            .collect();
+
+        self.record(visible.len(), positions.len());
+        visible
+    }
+
+    /// Culls using each entity's bounds, so large sprites that only overlap
+    /// the edge of the view are kept
+    pub fn cull_by_aabb(&mut self, entities: &[(Entity, AABB)]) -> Vec<Entity> {
+        let visible: Vec<Entity> = entities
+            .iter()
+            .filter(|(_, bounds)| self.view_bounds.intersects(bounds))
+            .map(|(entity, _)| *entity)
+            .collect();
+
+        self.record(visible.len(), entities.len());
+        visible
+    }
+
+    pub fn visible_count(&self) -> usize {
+        self.visible_count
+    }
+
+    pub fn culled_count(&self) -> usize {
+        self.culled_count
+    }
+
+    fn record(&mut self, visible: usize, total: usize) {
+        self.visible_count = visible;
+        self.culled_count = total - visible;
+    }
+}
+
+/// Level returned for entities too far away to render
+pub const LOD_CULLED: u8 = 255;
+
+/// Default dead-band around each LOD threshold, in world units
+pub const DEFAULT_LOD_HYSTERESIS: f32 = 10.0;
+
+/// Picks a detail level from an entity's distance to the camera
+pub struct LODSystem {
+    camera_pos: Vec2,
+    /// Upper distance bound for each detail level, nearest first
+    thresholds: Vec<f32>,
+    max_distance: f32,
+    /// How far past a threshold an entity must move before its level changes
+    hysteresis: f32,
+    levels: HashMap<Entity, usize>,
+}
+
+impl LODSystem {
+    pub fn new(camera_pos: Vec2) -> Self {
+        Self {
+            camera_pos,
+            thresholds: vec![50.0, 150.0, 400.0],
+            max_distance: 800.0,
+            hysteresis: DEFAULT_LOD_HYSTERESIS,
+            levels: HashMap::new(),
+        }
+    }
+
+    pub fn with_hysteresis(mut self, hysteresis: f32) -> Self {
+        self.hysteresis = hysteresis.max(0.0);
+        self
+    }
+
+    pub fn set_camera_position(&mut self, camera_pos: Vec2) {
+        self.camera_pos = camera_pos;
+    }
+
+    /// Stateless level lookup, without hysteresis
+    pub fn get_lod_level(&self, position: Vec2) -> u8 {
+        let distance = (position - self.camera_pos).magnitude();
+        self.level_for_index(self.raw_index(distance))
+    }
+
+    pub fn should_render(&self, position: Vec2) -> bool {
+        self.get_lod_level(position) != LOD_CULLED
+    }
+
+    /// Level for a tracked entity. The entity keeps its previous level until
+    /// it crosses a threshold by more than the hysteresis margin, so hovering
+    /// on a boundary doesn't flicker between levels.
+    pub fn update_lod(&mut self, entity: Entity, position: Vec2) -> u8 {
+        let distance = (position - self.camera_pos).magnitude();
+        let index = match self.levels.get(&entity) {
+            Some(&previous) => self.settle_index(previous, distance),
+            None => self.raw_index(distance),
+        };
+
+        self.levels.insert(entity, index);
+        self.level_for_index(index)
+    }
+
+    /// Drops remembered state for an entity that left the world
+    pub fn forget(&mut self, entity: Entity) {
+        self.levels.remove(&entity);
+    }
+
+    /// Boundary between index `i` and `i + 1`; the last one is the cull distance
+    fn boundary(&self, index: usize) -> f32 {
+        self.thresholds
+            .get(index)
+            .copied()
+            .unwrap_or(self.max_distance)
+    }
+
+    fn raw_index(&self, distance: f32) -> usize {
+        (0..=self.thresholds.len())
+            .find(|&i| distance <= self.boundary(i))
+            .unwrap_or(self.thresholds.len() + 1)
+    }
+
+    fn settle_index(&self, mut index: usize, distance: f32) -> usize {
+        let last = self.thresholds.len() + 1;
+        while index < last && distance > self.boundary(index) + self.hysteresis {
+            index += 1;
+        }
+        while index > 0 && distance < self.boundary(index - 1) - self.hysteresis {
+            index -= 1;
+        }
+        index
+    }
+
+    fn level_for_index(&self, index: usize) -> u8 {
+        if index > self.thresholds.len() {
+            LOD_CULLED
+        } else {
+            index as u8
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn view() -> AABB {
+        AABB::new(Vec2::new(0.0, 0.0), Vec2::new(100.0, 100.0))
+    }
+
+    #[test]
+    fn test_cull_by_aabb_keeps_large_entity_on_edge() {
+        let mut culling = CullingSystem::new(view());
+
+        // Centre is outside the view but the sprite reaches into it
+        let straddling = AABB::from_center_size(Vec2::new(120.0, 50.0), Vec2::new(60.0, 60.0));
+        let outside = AABB::from_center_size(Vec2::new(200.0, 50.0), Vec2::new(20.0, 20.0));
+        let entities = vec![(Entity::new(1), straddling), (Entity::new(2), outside)];
+
+        let visible = culling.cull_by_aabb(&entities);
+        assert_eq!(visible, vec![Entity::new(1)]);
+        assert_eq!(culling.visible_count(), 1);
+        assert_eq!(culling.culled_count(), 1);
+    }
+
+    #[test]
+    fn test_update_view_follows_camera() {
+        let mut culling = CullingSystem::new(view());
+        let positions = vec![Position::new(250.0, 50.0)];
+        assert!(culling.cull_by_position(&positions).is_empty());
+
+        culling.update_view(AABB::new(Vec2::new(200.0, 0.0), Vec2::new(300.0, 100.0)));
+        assert_eq!(culling.cull_by_position(&positions), vec![0]);
+    }
+
+    #[test]
+    fn test_lod_levels_by_distance() {
+        let lod = LODSystem::new(Vec2::new(0.0, 0.0));
+
+        assert_eq!(lod.get_lod_level(Vec2::new(10.0, 0.0)), 0);
+        assert_eq!(lod.get_lod_level(Vec2::new(100.0, 0.0)), 1);
+        assert_eq!(lod.get_lod_level(Vec2::new(300.0, 0.0)), 2);
+        assert_eq!(lod.get_lod_level(Vec2::new(600.0, 0.0)), 3);
+        assert_eq!(lod.get_lod_level(Vec2::new(900.0, 0.0)), LOD_CULLED);
+    }
+
+    #[test]
+    fn test_lod_hysteresis_holds_level_near_threshold() {
+        let mut lod = LODSystem::new(Vec2::new(0.0, 0.0));
+        let entity = Entity::new(1);
+
+        // Oscillate around the 150 boundary between levels 1 and 2
+        assert_eq!(lod.update_lod(entity, Vec2::new(145.0, 0.0)), 1);
+        for &x in &[152.0, 148.0, 155.0, 146.0, 159.0] {
+            assert_eq!(lod.update_lod(entity, Vec2::new(x, 0.0)), 1);
+        }
+
+        // Clearly crossing switches, then the new level is held in turn
+        assert_eq!(lod.update_lod(entity, Vec2::new(165.0, 0.0)), 2);
+        for &x in &[148.0, 145.0, 152.0] {
+            assert_eq!(lod.update_lod(entity, Vec2::new(x, 0.0)), 2);
+        }
+        assert_eq!(lod.update_lod(entity, Vec2::new(135.0, 0.0)), 1);
+    }
+
+    #[test]
+    fn test_lod_hysteresis_is_per_entity() {
+        let mut lod = LODSystem::new(Vec2::new(0.0, 0.0));
+        let near = Entity::new(1);
+        let far = Entity::new(2);
+
+        lod.update_lod(near, Vec2::new(140.0, 0.0));
+        lod.update_lod(far, Vec2::new(170.0, 0.0));
+
+        let boundary = Vec2::new(150.0, 0.0);
+        assert_eq!(lod.update_lod(near, boundary), 1);
+        assert_eq!(lod.update_lod(far, boundary), 2);
+
+        // Forgotten entities start fresh from the raw level
+        lod.forget(far);
+        assert_eq!(lod.update_lod(far, boundary), 1);
+    }
+
+    #[test]
+    fn test_lod_hysteresis_large_jump() {
+        let mut lod = LODSystem::new(Vec2::new(0.0, 0.0));
+        let entity = Entity::new(1);
+
+        assert_eq!(lod.update_lod(entity, Vec2::new(10.0, 0.0)), 0);
+        assert_eq!(lod.update_lod(entity, Vec2::new(900.0, 0.0)), LOD_CULLED);
+        assert_eq!(lod.update_lod(entity, Vec2::new(10.0, 0.0)), 0);
+    }
+}
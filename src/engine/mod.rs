@@ -2,3 +2,7 @@ pub mod renderer;
 pub mod audio;
 pub mod input;
 pub mod resources;
+pub mod culling;
+pub mod batcher;
+pub mod atlas;
+pub mod camera;
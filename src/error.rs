@@ -0,0 +1,27 @@
+//! Crate-wide error type for game APIs that can fail on bad ids or
+//! exhausted resources
+
+use crate::game::state::UpgradeId;
+use crate::game::systems::weapon::WeaponId;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    UnknownWeapon(WeaponId),
+    UnknownUpgrade(UpgradeId),
+    PoolExhausted,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::UnknownWeapon(id) => write!(f, "unknown weapon {}", id.0),
+            Error::UnknownUpgrade(id) => write!(f, "unknown upgrade {}", id.0),
+            Error::PoolExhausted => write!(f, "object pool exhausted"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+pub type Result<T> = std::result::Result<T, Error>;
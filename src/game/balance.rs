@@ -0,0 +1,160 @@
+//! Balance tunables shared by the difficulty curve, wave generation and
+//! upgrade offers. Loadable from JSON; missing fields keep their defaults,
+//! which reproduce the built-in balance.
+
+use crate::game::systems::upgrade::Rarity;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BalanceConfig {
+    pub difficulty: DifficultyBalance,
+    pub waves: WaveBalance,
+    pub rarity_weights: RarityWeights,
+}
+
+impl BalanceConfig {
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+/// Zone difficulty curve used by `DifficultyManager`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DifficultyBalance {
+    /// Difficulty before any zones are cleared
+    pub base: f32,
+    /// Difficulty added per zone
+    pub per_zone: f32,
+}
+
+impl Default for DifficultyBalance {
+    fn default() -> Self {
+        Self {
+            base: 0.1,
+            per_zone: 0.15,
+        }
+    }
+}
+
+/// How generated waves scale with difficulty
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WaveBalance {
+    pub health_per_difficulty: f32,
+    pub damage_per_difficulty: f32,
+    pub speed_per_difficulty: f32,
+    /// Chance of an elite per point of difficulty
+    pub elite_chance_per_difficulty: f64,
+    /// Chance, from 0 to 1, that a zone gets a special event
+    pub special_event_chance: f64,
+}
+
+impl WaveBalance {
+    pub fn health_multiplier(&self, difficulty: f32) -> f32 {
+        1.0 + difficulty * self.health_per_difficulty
+    }
+
+    pub fn damage_multiplier(&self, difficulty: f32) -> f32 {
+        1.0 + difficulty * self.damage_per_difficulty
+    }
+
+    pub fn speed_multiplier(&self, difficulty: f32) -> f32 {
+        1.0 + difficulty * self.speed_per_difficulty
+    }
+
+    pub fn elite_chance(&self, difficulty: f32) -> f64 {
+        (difficulty as f64 * self.elite_chance_per_difficulty).clamp(0.0, 1.0)
+    }
+
+    pub fn event_chance(&self) -> f64 {
+        self.special_event_chance.clamp(0.0, 1.0)
+    }
+}
+
+impl Default for WaveBalance {
+    fn default() -> Self {
+        Self {
+            health_per_difficulty: 0.2,
+            damage_per_difficulty: 0.15,
+            speed_per_difficulty: 0.1,
+            elite_chance_per_difficulty: 0.3,
+            special_event_chance: 0.3,
+        }
+    }
+}
+
+/// Base offer weight of each upgrade rarity
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RarityWeights {
+    pub common: f32,
+    pub rare: f32,
+    pub epic: f32,
+    pub legendary: f32,
+}
+
+impl RarityWeights {
+    /// Weight for `rarity`, or `None` for rarities that are never offered
+    pub fn weight(&self, rarity: Rarity) -> Option<f32> {
+        match rarity {
+            Rarity::Common => Some(self.common),
+            Rarity::Rare => Some(self.rare),
+            Rarity::Epic => Some(self.epic),
+            Rarity::Legendary => Some(self.legendary),
+            Rarity::Unknown => None,
+        }
+    }
+}
+
+impl Default for RarityWeights {
+    fn default() -> Self {
+        Self {
+            common: 100.0,
+            rare: 25.0,
+            epic: 5.0,
+            legendary: 1.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_match_built_in_balance() {
+        let balance = BalanceConfig::default();
+        assert_eq!(balance.difficulty.base, 0.1);
+        assert_eq!(balance.difficulty.per_zone, 0.15);
+        assert_eq!(balance.waves.health_multiplier(1.0), 1.0 + 0.2);
+        assert_eq!(balance.waves.damage_multiplier(1.0), 1.0 + 0.15);
+        assert_eq!(balance.waves.speed_multiplier(1.0), 1.0 + 0.1);
+        assert_eq!(balance.waves.elite_chance(0.5), 0.5 * 0.3);
+        assert_eq!(balance.waves.event_chance(), 0.3);
+        assert_eq!(balance.rarity_weights.weight(Rarity::Common), Some(100.0));
+        assert_eq!(balance.rarity_weights.weight(Rarity::Legendary), Some(1.0));
+        assert_eq!(balance.rarity_weights.weight(Rarity::Unknown), None);
+    }
+
+    #[test]
+    fn test_partial_json_keeps_defaults() {
+        let balance =
+            BalanceConfig::from_json(r#"{"waves": {"health_per_difficulty": 0.5}}"#).unwrap();
+        assert_eq!(balance.waves.health_per_difficulty, 0.5);
+        assert_eq!(balance.waves.damage_per_difficulty, 0.15);
+        assert_eq!(balance.difficulty, DifficultyBalance::default());
+
+        assert_eq!(
+            BalanceConfig::from_json("{}").unwrap(),
+            BalanceConfig::default()
+        );
+        let json = balance.to_json().unwrap();
+        assert_eq!(BalanceConfig::from_json(&json).unwrap(), balance);
+    }
+}
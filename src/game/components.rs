@@ -1,26 +1,39 @@
+use crate::engine::atlas::TextureRegion;
 use crate::engine::webgl::TextureHandle;
 use crate::game::entities::AircraftType;
 use crate::utils::math::{Color, Vec2, AABB};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ops::{Add, AddAssign, Mul, Sub};
 
 /// Sprite component for rendering
 #[derive(Debug, Clone)]
 pub struct Sprite {
-    pub texture: TextureHandle,
+    pub region: TextureRegion,
     pub rotation: f32,
     pub scale: Vec2,
     pub color: Color,
 }
 
 impl Sprite {
+    /// Sprite drawing the whole texture
     pub fn new(texture: TextureHandle) -> Self {
+        Self::from_region(TextureRegion::full(texture))
+    }
+
+    /// Sprite drawing a sub-region of a texture, typically an atlas entry
+    pub fn from_region(region: TextureRegion) -> Self {
         Self {
-            texture,
+            region,
             rotation: 0.0,
             scale: Vec2::new(1.0, 1.0),
             color: Color::white(),
         }
     }
+
+    pub fn texture(&self) -> TextureHandle {
+        self.region.handle
+    }
 }
 
 /// Position component
@@ -42,6 +55,49 @@ impl Position {
     pub fn from_vec2(v: Vec2) -> Self {
         Self { x: v.x, y: v.y }
     }
+
+    pub fn translated(&self, offset: Vec2) -> Self {
+        Self::new(self.x + offset.x, self.y + offset.y)
+    }
+}
+
+impl Add<Vec2> for Position {
+    type Output = Position;
+
+    fn add(self, offset: Vec2) -> Position {
+        self.translated(offset)
+    }
+}
+
+impl AddAssign<Vec2> for Position {
+    fn add_assign(&mut self, offset: Vec2) {
+        *self = self.translated(offset);
+    }
+}
+
+impl Sub<Vec2> for Position {
+    type Output = Position;
+
+    fn sub(self, offset: Vec2) -> Position {
+        self.translated(-offset)
+    }
+}
+
+/// The offset from `other` to `self`
+impl Sub for Position {
+    type Output = Vec2;
+
+    fn sub(self, other: Position) -> Vec2 {
+        self.as_vec2() - other.as_vec2()
+    }
+}
+
+impl Mul<f32> for Position {
+    type Output = Position;
+
+    fn mul(self, scale: f32) -> Position {
+        Self::new(self.x * scale, self.y * scale)
+    }
 }
 
 /// Velocity component
@@ -65,12 +121,56 @@ impl Velocity {
     }
 }
 
+impl Add for Velocity {
+    type Output = Velocity;
+
+    fn add(self, other: Velocity) -> Velocity {
+        Self::new(self.dx + other.dx, self.dy + other.dy)
+    }
+}
+
+impl AddAssign for Velocity {
+    fn add_assign(&mut self, other: Velocity) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Velocity {
+    type Output = Velocity;
+
+    fn sub(self, other: Velocity) -> Velocity {
+        Self::new(self.dx - other.dx, self.dy - other.dy)
+    }
+}
+
+impl Mul<f32> for Velocity {
+    type Output = Velocity;
+
+    fn mul(self, scale: f32) -> Velocity {
+        Self::new(self.dx * scale, self.dy * scale)
+    }
+}
+
+/// Kind of damage, each resisted separately on top of base armor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum DamageType {
+    #[default]
+    Kinetic,
+    Explosive,
+    Energy,
+    Fire,
+}
+
 /// Health component
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Health {
     pub current: i32,
     pub max: i32,
     pub armor: f32,
+    /// Fraction of each damage type ignored before armor; negative values
+    /// are weaknesses. Missing types have no resistance.
+    #[serde(default)]
+    pub resistances: HashMap<DamageType, f32>,
 }
 
 impl Health {
@@ -79,6 +179,7 @@ impl Health {
             current: max,
             max,
             armor: 0.0,
+            resistances: HashMap::new(),
         }
     }
 
@@ -87,11 +188,23 @@ impl Health {
             current: max,
             max,
             armor,
+            resistances: HashMap::new(),
         }
     }
 
-    pub fn take_damage(&mut self, damage: f32) {
-        let actual_damage = (damage * (1.0 - self.armor)) as i32;
+    pub fn with_resistance(mut self, damage_type: DamageType, resistance: f32) -> Self {
+        self.resistances.insert(damage_type, resistance);
+        self
+    }
+
+    pub fn resistance(&self, damage_type: DamageType) -> f32 {
+        self.resistances.get(&damage_type).copied().unwrap_or(0.0)
+    }
+
+    /// Applies the matching resistance, then base armor
+    pub fn take_damage(&mut self, damage: f32, damage_type: DamageType) {
+        let resisted = damage * (1.0 - self.resistance(damage_type).min(1.0));
+        let actual_damage = (resisted * (1.0 - self.armor)) as i32;
         self.current = (self.current - actual_damage).max(0);
     }
 
@@ -102,6 +215,14 @@ impl Health {
     pub fn is_alive(&self) -> bool {
         self.current > 0
     }
+
+    /// Remaining health in [0, 1]; zero when max health is not positive
+    pub fn fraction(&self) -> f32 {
+        if self.max <= 0 {
+            return 0.0;
+        }
+        (self.current as f32 / self.max as f32).clamp(0.0, 1.0)
+    }
 }
 
 /// Collider component
@@ -133,6 +254,104 @@ impl Collider {
             }
         }
     }
+
+    /// This collider resized by `scale`, typically the entity's
+    /// `Sprite::scale`, so the hitbox grows with what is drawn. Circles
+    /// take the larger axis so they still cover the sprite.
+    pub fn scaled(&self, scale: Vec2) -> Self {
+        let (sx, sy) = (scale.x.abs(), scale.y.abs());
+        match *self {
+            Collider::Circle { radius } => Collider::circle(radius * sx.max(sy)),
+            Collider::AABB { width, height } => Collider::aabb(width * sx, height * sy),
+        }
+    }
+
+    /// Bounds of the collider after scaling by `scale`
+    pub fn get_scaled_aabb(&self, position: &Position, scale: Vec2) -> AABB {
+        self.scaled(scale).get_aabb(position)
+    }
+}
+
+/// Physical body used when separating overlapping entities
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RigidBody {
+    /// Zero means immovable (infinite mass)
+    pub inverse_mass: f32,
+    /// Non-solid bodies (projectiles, triggers) are never pushed apart
+    pub solid: bool,
+}
+
+impl RigidBody {
+    pub fn dynamic(mass: f32) -> Self {
+        Self {
+            inverse_mass: if mass > 0.0 { 1.0 / mass } else { 0.0 },
+            solid: true,
+        }
+    }
+
+    pub fn fixed() -> Self {
+        Self {
+            inverse_mass: 0.0,
+            solid: true,
+        }
+    }
+
+    pub fn trigger() -> Self {
+        Self {
+            inverse_mass: 0.0,
+            solid: false,
+        }
+    }
+}
+
+/// Bitset of generic entity markers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct EntityFlags(u32);
+
+impl EntityFlags {
+    pub const NONE: EntityFlags = EntityFlags(0);
+    pub const PLAYER: EntityFlags = EntityFlags(1 << 0);
+    pub const ENEMY: EntityFlags = EntityFlags(1 << 1);
+    pub const BOSS: EntityFlags = EntityFlags(1 << 2);
+    pub const ELITE: EntityFlags = EntityFlags(1 << 3);
+    pub const INVULNERABLE: EntityFlags = EntityFlags(1 << 4);
+    pub const HAZARD: EntityFlags = EntityFlags(1 << 5);
+    /// Pickups, which only ever collide with the player
+    pub const COLLECTIBLE: EntityFlags = EntityFlags(1 << 6);
+
+    pub fn new() -> Self {
+        Self::NONE
+    }
+
+    pub fn bits(&self) -> u32 {
+        self.0
+    }
+
+    /// Returns true if every flag in `flags` is set
+    pub fn has(&self, flags: EntityFlags) -> bool {
+        self.0 & flags.0 == flags.0
+    }
+
+    pub fn set(&mut self, flags: EntityFlags) {
+        self.0 |= flags.0;
+    }
+
+    pub fn clear(&mut self, flags: EntityFlags) {
+        self.0 &= !flags.0;
+    }
+
+    pub fn with(mut self, flags: EntityFlags) -> Self {
+        self.set(flags);
+        self
+    }
+}
+
+impl std::ops::BitOr for EntityFlags {
+    type Output = EntityFlags;
+
+    fn bitor(self, rhs: EntityFlags) -> EntityFlags {
+        EntityFlags(self.0 | rhs.0)
+    }
 }
 
 /// Aircraft component
@@ -156,15 +375,16 @@ impl Aircraft {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::math::{health_bar_color, Gradient};
 
     #[test]
     fn test_health_damage() {
         let mut health = Health::new(100);
-        health.take_damage(30.0);
+        health.take_damage(30.0, DamageType::Kinetic);
         assert_eq!(health.current, 70);
         assert!(health.is_alive());
 
-        health.take_damage(100.0);
+        health.take_damage(100.0, DamageType::Kinetic);
         assert_eq!(health.current, 0);
         assert!(!health.is_alive());
     }
@@ -172,18 +392,117 @@ mod tests {
     #[test]
     fn test_health_armor() {
         let mut health = Health::with_armor(100, 0.5);
-        health.take_damage(40.0);
+        health.take_damage(40.0, DamageType::Kinetic);
+        assert_eq!(health.current, 80);
+    }
+
+    #[test]
+    fn test_health_resistances() {
+        let mut health = Health::new(100).with_resistance(DamageType::Fire, 0.5);
+        health.take_damage(40.0, DamageType::Fire);
         assert_eq!(health.current, 80);
+        health.take_damage(40.0, DamageType::Kinetic);
+        assert_eq!(health.current, 40);
+
+        // Resistance stacks with base armor
+        let mut armored = Health::with_armor(100, 0.5).with_resistance(DamageType::Fire, 0.5);
+        armored.take_damage(40.0, DamageType::Fire);
+        assert_eq!(armored.current, 90);
+        armored.take_damage(40.0, DamageType::Kinetic);
+        assert_eq!(armored.current, 70);
+
+        // Negative resistance is a weakness
+        let mut weak = Health::new(100).with_resistance(DamageType::Energy, -0.5);
+        weak.take_damage(40.0, DamageType::Energy);
+        assert_eq!(weak.current, 40);
     }
 
     #[test]
     fn test_health_heal() {
         let mut health = Health::new(100);
-        health.take_damage(50.0);
+        health.take_damage(50.0, DamageType::Kinetic);
         health.heal(30);
         assert_eq!(health.current, 80);
 
         health.heal(50);
         assert_eq!(health.current, 100);
     }
+
+    #[test]
+    fn test_health_fraction() {
+        let mut health = Health::new(80);
+        assert_eq!(health.fraction(), 1.0);
+
+        health.current = 40;
+        assert_eq!(health.fraction(), 0.5);
+
+        let empty = Health::new(0);
+        assert_eq!(empty.fraction(), 0.0);
+        let red = Color::rgb(1.0, 0.0, 0.0);
+        let gradient = Gradient::new(vec![(0.0, red), (1.0, Color::rgb(0.0, 1.0, 0.0))]);
+        assert_eq!(health_bar_color(empty.fraction(), &gradient), red);
+    }
+
+    #[test]
+    fn test_entity_flags() {
+        let mut flags = EntityFlags::new();
+        assert!(!flags.has(EntityFlags::BOSS));
+
+        flags.set(EntityFlags::BOSS | EntityFlags::INVULNERABLE);
+        flags.set(EntityFlags::ENEMY);
+        assert!(flags.has(EntityFlags::BOSS));
+        assert!(flags.has(EntityFlags::INVULNERABLE));
+        assert!(flags.has(EntityFlags::ENEMY | EntityFlags::BOSS));
+        assert!(!flags.has(EntityFlags::PLAYER));
+
+        flags.clear(EntityFlags::INVULNERABLE);
+        assert!(!flags.has(EntityFlags::INVULNERABLE));
+        assert!(flags.has(EntityFlags::BOSS));
+        assert!(flags.has(EntityFlags::ENEMY));
+        assert!(!flags.has(EntityFlags::BOSS | EntityFlags::INVULNERABLE));
+    }
+
+    #[test]
+    fn test_collider_scales_with_sprite() {
+        let position = Position::new(10.0, 20.0);
+        let double = Vec2::new(2.0, 2.0);
+
+        let circle = Collider::circle(8.0);
+        assert_eq!(circle.scaled(double), Collider::circle(16.0));
+        let bounds = circle.get_scaled_aabb(&position, double);
+        assert_eq!(bounds.max - bounds.min, Vec2::new(32.0, 32.0));
+
+        let boxed = Collider::aabb(12.0, 6.0);
+        assert_eq!(boxed.scaled(double), Collider::aabb(24.0, 12.0));
+        let bounds = boxed.get_scaled_aabb(&position, double);
+        assert_eq!(bounds.max - bounds.min, Vec2::new(24.0, 12.0));
+        assert_eq!((bounds.min + bounds.max) * 0.5, position.as_vec2());
+
+        // Unit scale leaves the collider alone
+        assert_eq!(boxed.scaled(Vec2::new(1.0, 1.0)), boxed);
+    }
+
+    #[test]
+    fn test_position_velocity_arithmetic() {
+        let position = Position::new(10.0, -5.0);
+        let velocity = Velocity::new(3.0, 4.0);
+        let delta = 0.25;
+
+        let expected = Position::from_vec2(position.as_vec2() + velocity.as_vec2() * delta);
+        assert_eq!(position + velocity.as_vec2() * delta, expected);
+        assert_eq!(position + (velocity * delta).as_vec2(), expected);
+        assert_eq!(position.translated(velocity.as_vec2() * delta), expected);
+
+        let mut moved = position;
+        moved += velocity.as_vec2() * delta;
+        assert_eq!(moved, expected);
+        assert_eq!(moved - velocity.as_vec2() * delta, position);
+        assert_eq!(moved - position, velocity.as_vec2() * delta);
+        assert_eq!(position * 2.0, Position::new(20.0, -10.0));
+
+        let mut boosted = velocity;
+        boosted += Velocity::new(1.0, 1.0);
+        assert_eq!(boosted, Velocity::new(4.0, 5.0));
+        assert_eq!(boosted - velocity, Velocity::new(1.0, 1.0));
+    }
 }
@@ -0,0 +1,136 @@
+//! Deferred entity removal, so systems can kill entities mid-iteration
+
+use crate::game::entities::Entity;
+use crate::game::systems::ai::AISystem;
+use crate::game::systems::collision::CollisionSystem;
+use crate::game::world::World;
+
+/// Entities flagged for removal during a frame. Nothing is removed until
+/// `flush` runs at a safe point after all systems have updated.
+#[derive(Debug, Clone, Default)]
+pub struct DespawnQueue {
+    pending: Vec<Entity>,
+}
+
+impl DespawnQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Flags an entity for removal; queuing it twice is harmless
+    pub fn push(&mut self, entity: Entity) {
+        if !self.pending.contains(&entity) {
+            self.pending.push(entity);
+        }
+    }
+
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.pending.contains(&entity)
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Removes every queued entity from the world and from each system's
+    /// maps, then empties the queue. Returns how many were still alive.
+    pub fn flush(
+        &mut self,
+        world: &mut World,
+        ai: &mut AISystem,
+        collision: &mut CollisionSystem,
+    ) -> usize {
+        let mut despawned = 0;
+        for entity in self.pending.drain(..) {
+            ai.unregister_enemy(entity);
+            collision.remove(entity);
+            if world.despawn(entity) {
+                despawned += 1;
+            }
+        }
+        despawned
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::components::{Collider, Health, Position};
+    use crate::game::entities::EnemyType;
+    use crate::utils::Vec2;
+
+    fn spawn_enemy(
+        world: &mut World,
+        ai: &mut AISystem,
+        collision: &mut CollisionSystem,
+        x: f32,
+        health: i32,
+    ) -> Entity {
+        let entity = world.spawn();
+        let position = Position::new(x, 0.0);
+        let collider = Collider::circle(5.0);
+        world.insert(entity, position);
+        world.insert(entity, collider);
+        world.insert(entity, Health::new(health));
+        ai.register_enemy(entity, EnemyType::Fighter);
+        collision.insert(entity, &position, &collider);
+        entity
+    }
+
+    #[test]
+    fn test_flush_removes_queued_entities_everywhere() {
+        let mut world = World::new();
+        let mut ai = AISystem::with_seed(1);
+        let mut collision = CollisionSystem::default();
+        let entities: Vec<Entity> = (0..4)
+            .map(|i| spawn_enemy(&mut world, &mut ai, &mut collision, i as f32 * 50.0, i))
+            .collect();
+
+        // Queue the dead while iterating; nothing is removed yet
+        let mut queue = DespawnQueue::new();
+        for (entity, health) in world.storage::<Health>().iter() {
+            if !health.is_alive() {
+                queue.push(entity);
+            }
+        }
+        queue.push(entities[0]);
+        assert_eq!(queue.len(), 1);
+        assert!(world.is_alive(entities[0]));
+
+        assert_eq!(queue.flush(&mut world, &mut ai, &mut collision), 1);
+        assert!(queue.is_empty());
+
+        assert!(!world.is_alive(entities[0]));
+        assert!(!ai.is_registered(entities[0]));
+        assert!(!collision.contains(entities[0]));
+        assert!(collision
+            .query_radius_sorted(Vec2::new(0.0, 0.0), 10.0)
+            .is_empty());
+
+        for &survivor in &entities[1..] {
+            assert!(world.is_alive(survivor));
+            assert!(ai.is_registered(survivor));
+            assert!(collision.contains(survivor));
+        }
+    }
+
+    #[test]
+    fn test_flush_ignores_already_despawned() {
+        let mut world = World::new();
+        let mut ai = AISystem::with_seed(1);
+        let mut collision = CollisionSystem::default();
+        let entity = spawn_enemy(&mut world, &mut ai, &mut collision, 0.0, 10);
+
+        let mut queue = DespawnQueue::new();
+        queue.push(entity);
+        world.despawn(entity);
+
+        assert_eq!(queue.flush(&mut world, &mut ai, &mut collision), 0);
+        assert!(!ai.is_registered(entity));
+        assert!(!collision.contains(entity));
+    }
+}
@@ -23,6 +23,9 @@ pub enum AircraftType {
     Corsair,
     Thunderbolt,
     Lightning,
+    /// Aircraft from a newer save that this build doesn't know about
+    #[serde(other)]
+    Unknown,
 }
 
 /// Enemy types
@@ -33,6 +36,9 @@ pub enum EnemyType {
     Ace,
     Kamikaze,
     HeavyBomber,
+    /// Enemy from newer data; treated like a `Fighter`
+    #[serde(other)]
+    Unknown,
 }
 
 /// Projectile owner (player or enemy)
@@ -0,0 +1,63 @@
+//! Gameplay events shared by systems that react to combat and progression
+
+use crate::game::entities::EnemyType;
+use crate::game::systems::procedural::HazardType;
+use crate::utils::Vec2;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum GameEvent {
+    EnemyKilled {
+        enemy_type: EnemyType,
+        is_elite: bool,
+        is_boss: bool,
+    },
+    DamageTaken {
+        amount: i32,
+    },
+    ZoneCleared {
+        zone_number: u32,
+    },
+    /// A new run began; per-run tallies start over
+    RunStarted,
+    /// Time played since the previous `TimeElapsed`
+    TimeElapsed {
+        seconds: f32,
+    },
+    /// Floating damage text to show over a hit
+    DamageNumber(DamageNumberEvent),
+    /// A telegraphed spawn's warning ran out; spawn it now
+    SpawnNow(Telegraph),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TelegraphKind {
+    Enemy(EnemyType),
+    Hazard(HazardType),
+}
+
+/// A pending spawn and how long to warn about it
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Telegraph {
+    pub position: Vec2,
+    pub duration: f32,
+    pub kind: TelegraphKind,
+}
+
+impl Telegraph {
+    pub fn new(position: Vec2, duration: f32, kind: TelegraphKind) -> Self {
+        Self {
+            position,
+            duration,
+            kind,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DamageNumberEvent {
+    pub position: Vec2,
+    /// Damage before the target's resistances and armor
+    pub amount: f32,
+    pub crit: bool,
+}
@@ -0,0 +1,377 @@
+//! Device-independent input state folded from raw browser events
+
+use crate::game::entities::Entity;
+use crate::utils::Vec2;
+use cgmath::InnerSpace;
+use serde::{Deserialize, Serialize};
+
+/// Logical buttons the game reacts to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Button {
+    Fire,
+    Dash,
+}
+
+/// Raw input already translated from keyboard, mouse, gamepad or touch
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum InputEvent {
+    /// Analog movement; longer than unit length is clamped
+    Move(Vec2),
+    /// Aim position in world space
+    Aim(Vec2),
+    ButtonDown(Button),
+    ButtonUp(Button),
+    TouchStart {
+        id: u32,
+        position: Vec2,
+    },
+    TouchMove {
+        id: u32,
+        position: Vec2,
+    },
+    TouchEnd {
+        id: u32,
+    },
+}
+
+/// Held state of a button plus the edges seen this frame
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ButtonState {
+    pub held: bool,
+    pub pressed: bool,
+    pub released: bool,
+}
+
+impl ButtonState {
+    fn press(&mut self) {
+        if !self.held {
+            self.pressed = true;
+        }
+        self.held = true;
+    }
+
+    fn release(&mut self) {
+        if self.held {
+            self.released = true;
+        }
+        self.held = false;
+    }
+
+    fn clear_edges(&mut self) {
+        self.pressed = false;
+        self.released = false;
+    }
+}
+
+/// Per-frame input snapshot consumed by the simulation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InputState {
+    pub move_axis: Vec2,
+    pub aim: Vec2,
+    pub fire: ButtonState,
+    pub dash: ButtonState,
+    /// Active touches in the order they started
+    touches: Vec<(u32, Vec2)>,
+}
+
+impl InputState {
+    pub fn new() -> Self {
+        Self {
+            move_axis: Vec2::new(0.0, 0.0),
+            aim: Vec2::new(0.0, 0.0),
+            fire: ButtonState::default(),
+            dash: ButtonState::default(),
+            touches: Vec::new(),
+        }
+    }
+
+    /// Clears last frame's edges and applies this frame's events in order
+    pub fn update(&mut self, events: &[InputEvent]) {
+        self.clear_edges();
+        for event in events {
+            self.apply(event);
+        }
+    }
+
+    pub fn apply(&mut self, event: &InputEvent) {
+        match *event {
+            InputEvent::Move(axis) => {
+                self.move_axis = if axis.magnitude2() > 1.0 {
+                    axis.normalize()
+                } else {
+                    axis
+                };
+            }
+            InputEvent::Aim(position) => self.aim = position,
+            InputEvent::ButtonDown(button) => self.button_mut(button).press(),
+            InputEvent::ButtonUp(button) => self.button_mut(button).release(),
+            InputEvent::TouchStart { id, position } | InputEvent::TouchMove { id, position } => {
+                match self.touches.iter_mut().find(|(touch, _)| *touch == id) {
+                    Some(touch) => touch.1 = position,
+                    None => self.touches.push((id, position)),
+                }
+            }
+            InputEvent::TouchEnd { id } => self.touches.retain(|(touch, _)| *touch != id),
+        }
+    }
+
+    pub fn clear_edges(&mut self) {
+        self.fire.clear_edges();
+        self.dash.clear_edges();
+    }
+
+    pub fn button(&self, button: Button) -> ButtonState {
+        match button {
+            Button::Fire => self.fire,
+            Button::Dash => self.dash,
+        }
+    }
+
+    fn button_mut(&mut self, button: Button) -> &mut ButtonState {
+        match button {
+            Button::Fire => &mut self.fire,
+            Button::Dash => &mut self.dash,
+        }
+    }
+
+    pub fn touches(&self) -> &[(u32, Vec2)] {
+        &self.touches
+    }
+}
+
+impl Default for InputState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Radial deadzone: inputs inside `deadzone` map to zero and the rest is
+/// rescaled so output magnitude starts from zero at the deadzone edge
+pub fn apply_deadzone(axis: Vec2, deadzone: f32) -> Vec2 {
+    let deadzone = deadzone.max(0.0);
+    let magnitude = axis.magnitude();
+    if magnitude <= deadzone || magnitude == 0.0 || deadzone >= 1.0 {
+        return Vec2::new(0.0, 0.0);
+    }
+
+    let scaled = ((magnitude - deadzone) / (1.0 - deadzone)).min(1.0);
+    axis / magnitude * scaled
+}
+
+/// Exponential smoothing for aim input
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AimFilter {
+    /// Fraction of the remaining distance covered per update, in (0, 1]
+    pub factor: f32,
+    value: Option<Vec2>,
+}
+
+impl AimFilter {
+    pub fn new(factor: f32) -> Self {
+        Self {
+            factor: factor.clamp(f32::EPSILON, 1.0),
+            value: None,
+        }
+    }
+
+    /// Moves the smoothed aim toward `target`. The first sample is taken as is.
+    pub fn update(&mut self, target: Vec2) -> Vec2 {
+        let value = match self.value {
+            Some(value) => value + (target - value) * self.factor,
+            None => target,
+        };
+        self.value = Some(value);
+        value
+    }
+
+    pub fn value(&self) -> Option<Vec2> {
+        self.value
+    }
+
+    pub fn reset(&mut self) {
+        self.value = None;
+    }
+}
+
+/// Aim assist: turns `raw_aim` (a direction from the player) onto the
+/// nearest candidate within `max_dist` and `cone_half_angle` radians of it,
+/// keeping its length. Returns `raw_aim` unchanged when nothing qualifies.
+pub fn aim_assist(
+    player_pos: Vec2,
+    raw_aim: Vec2,
+    candidates: &[(Entity, Vec2)],
+    cone_half_angle: f32,
+    max_dist: f32,
+) -> Vec2 {
+    let aim_length = raw_aim.magnitude();
+    if aim_length == 0.0 {
+        return raw_aim;
+    }
+    let aim_direction = raw_aim / aim_length;
+    let min_cos = cone_half_angle.cos();
+
+    let nearest = candidates
+        .iter()
+        .map(|(_, position)| position - player_pos)
+        .filter(|offset| {
+            let distance = offset.magnitude();
+            distance > 0.0
+                && distance <= max_dist
+                && offset.dot(aim_direction) / distance >= min_cos
+        })
+        .min_by(|a, b| a.magnitude2().total_cmp(&b.magnitude2()));
+
+    match nearest {
+        Some(offset) => offset.normalize() * aim_length,
+        None => raw_aim,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_press_and_release_edges_fire_once() {
+        let mut input = InputState::new();
+
+        input.update(&[InputEvent::ButtonDown(Button::Fire)]);
+        assert!(input.fire.pressed && input.fire.held);
+
+        // Held across frames, with repeated key-down events ignored
+        input.update(&[InputEvent::ButtonDown(Button::Fire)]);
+        assert!(!input.fire.pressed && input.fire.held);
+        input.update(&[]);
+        assert!(!input.fire.pressed && input.fire.held);
+
+        input.update(&[InputEvent::ButtonUp(Button::Fire)]);
+        assert!(input.fire.released && !input.fire.held);
+
+        input.update(&[InputEvent::ButtonUp(Button::Fire)]);
+        assert!(!input.fire.released);
+        assert_eq!(input.dash, ButtonState::default());
+    }
+
+    #[test]
+    fn test_tap_within_one_frame() {
+        let mut input = InputState::new();
+        input.update(&[
+            InputEvent::ButtonDown(Button::Dash),
+            InputEvent::ButtonUp(Button::Dash),
+        ]);
+
+        let dash = input.button(Button::Dash);
+        assert!(dash.pressed && dash.released && !dash.held);
+    }
+
+    #[test]
+    fn test_move_axis_is_clamped() {
+        let mut input = InputState::new();
+        input.update(&[InputEvent::Move(Vec2::new(3.0, 4.0))]);
+        assert!((input.move_axis.magnitude() - 1.0).abs() < 1e-5);
+
+        input.update(&[InputEvent::Move(Vec2::new(0.3, 0.0))]);
+        assert_eq!(input.move_axis, Vec2::new(0.3, 0.0));
+    }
+
+    #[test]
+    fn test_multi_touch_tracking() {
+        let mut input = InputState::new();
+        input.update(&[
+            InputEvent::TouchStart {
+                id: 4,
+                position: Vec2::new(10.0, 10.0),
+            },
+            InputEvent::TouchStart {
+                id: 9,
+                position: Vec2::new(200.0, 50.0),
+            },
+            InputEvent::TouchMove {
+                id: 4,
+                position: Vec2::new(12.0, 8.0),
+            },
+        ]);
+        assert_eq!(
+            input.touches(),
+            &[(4, Vec2::new(12.0, 8.0)), (9, Vec2::new(200.0, 50.0))]
+        );
+
+        input.update(&[InputEvent::TouchEnd { id: 4 }]);
+        assert_eq!(input.touches(), &[(9, Vec2::new(200.0, 50.0))]);
+    }
+
+    #[test]
+    fn test_deadzone_is_radial_and_rescaled() {
+        assert_eq!(
+            apply_deadzone(Vec2::new(0.1, 0.1), 0.2),
+            Vec2::new(0.0, 0.0)
+        );
+        assert_eq!(
+            apply_deadzone(Vec2::new(0.0, 0.0), 0.2),
+            Vec2::new(0.0, 0.0)
+        );
+
+        // Just outside the deadzone starts near zero, not at the deadzone size
+        let just_outside = apply_deadzone(Vec2::new(0.21, 0.0), 0.2);
+        assert!(just_outside.x > 0.0 && just_outside.x < 0.02);
+
+        // Diagonals use the same radius as the axes
+        let diagonal = apply_deadzone(Vec2::new(0.15, 0.15), 0.2);
+        assert!(diagonal.magnitude() > 0.0);
+
+        let full = apply_deadzone(Vec2::new(0.0, -1.0), 0.2);
+        assert!((full.y + 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_aim_filter_converges() {
+        let mut filter = AimFilter::new(0.5);
+        let target = Vec2::new(100.0, 0.0);
+        assert_eq!(filter.update(Vec2::new(0.0, 0.0)), Vec2::new(0.0, 0.0));
+
+        let mut last_error = f32::MAX;
+        for _ in 0..20 {
+            let error = (target - filter.update(target)).magnitude();
+            assert!(error < last_error);
+            last_error = error;
+        }
+        assert!(last_error < 0.01);
+    }
+
+    const CONE: f32 = std::f32::consts::FRAC_PI_8;
+
+    #[test]
+    fn test_aim_assist_turns_toward_target_in_cone() {
+        let player = Vec2::new(10.0, 10.0);
+        let target = (Entity::new(1), Vec2::new(30.0, 110.0));
+        let aim = aim_assist(player, Vec2::new(0.0, 2.0), &[target], CONE, 300.0);
+
+        assert!((aim.magnitude() - 2.0).abs() < 1e-5);
+        assert!((aim.normalize() - Vec2::new(20.0, 100.0).normalize()).magnitude() < 1e-5);
+    }
+
+    #[test]
+    fn test_aim_assist_ignores_targets_outside_cone_or_range() {
+        let player = Vec2::new(0.0, 0.0);
+        let raw = Vec2::new(0.0, 1.0);
+        let candidates = [
+            (Entity::new(1), Vec2::new(100.0, 20.0)),
+            (Entity::new(2), Vec2::new(0.0, 500.0)),
+        ];
+
+        assert_eq!(aim_assist(player, raw, &candidates, CONE, 300.0), raw);
+        assert_eq!(aim_assist(player, raw, &[], CONE, 300.0), raw);
+    }
+
+    #[test]
+    fn test_aim_assist_picks_nearest() {
+        let player = Vec2::new(0.0, 0.0);
+        let candidates = [
+            (Entity::new(1), Vec2::new(20.0, 200.0)),
+            (Entity::new(2), Vec2::new(-10.0, 80.0)),
+            (Entity::new(3), Vec2::new(5.0, 150.0)),
+        ];
+        let aim = aim_assist(player, Vec2::new(0.0, 1.0), &candidates, CONE, 300.0);
+        assert!((aim - Vec2::new(-10.0, 80.0).normalize()).magnitude() < 1e-5);
+    }
+}
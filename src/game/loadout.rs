@@ -0,0 +1,287 @@
+//! Per-aircraft starting weapons and abilities, and the weapons enemies fire
+
+use crate::game::entities::{AircraftType, EnemyType};
+use crate::game::state::RunState;
+use crate::game::systems::ability::{AbilitySystem, DASH_ABILITY, SHIELD_ABILITY};
+use crate::game::systems::upgrade::AbilityId;
+use crate::game::systems::weapon::{
+    ProjectileType, SpreadPattern, WeaponDefinition, WeaponId, WeaponSystem,
+};
+
+pub const MACHINE_GUN: WeaponId = WeaponId(1);
+/// Also granted by the Twin Guns upgrade
+pub const TWIN_GUNS: WeaponId = WeaponId(2);
+pub const ROCKETS: WeaponId = WeaponId(3);
+pub const CANNON: WeaponId = WeaponId(4);
+
+pub const ENEMY_GUN: WeaponId = WeaponId(101);
+pub const ENEMY_BOMBS: WeaponId = WeaponId(102);
+pub const ENEMY_RAPID_GUN: WeaponId = WeaponId(103);
+pub const ENEMY_SPREAD_GUN: WeaponId = WeaponId(104);
+
+const ENEMY_WEAPONS: [WeaponId; 4] = [ENEMY_GUN, ENEMY_BOMBS, ENEMY_RAPID_GUN, ENEMY_SPREAD_GUN];
+
+/// Reserve ammo given to ammo-consuming starting weapons
+pub const STARTING_AMMO: u32 = 20;
+
+/// Weapons and abilities an aircraft starts a run with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Loadout {
+    pub weapons: Vec<WeaponId>,
+    pub abilities: Vec<AbilityId>,
+}
+
+impl Loadout {
+    /// Registers and stocks the loadout's weapons and unlocks its abilities.
+    /// Weapons already registered elsewhere, such as enemy guns, keep their
+    /// reserves.
+    pub fn apply(&self, weapons: &mut WeaponSystem, abilities: &mut AbilitySystem) {
+        for definition in self.weapons.iter().filter_map(|&id| weapon_definition(id)) {
+            weapons.register_weapon(definition);
+        }
+        for &weapon_id in &self.weapons {
+            weapons.refill_weapon_ammo(weapon_id, STARTING_AMMO);
+        }
+
+        for &ability in &self.abilities {
+            abilities.unlock(ability);
+        }
+    }
+}
+
+pub fn starting_loadout(aircraft: AircraftType) -> Loadout {
+    let (weapons, abilities) = match aircraft {
+        AircraftType::Spitfire | AircraftType::Unknown => (vec![MACHINE_GUN], vec![]),
+        AircraftType::Mustang => (vec![MACHINE_GUN], vec![DASH_ABILITY]),
+        AircraftType::Corsair => (vec![CANNON], vec![SHIELD_ABILITY]),
+        AircraftType::Thunderbolt => (vec![MACHINE_GUN, ROCKETS], vec![]),
+        AircraftType::Lightning => (vec![TWIN_GUNS], vec![]),
+    };
+
+    Loadout { weapons, abilities }
+}
+
+/// Weapon an enemy type fires. Kamikazes rarely get a shot off before
+/// ramming, so they share the basic gun with fighters.
+pub fn enemy_weapon(enemy_type: EnemyType) -> WeaponId {
+    match enemy_type {
+        EnemyType::Fighter | EnemyType::Kamikaze | EnemyType::Unknown => ENEMY_GUN,
+        EnemyType::Bomber => ENEMY_BOMBS,
+        EnemyType::Ace => ENEMY_RAPID_GUN,
+        EnemyType::HeavyBomber => ENEMY_SPREAD_GUN,
+    }
+}
+
+/// Registered weapon for an enemy type, falling back to the basic enemy
+/// gun when its own weapon isn't registered
+pub fn resolve_enemy_weapon(weapons: &WeaponSystem, enemy_type: EnemyType) -> Option<WeaponId> {
+    [enemy_weapon(enemy_type), ENEMY_GUN]
+        .into_iter()
+        .find(|&id| weapons.get_weapon(id).is_some())
+}
+
+pub fn register_enemy_weapons(weapons: &mut WeaponSystem) {
+    for definition in ENEMY_WEAPONS.into_iter().filter_map(weapon_definition) {
+        weapons.register_weapon(definition);
+    }
+}
+
+/// Definition of a built-in weapon, or `None` for an unknown id
+pub fn weapon_definition(id: WeaponId) -> Option<WeaponDefinition> {
+    let base = WeaponDefinition::builder(id).build();
+
+    let definition = match id {
+        MACHINE_GUN => WeaponDefinition {
+            name: "Machine Gun".to_string(),
+            ..base
+        },
+        TWIN_GUNS => WeaponDefinition {
+            name: "Twin Guns".to_string(),
+            base_damage: 8.0,
+            spread_pattern: SpreadPattern::Twin { spacing: 12.0 },
+            ..base
+        },
+        ROCKETS => WeaponDefinition {
+            name: "Rockets".to_string(),
+            base_damage: 40.0,
+            fire_rate: 1.0,
+            projectile_speed: 350.0,
+            projectile_type: ProjectileType::Rocket,
+            ammo_consumption: Some(1),
+            ..base
+        },
+        CANNON => WeaponDefinition {
+            name: "Cannon".to_string(),
+            base_damage: 30.0,
+            fire_rate: 2.5,
+            projectile_speed: 500.0,
+            ..base
+        },
+        ENEMY_GUN => WeaponDefinition {
+            name: "Enemy Gun".to_string(),
+            base_damage: 5.0,
+            fire_rate: 1.5,
+            projectile_speed: 300.0,
+            ..base
+        },
+        ENEMY_BOMBS => WeaponDefinition {
+            name: "Bomb Bay".to_string(),
+            base_damage: 25.0,
+            fire_rate: 0.5,
+            projectile_speed: 150.0,
+            projectile_type: ProjectileType::Bomb,
+            ..base
+        },
+        ENEMY_RAPID_GUN => WeaponDefinition {
+            name: "Ace Guns".to_string(),
+            base_damage: 6.0,
+            fire_rate: 4.0,
+            projectile_speed: 550.0,
+            ..base
+        },
+        ENEMY_SPREAD_GUN => WeaponDefinition {
+            name: "Defensive Turrets".to_string(),
+            base_damage: 5.0,
+            fire_rate: 1.0,
+            projectile_speed: 250.0,
+            spread_pattern: SpreadPattern::Spread {
+                count: 5,
+                angle: 60.0,
+            },
+            ..base
+        },
+        _ => return None,
+    };
+
+    Some(definition)
+}
+
+/// Creates the run state and equips the aircraft's starting loadout
+pub fn start_run(
+    seed: u64,
+    aircraft: AircraftType,
+    weapons: &mut WeaponSystem,
+    abilities: &mut AbilitySystem,
+) -> RunState {
+    starting_loadout(aircraft).apply(weapons, abilities);
+    RunState::new(seed, aircraft)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    const AIRCRAFT: [AircraftType; 5] = [
+        AircraftType::Spitfire,
+        AircraftType::Mustang,
+        AircraftType::Corsair,
+        AircraftType::Thunderbolt,
+        AircraftType::Lightning,
+    ];
+
+    #[test]
+    fn test_loadouts_are_distinct() {
+        for (i, a) in AIRCRAFT.iter().enumerate() {
+            for b in &AIRCRAFT[i + 1..] {
+                assert_ne!(
+                    starting_loadout(*a),
+                    starting_loadout(*b),
+                    "{:?} vs {:?}",
+                    a,
+                    b
+                );
+            }
+        }
+
+        assert!(starting_loadout(AircraftType::Thunderbolt)
+            .weapons
+            .contains(&ROCKETS));
+        assert_eq!(
+            starting_loadout(AircraftType::Lightning).weapons,
+            vec![TWIN_GUNS]
+        );
+    }
+
+    #[test]
+    fn test_every_starting_weapon_is_defined() {
+        for aircraft in AIRCRAFT {
+            for id in starting_loadout(aircraft).weapons {
+                assert_eq!(weapon_definition(id).map(|w| w.id), Some(id));
+            }
+        }
+        assert!(weapon_definition(WeaponId(999)).is_none());
+    }
+
+    #[test]
+    fn test_enemy_weapons_are_distinct() {
+        let shooters = [
+            EnemyType::Fighter,
+            EnemyType::Bomber,
+            EnemyType::Ace,
+            EnemyType::HeavyBomber,
+        ];
+        let ids: HashSet<WeaponId> = shooters.iter().map(|&t| enemy_weapon(t)).collect();
+        assert_eq!(ids.len(), shooters.len());
+
+        for id in ids {
+            assert_eq!(weapon_definition(id).map(|w| w.id), Some(id));
+        }
+        assert_eq!(
+            enemy_weapon(EnemyType::Unknown),
+            enemy_weapon(EnemyType::Fighter)
+        );
+        assert!(matches!(
+            weapon_definition(enemy_weapon(EnemyType::Bomber)).map(|w| w.projectile_type),
+            Some(ProjectileType::Bomb)
+        ));
+    }
+
+    #[test]
+    fn test_unregistered_enemy_weapon_falls_back() {
+        let mut weapons = WeaponSystem::new();
+        assert_eq!(resolve_enemy_weapon(&weapons, EnemyType::Ace), None);
+
+        weapons.register_weapon(weapon_definition(ENEMY_GUN).unwrap());
+        assert_eq!(
+            resolve_enemy_weapon(&weapons, EnemyType::Ace),
+            Some(ENEMY_GUN)
+        );
+
+        register_enemy_weapons(&mut weapons);
+        assert_eq!(
+            resolve_enemy_weapon(&weapons, EnemyType::Ace),
+            Some(ENEMY_RAPID_GUN)
+        );
+    }
+
+    #[test]
+    fn test_start_run_applies_loadout() {
+        let mut weapons = WeaponSystem::new();
+        let mut abilities = AbilitySystem::new();
+        let run = start_run(5, AircraftType::Thunderbolt, &mut weapons, &mut abilities);
+
+        assert_eq!(run.aircraft, AircraftType::Thunderbolt);
+        assert!(weapons.get_weapon(MACHINE_GUN).is_some());
+        assert!(weapons.get_weapon(ROCKETS).is_some());
+        assert!(weapons.get_weapon(TWIN_GUNS).is_none());
+        assert_eq!(weapons.ammo(ROCKETS), Some(STARTING_AMMO));
+        assert!(!abilities.is_unlocked(DASH_ABILITY));
+
+        let mut weapons = WeaponSystem::new();
+        let mut abilities = AbilitySystem::new();
+        start_run(5, AircraftType::Mustang, &mut weapons, &mut abilities);
+        assert!(abilities.is_unlocked(DASH_ABILITY));
+        assert!(!abilities.is_unlocked(SHIELD_ABILITY));
+    }
+
+    #[test]
+    fn test_loadout_only_stocks_its_own_weapons() {
+        let mut weapons = WeaponSystem::new();
+        let mut abilities = AbilitySystem::new();
+        weapons.register_weapon(weapon_definition(ROCKETS).unwrap());
+
+        start_run(5, AircraftType::Spitfire, &mut weapons, &mut abilities);
+        assert_eq!(weapons.ammo(ROCKETS), Some(0));
+    }
+}
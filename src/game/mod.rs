@@ -1,9 +1,27 @@
+pub mod balance;
 pub mod components;
+pub mod despawn;
 pub mod entities;
+pub mod events;
+pub mod input;
+pub mod loadout;
+pub mod scoring;
+pub mod shop;
+pub mod simulation;
 pub mod state;
 pub mod systems;
+pub mod world;
 
+pub use balance::*;
 pub use components::*;
+pub use despawn::*;
 pub use entities::*;
+pub use events::*;
+pub use input::*;
+pub use loadout::*;
+pub use scoring::*;
+pub use shop::*;
+pub use simulation::*;
 pub use state::*;
 pub use systems::*;
+pub use world::*;
@@ -0,0 +1,50 @@
+//! Score rules for kills, shared by the run state and statistics
+
+use crate::game::entities::EnemyType;
+
+/// Score multiplier for killing the elite picked out of a wave
+pub const ELITE_SCORE_MULTIPLIER: u32 = 3;
+/// Score multiplier for killing a zone boss
+pub const BOSS_SCORE_MULTIPLIER: u32 = 10;
+
+/// Base points awarded for killing an enemy of this type
+pub fn score_value(enemy_type: EnemyType) -> u32 {
+    match enemy_type {
+        EnemyType::Kamikaze => 75,
+        EnemyType::Fighter | EnemyType::Unknown => 100,
+        EnemyType::Bomber => 200,
+        EnemyType::Ace => 500,
+        EnemyType::HeavyBomber => 600,
+    }
+}
+
+/// Points for a kill, including the elite and boss multipliers
+pub fn kill_score(enemy_type: EnemyType, is_elite: bool, is_boss: bool) -> u32 {
+    let mut score = score_value(enemy_type);
+    if is_elite {
+        score *= ELITE_SCORE_MULTIPLIER;
+    }
+    if is_boss {
+        score *= BOSS_SCORE_MULTIPLIER;
+    }
+    score
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kill_score_multipliers() {
+        let base = score_value(EnemyType::Bomber);
+        assert_eq!(kill_score(EnemyType::Bomber, false, false), base);
+        assert_eq!(
+            kill_score(EnemyType::Bomber, true, false),
+            base * ELITE_SCORE_MULTIPLIER
+        );
+        assert_eq!(
+            kill_score(EnemyType::Bomber, false, true),
+            base * BOSS_SCORE_MULTIPLIER
+        );
+    }
+}
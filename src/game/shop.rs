@@ -0,0 +1,194 @@
+//! Between-runs shop spending meta-currency on permanent upgrades
+
+use crate::game::state::{MetaProgression, ShopItemId};
+use crate::game::systems::upgrade::{Effect, Modifier, Stat};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShopItem {
+    pub id: ShopItemId,
+    pub name: String,
+    pub cost: u32,
+    pub effect: Effect,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShopError {
+    UnknownItem(ShopItemId),
+    InsufficientFunds { cost: u32, available: u32 },
+    AlreadyPurchased(ShopItemId),
+}
+
+impl fmt::Display for ShopError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShopError::UnknownItem(id) => write!(f, "unknown shop item {}", id.0),
+            ShopError::InsufficientFunds { cost, available } => {
+                write!(f, "item costs {} but only {} available", cost, available)
+            }
+            ShopError::AlreadyPurchased(id) => write!(f, "shop item {} already purchased", id.0),
+        }
+    }
+}
+
+impl std::error::Error for ShopError {}
+
+pub struct MetaShop {
+    items: Vec<ShopItem>,
+}
+
+impl MetaShop {
+    pub fn new() -> Self {
+        Self::with_items(vec![
+            ShopItem {
+                id: ShopItemId(1),
+                name: "Reinforced Airframe".to_string(),
+                cost: 50,
+                effect: Effect::StatModifier {
+                    stat: Stat::MaxHealth,
+                    modifier: Modifier::Add(20.0),
+                },
+            },
+            ShopItem {
+                id: ShopItemId(2),
+                name: "Tuned Engine".to_string(),
+                cost: 75,
+                effect: Effect::StatModifier {
+                    stat: Stat::MoveSpeed,
+                    modifier: Modifier::Multiply(1.1),
+                },
+            },
+            ShopItem {
+                id: ShopItemId(3),
+                name: "Veteran Gunnery".to_string(),
+                cost: 120,
+                effect: Effect::StatModifier {
+                    stat: Stat::Damage,
+                    modifier: Modifier::Multiply(1.1),
+                },
+            },
+        ])
+    }
+
+    pub fn with_items(items: Vec<ShopItem>) -> Self {
+        Self { items }
+    }
+
+    pub fn items(&self) -> &[ShopItem] {
+        &self.items
+    }
+
+    pub fn get_item(&self, id: ShopItemId) -> Option<&ShopItem> {
+        self.items.iter().find(|item| item.id == id)
+    }
+
+    /// Spends currency on an item and records the permanent unlock
+    pub fn purchase(
+        &self,
+        id: ShopItemId,
+        progression: &mut MetaProgression,
+    ) -> Result<(), ShopError> {
+        let item = self.get_item(id).ok_or(ShopError::UnknownItem(id))?;
+
+        if progression.has_purchased(id) {
+            return Err(ShopError::AlreadyPurchased(id));
+        }
+        if progression.currency < item.cost {
+            return Err(ShopError::InsufficientFunds {
+                cost: item.cost,
+                available: progression.currency,
+            });
+        }
+
+        progression.currency -= item.cost;
+        progression.purchased_items.insert(id);
+        Ok(())
+    }
+
+    /// Effects of every item the player owns
+    pub fn owned_effects<'a>(
+        &'a self,
+        progression: &'a MetaProgression,
+    ) -> impl Iterator<Item = &'a Effect> + 'a {
+        self.items
+            .iter()
+            .filter(move |item| progression.has_purchased(item.id))
+            .map(|item| &item.effect)
+    }
+}
+
+impl Default for MetaShop {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::entities::AircraftType;
+    use crate::game::state::RunState;
+
+    fn funded_progression(score: u64) -> MetaProgression {
+        let mut progression = MetaProgression::new();
+        let mut run = RunState::new(1, AircraftType::Spitfire);
+        run.score = score;
+        progression.complete_run(&run);
+        progression
+    }
+
+    #[test]
+    fn test_run_score_earns_currency() {
+        let progression = funded_progression(12_345);
+        assert_eq!(progression.currency, 123);
+        assert_eq!(progression.total_score, 12_345);
+        assert_eq!(progression.total_runs, 1);
+    }
+
+    #[test]
+    fn test_purchase_deducts_currency() {
+        let shop = MetaShop::new();
+        let mut progression = funded_progression(10_000);
+
+        assert_eq!(shop.purchase(ShopItemId(1), &mut progression), Ok(()));
+        assert_eq!(progression.currency, 50);
+        assert!(progression.has_purchased(ShopItemId(1)));
+        assert_eq!(shop.owned_effects(&progression).count(), 1);
+    }
+
+    #[test]
+    fn test_purchase_rejects_insufficient_funds() {
+        let shop = MetaShop::new();
+        let mut progression = funded_progression(6_000);
+
+        assert_eq!(
+            shop.purchase(ShopItemId(3), &mut progression),
+            Err(ShopError::InsufficientFunds {
+                cost: 120,
+                available: 60
+            })
+        );
+        assert_eq!(progression.currency, 60);
+        assert!(!progression.has_purchased(ShopItemId(3)));
+    }
+
+    #[test]
+    fn test_purchase_rejects_duplicates_and_unknown_items() {
+        let shop = MetaShop::new();
+        let mut progression = funded_progression(100_000);
+
+        shop.purchase(ShopItemId(2), &mut progression).unwrap();
+        let remaining = progression.currency;
+        assert_eq!(
+            shop.purchase(ShopItemId(2), &mut progression),
+            Err(ShopError::AlreadyPurchased(ShopItemId(2)))
+        );
+        assert_eq!(progression.currency, remaining);
+
+        assert_eq!(
+            shop.purchase(ShopItemId(42), &mut progression),
+            Err(ShopError::UnknownItem(ShopItemId(42)))
+        );
+    }
+}
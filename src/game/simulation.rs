@@ -0,0 +1,1006 @@
+//! Fixed-timestep simulation facade owning and ordering system updates
+
+use crate::game::components::{Collider, EntityFlags, Health, Position, Sprite, Velocity};
+use crate::game::despawn::DespawnQueue;
+use crate::game::entities::{EnemyType, Entity, ProjectileOwner};
+use crate::game::events::{GameEvent, Telegraph, TelegraphKind};
+use crate::game::input::InputState;
+use crate::game::loadout::{register_enemy_weapons, resolve_enemy_weapon};
+use crate::game::state::RunState;
+use crate::game::systems::ability::{AbilitySystem, DASH_ABILITY};
+use crate::game::systems::ai::{AICommand, AISystem, AITarget, FormationStatus, TargetingPolicy};
+use crate::game::systems::collision::CollisionSystem;
+use crate::game::systems::procedural::{
+    spawn_enemy_stats, Hazard, Wave, Zone, ZoneEvent, ZoneRunner,
+};
+use crate::game::systems::telegraph::{
+    ActiveTelegraph, TelegraphSystem, DEFAULT_TELEGRAPH_DURATION,
+};
+use crate::game::systems::weapon::{Explosion, Muzzle, Projectile, WeaponSystem};
+use crate::game::world::World;
+use crate::utils::Vec2;
+use cgmath::InnerSpace;
+use std::collections::HashMap;
+
+/// Default fixed update rate (60 Hz)
+pub const DEFAULT_FIXED_DELTA: f32 = 1.0 / 60.0;
+
+/// Upper bound on fixed updates per `step` so a long frame can't stall the game
+const MAX_TICKS_PER_STEP: u32 = 8;
+
+/// Player movement speed at full stick deflection, in units per second
+pub const PLAYER_SPEED: f32 = 300.0;
+
+/// Most projectiles the player may have in flight at once
+pub const PLAYER_PROJECTILE_CAP: u32 = 256;
+/// Most projectiles each enemy may have in flight at once
+pub const ENEMY_PROJECTILE_CAP: u32 = 24;
+
+/// Collision radius of spawned enemies
+const ENEMY_COLLIDER_RADIUS: f32 = 16.0;
+
+/// The zone being played: its waves and the runner pacing their release
+struct ActiveZone {
+    waves: Vec<Wave>,
+    runner: ZoneRunner,
+}
+
+/// What a telegraph turns into once its warning runs out
+enum PendingSpawn {
+    /// Entry `index` of the active zone's wave `wave`
+    Enemy {
+        wave: usize,
+        index: usize,
+    },
+    Hazard(Hazard),
+}
+
+pub struct Simulation {
+    world: World,
+    ai: AISystem,
+    weapons: WeaponSystem,
+    abilities: AbilitySystem,
+    collision: CollisionSystem,
+    input: InputState,
+    despawns: DespawnQueue,
+    projectiles: Vec<Projectile>,
+    zone: Option<ActiveZone>,
+    telegraphs: TelegraphSystem,
+    /// Spawns waiting on their telegraph, in telegraph order
+    pending_spawns: Vec<(Telegraph, PendingSpawn)>,
+    /// Wave each spawned enemy flies with
+    formation_of: HashMap<Entity, usize>,
+    /// How much of each released wave is still flying, by wave index
+    formations: HashMap<usize, FormationStatus>,
+    /// Run whose phase gates spawning; without one, waves release freely
+    run: Option<RunState>,
+    /// Seconds until each enemy may fire again
+    enemy_cooldowns: HashMap<Entity, f32>,
+    fixed_delta: f32,
+    accumulator: f32,
+    time_scale: f32,
+    paused: bool,
+    tick: u64,
+    frame: u64,
+}
+
+impl Simulation {
+    pub fn new(seed: u64) -> Self {
+        Self::with_fixed_delta(seed, DEFAULT_FIXED_DELTA)
+    }
+
+    pub fn with_fixed_delta(seed: u64, fixed_delta: f32) -> Self {
+        let mut weapons = WeaponSystem::new();
+        register_enemy_weapons(&mut weapons);
+        weapons.set_projectile_cap(ProjectileOwner::Player, PLAYER_PROJECTILE_CAP);
+        weapons.set_projectile_cap(ProjectileOwner::Enemy, ENEMY_PROJECTILE_CAP);
+
+        Self {
+            world: World::new(),
+            ai: AISystem::with_seed(seed),
+            weapons,
+            abilities: AbilitySystem::new(),
+            collision: CollisionSystem::default(),
+            input: InputState::new(),
+            despawns: DespawnQueue::new(),
+            projectiles: Vec::new(),
+            zone: None,
+            telegraphs: TelegraphSystem::new(),
+            pending_spawns: Vec::new(),
+            formation_of: HashMap::new(),
+            formations: HashMap::new(),
+            run: None,
+            enemy_cooldowns: HashMap::new(),
+            fixed_delta,
+            accumulator: 0.0,
+            time_scale: 1.0,
+            paused: false,
+            tick: 0,
+            frame: 0,
+        }
+    }
+
+    /// Accumulates frame time, scaled by the time scale, and runs as many
+    /// fixed updates as fit. Returns the number of ticks run; always zero
+    /// while paused.
+    pub fn step(&mut self, delta: f32) -> u32 {
+        self.frame += 1;
+        if self.paused {
+            return 0;
+        }
+
+        // The single point where real time becomes simulation time
+        self.accumulator += delta * self.time_scale;
+        let mut ticks = 0;
+        while self.accumulator >= self.fixed_delta && ticks < MAX_TICKS_PER_STEP {
+            self.accumulator -= self.fixed_delta;
+            self.fixed_update();
+            ticks += 1;
+        }
+
+        // Drop time we couldn't catch up on instead of spiraling
+        if ticks == MAX_TICKS_PER_STEP {
+            self.accumulator = self.accumulator.min(self.fixed_delta);
+        }
+
+        ticks
+    }
+
+    /// Runs exactly one fixed update, only while paused
+    pub fn step_once(&mut self) -> bool {
+        if !self.paused {
+            return false;
+        }
+
+        self.fixed_update();
+        true
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Speed of simulation time relative to real time, e.g. 0.25 for
+    /// slow-motion. Zero freezes the simulation; negative values are clamped.
+    pub fn set_time_scale(&mut self, time_scale: f32) {
+        self.time_scale = time_scale.max(0.0);
+    }
+
+    pub fn time_scale(&self) -> f32 {
+        self.time_scale
+    }
+
+    /// Number of real frames stepped, regardless of pause or time scale
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Input for the coming ticks. Press/release edges are seen by the first
+    /// tick only, so they aren't repeated when a frame runs several ticks.
+    pub fn set_input(&mut self, input: InputState) {
+        self.input = input;
+    }
+
+    pub fn input(&self) -> &InputState {
+        &self.input
+    }
+
+    /// Flags an entity for removal at the end of the current tick
+    pub fn queue_despawn(&mut self, entity: Entity) {
+        self.despawns.push(entity);
+    }
+
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    pub fn fixed_delta(&self) -> f32 {
+        self.fixed_delta
+    }
+
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    pub fn world_mut(&mut self) -> &mut World {
+        &mut self.world
+    }
+
+    pub fn ai_mut(&mut self) -> &mut AISystem {
+        &mut self.ai
+    }
+
+    pub fn weapons_mut(&mut self) -> &mut WeaponSystem {
+        &mut self.weapons
+    }
+
+    pub fn abilities_mut(&mut self) -> &mut AbilitySystem {
+        &mut self.abilities
+    }
+
+    pub fn collision(&self) -> &CollisionSystem {
+        &self.collision
+    }
+
+    /// Projectiles in flight
+    /// Starts releasing `zone`'s waves, replacing any zone in progress, and
+    /// telegraphs its hazards
+    pub fn start_zone(&mut self, zone: &Zone) {
+        self.telegraphs.cancel_all();
+        self.pending_spawns.clear();
+        self.formation_of.clear();
+        self.formations.clear();
+        self.zone = Some(ActiveZone {
+            waves: zone.waves.clone(),
+            runner: ZoneRunner::new(zone),
+        });
+
+        for hazard in &zone.hazards {
+            let kind = TelegraphKind::Hazard(hazard.hazard_type);
+            let telegraph = Telegraph::new(hazard.position, DEFAULT_TELEGRAPH_DURATION, kind);
+            self.telegraphs.telegraph(telegraph);
+            self.pending_spawns
+                .push((telegraph, PendingSpawn::Hazard(hazard.clone())));
+        }
+    }
+
+    /// Warnings for spawns that haven't appeared yet
+    pub fn telegraphs(&self) -> &[ActiveTelegraph] {
+        self.telegraphs.active()
+    }
+
+    pub fn zone_runner(&self) -> Option<&ZoneRunner> {
+        self.zone.as_ref().map(|zone| &zone.runner)
+    }
+
+    pub fn set_run(&mut self, run: RunState) {
+        self.run = Some(run);
+    }
+
+    pub fn run(&self) -> Option<&RunState> {
+        self.run.as_ref()
+    }
+
+    pub fn run_mut(&mut self) -> Option<&mut RunState> {
+        self.run.as_mut()
+    }
+
+    pub fn projectiles(&self) -> &[Projectile] {
+        &self.projectiles
+    }
+
+    fn fixed_update(&mut self) {
+        let dt = self.fixed_delta;
+        self.update_waves(dt);
+
+        // Entities are visited in id order so seeded runs replay identically
+        let mut entities: Vec<Entity> = self.world.entities().collect();
+        entities.sort_by_key(|e| (e.id, e.generation));
+
+        self.apply_input(&entities);
+        self.update_ai(&entities, dt);
+        self.integrate(&entities, dt);
+        // Projectiles after the grid rebuild so blasts see this tick's positions
+        self.update_collision(&entities);
+        self.update_projectiles(dt);
+        self.resolve_kills(&entities);
+
+        // Safe point: no system is iterating entities any more
+        self.despawns
+            .flush(&mut self.world, &mut self.ai, &mut self.collision);
+
+        self.input.clear_edges();
+        self.tick += 1;
+    }
+
+    /// Releases the zone's next wave when it is due, telegraphing each enemy
+    /// for its slot in the spawn schedule, and spawns whatever's warning ran
+    /// out. Nothing spawns, and every timer stands still, while the run
+    /// isn't in a zone.
+    fn update_waves(&mut self, dt: f32) {
+        if !self.run.as_ref().is_none_or(RunState::can_spawn) {
+            return;
+        }
+
+        if let Some(zone) = self.zone.as_mut() {
+            if let Some(wave_index) = zone.runner.update(dt) {
+                let wave = &zone.waves[wave_index];
+                zone.runner.on_wave_spawned(wave);
+                let size = wave.enemy_composition.len() as u32;
+                self.formations
+                    .insert(wave_index, FormationStatus::new(size, size));
+                for (index, &enemy_type) in wave.enemy_composition.iter().enumerate() {
+                    let position = wave
+                        .spawn_positions
+                        .get(index)
+                        .copied()
+                        .unwrap_or(Vec2::new(0.0, 0.0));
+                    let delay = wave.spawn_schedule.get(index).copied().unwrap_or(0.0);
+                    let telegraph = Telegraph::new(
+                        position,
+                        DEFAULT_TELEGRAPH_DURATION + delay,
+                        TelegraphKind::Enemy(enemy_type),
+                    );
+                    self.telegraphs.telegraph(telegraph);
+                    let spawn = PendingSpawn::Enemy {
+                        wave: wave_index,
+                        index,
+                    };
+                    self.pending_spawns.push((telegraph, spawn));
+                }
+            }
+        }
+
+        for event in self.telegraphs.update(dt) {
+            if let GameEvent::SpawnNow(telegraph) = event {
+                self.spawn_telegraphed(telegraph);
+            }
+        }
+    }
+
+    /// Spawns what `telegraph` warned about
+    fn spawn_telegraphed(&mut self, telegraph: Telegraph) {
+        let slot = match self
+            .pending_spawns
+            .iter()
+            .position(|(pending, _)| *pending == telegraph)
+        {
+            Some(slot) => slot,
+            None => return,
+        };
+        match self.pending_spawns.remove(slot).1 {
+            PendingSpawn::Enemy { wave, index } => {
+                self.spawn_enemy(wave, index);
+            }
+            PendingSpawn::Hazard(hazard) => {
+                self.spawn_hazard(&hazard);
+            }
+        }
+    }
+
+    fn spawn_hazard(&mut self, hazard: &Hazard) -> Entity {
+        let entity = self.world.spawn();
+        self.world
+            .insert(entity, Position::from_vec2(hazard.position));
+        self.world.insert(entity, Collider::circle(hazard.radius));
+        self.world.insert(entity, EntityFlags::HAZARD);
+        entity
+    }
+
+    /// Spawns entry `index` of the active zone's wave `wave_index` at its
+    /// spawn position, with its scaled stats and registered with the AI
+    fn spawn_enemy(&mut self, wave_index: usize, index: usize) -> Option<Entity> {
+        let wave = self.zone.as_ref()?.waves.get(wave_index)?;
+        let stats = spawn_enemy_stats(wave, index)?;
+        let enemy_type = wave.enemy_composition[index];
+        let position = wave
+            .spawn_positions
+            .get(index)
+            .copied()
+            .unwrap_or(Vec2::new(0.0, 0.0));
+        let mut flags = EntityFlags::ENEMY;
+        if wave.elite_index == Some(index) {
+            flags.set(EntityFlags::ELITE);
+        }
+        if wave.is_boss {
+            flags.set(EntityFlags::BOSS);
+        }
+
+        let entity = self.world.spawn();
+        self.world.insert(entity, Position::from_vec2(position));
+        self.world.insert(entity, stats.health);
+        self.world
+            .insert(entity, Collider::circle(ENEMY_COLLIDER_RADIUS));
+        self.world.insert(entity, flags);
+        self.ai.register_enemy(entity, enemy_type);
+        self.formation_of.insert(entity, wave_index);
+        if let Some(&formation) = self.formations.get(&wave_index) {
+            self.ai.set_formation_status(entity, formation);
+        }
+        Some(entity)
+    }
+
+    /// Despawns enemies shot down this tick, scoring each and reporting it
+    /// to the zone runner and the rest of its formation
+    fn resolve_kills(&mut self, entities: &[Entity]) {
+        for &entity in entities {
+            let shot_down = self.world.has_flags(entity, EntityFlags::ENEMY)
+                && !self.despawns.contains(entity)
+                && self
+                    .world
+                    .get::<Health>(entity)
+                    .is_some_and(|health| !health.is_alive());
+            if !shot_down {
+                continue;
+            }
+
+            self.despawns.push(entity);
+            let is_elite = self.world.has_flags(entity, EntityFlags::ELITE);
+            let is_boss = self.world.has_flags(entity, EntityFlags::BOSS);
+            let enemy_type = self.ai.enemy_type(entity);
+            if let (Some(run), Some(enemy_type)) = (self.run.as_mut(), enemy_type) {
+                run.award_kill(enemy_type, is_elite, is_boss);
+            }
+            self.lose_formation_member(entity);
+
+            let cleared = self
+                .zone
+                .as_mut()
+                .and_then(|zone| zone.runner.on_enemy_killed(is_boss));
+            if let Some(ZoneEvent::ZoneCleared { zone_number }) = cleared {
+                let event = GameEvent::ZoneCleared { zone_number };
+                if let Some(run) = self.run.as_mut() {
+                    run.apply_event(&event);
+                }
+                self.telegraphs.on_event(&event);
+                self.pending_spawns.clear();
+            }
+        }
+    }
+
+    /// Counts the loss against the enemy's wave and tells its wingmen
+    fn lose_formation_member(&mut self, entity: Entity) {
+        let wave = match self.formation_of.remove(&entity) {
+            Some(wave) => wave,
+            None => return,
+        };
+        let formation = match self.formations.get_mut(&wave) {
+            Some(formation) => {
+                formation.alive = formation.alive.saturating_sub(1);
+                *formation
+            }
+            None => return,
+        };
+        for (member, _) in self.formation_of.iter().filter(|(_, w)| **w == wave) {
+            self.ai.set_formation_status(*member, formation);
+        }
+    }
+
+    fn apply_input(&mut self, entities: &[Entity]) {
+        let mut velocity = self.input.move_axis * PLAYER_SPEED;
+
+        // A dash adds its impulse along the stick heading for this tick only
+        if self.input.dash.pressed {
+            let now = self.tick as f32 * self.fixed_delta;
+            self.abilities.try_activate(DASH_ABILITY, now);
+        }
+        if let Some(impulse) = self.abilities.take_impulse() {
+            if self.input.move_axis.magnitude2() > 0.0 {
+                velocity += self.input.move_axis.normalize() * impulse;
+            }
+        }
+
+        let velocity = Velocity::from_vec2(velocity);
+        for entity in entities {
+            if self.world.has_flags(*entity, EntityFlags::PLAYER) {
+                self.world.insert(*entity, velocity);
+            }
+        }
+    }
+
+    fn update_ai(&mut self, entities: &[Entity], dt: f32) {
+        let targets: Vec<AITarget> = entities
+            .iter()
+            .filter(|e| self.world.has_flags(**e, EntityFlags::PLAYER))
+            .filter_map(|e| {
+                let position = self.world.get::<Position>(*e)?;
+                let health = self.world.get::<Health>(*e).map_or(0, |h| h.current);
+                Some(AITarget::player(*e, *position, health))
+            })
+            .collect();
+
+        self.enemy_cooldowns.retain(|_, cooldown| {
+            *cooldown -= dt;
+            *cooldown > 0.0
+        });
+
+        for entity in entities {
+            if !self.world.has_flags(*entity, EntityFlags::ENEMY) {
+                continue;
+            }
+            let position = match self.world.get::<Position>(*entity) {
+                Some(position) => *position,
+                None => continue,
+            };
+
+            let command = self
+                .ai
+                .update(*entity, &position, &targets, TargetingPolicy::Player, dt);
+            if let Some(velocity) = Self::movement_from(&command) {
+                self.world.insert(*entity, Velocity::from_vec2(velocity));
+            }
+            if let Some(direction) = Self::aim_from(&command) {
+                self.fire_enemy_weapon(*entity, position.as_vec2(), direction);
+            }
+        }
+    }
+
+    fn aim_from(command: &AICommand) -> Option<Vec2> {
+        match command {
+            AICommand::Fire { direction } => Some(*direction),
+            AICommand::Multiple(commands) => commands.iter().find_map(Self::aim_from),
+            _ => None,
+        }
+    }
+
+    /// Fires the enemy type's weapon, at most as often as its fire rate allows
+    fn fire_enemy_weapon(&mut self, entity: Entity, origin: Vec2, direction: Vec2) {
+        if self.enemy_cooldowns.contains_key(&entity) {
+            return;
+        }
+        let enemy_type = self.ai.enemy_type(entity).unwrap_or(EnemyType::Unknown);
+        let weapon = match resolve_enemy_weapon(&self.weapons, enemy_type)
+            .and_then(|id| self.weapons.get_weapon(id))
+        {
+            Some(weapon) => weapon,
+            None => return,
+        };
+        let (weapon_id, fire_rate) = (weapon.id, weapon.fire_rate);
+
+        if fire_rate > 0.0 {
+            self.enemy_cooldowns.insert(entity, 1.0 / fire_rate);
+        }
+        let velocity = self
+            .world
+            .get::<Velocity>(entity)
+            .map_or(Vec2::new(0.0, 0.0), Velocity::as_vec2);
+        let shots = self.weapons.fire_from(
+            weapon_id,
+            Muzzle::moving(origin, velocity),
+            direction,
+            ProjectileOwner::Enemy,
+            Some(entity),
+        );
+        // The id was just resolved from the registry, so firing can't fail
+        debug_assert!(shots.is_ok(), "enemy weapon {:?} failed to fire", weapon_id);
+        if let Ok(shots) = shots {
+            self.projectiles.extend(shots);
+        }
+    }
+
+    fn update_projectiles(&mut self, dt: f32) {
+        for projectile in &mut self.projectiles {
+            projectile.update(dt);
+        }
+
+        let blasts: Vec<(Vec2, f32, ProjectileOwner, Explosion)> = self
+            .projectiles
+            .iter()
+            .filter(|projectile| !projectile.is_alive())
+            .filter_map(|projectile| {
+                let explosion = projectile.explosion()?;
+                Some((
+                    projectile.position,
+                    projectile.damage,
+                    projectile.owner,
+                    explosion,
+                ))
+            })
+            .collect();
+        for (center, damage, owner, explosion) in blasts {
+            self.detonate(center, damage, owner, explosion);
+        }
+
+        self.projectiles.retain(|projectile| projectile.is_alive());
+        self.weapons.track_live_projectiles(&self.projectiles);
+    }
+
+    /// Applies a spent explosive's blast to every entity with `Health` in
+    /// range, sparing the side that fired it
+    fn detonate(
+        &mut self,
+        center: Vec2,
+        damage: f32,
+        owner: ProjectileOwner,
+        explosion: Explosion,
+    ) {
+        let friendly = match owner {
+            ProjectileOwner::Player => EntityFlags::PLAYER,
+            ProjectileOwner::Enemy => EntityFlags::ENEMY,
+        };
+        let mut healths: HashMap<Entity, Health> = self
+            .collision
+            .query_radius_sorted(center, explosion.radius)
+            .into_iter()
+            .filter(|(entity, _)| !self.world.has_flags(*entity, friendly))
+            .filter_map(|(entity, _)| Some((entity, self.world.get::<Health>(entity)?.clone())))
+            .collect();
+
+        self.collision.explode(
+            center,
+            explosion.radius,
+            damage,
+            explosion.falloff,
+            &mut healths,
+        );
+        for (entity, health) in healths {
+            self.world.insert(entity, health);
+        }
+    }
+
+    fn movement_from(command: &AICommand) -> Option<Vec2> {
+        match command {
+            AICommand::Move { direction, speed } => Some(*direction * *speed),
+            AICommand::Multiple(commands) => commands.iter().find_map(Self::movement_from),
+            _ => None,
+        }
+    }
+
+    fn integrate(&mut self, entities: &[Entity], dt: f32) {
+        for entity in entities {
+            let velocity = match self.world.get::<Velocity>(*entity) {
+                Some(velocity) => *velocity,
+                None => continue,
+            };
+            if let Some(position) = self.world.get_mut::<Position>(*entity) {
+                *position += (velocity * dt).as_vec2();
+            }
+        }
+    }
+
+    fn update_collision(&mut self, entities: &[Entity]) {
+        self.collision.clear();
+        for entity in entities {
+            if let (Some(position), Some(collider)) = (
+                self.world.get::<Position>(*entity),
+                self.world.get::<Collider>(*entity),
+            ) {
+                let flags = self
+                    .world
+                    .get::<EntityFlags>(*entity)
+                    .copied()
+                    .unwrap_or_default();
+                let scale = self
+                    .world
+                    .get::<Sprite>(*entity)
+                    .map_or(Vec2::new(1.0, 1.0), |sprite| sprite.scale);
+                self.collision
+                    .insert_scaled(*entity, position, collider, scale, flags);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::entities::AircraftType;
+    use crate::game::input::{Button, InputEvent};
+    use crate::game::loadout::{weapon_definition, ENEMY_RAPID_GUN};
+    use crate::game::state::{RunPhase, UpgradeId};
+    use crate::game::systems::procedural::{ProceduralGenerator, ZoneType};
+    use crate::game::systems::weapon::ProjectileType;
+
+    fn moving_simulation() -> (Simulation, Entity) {
+        let mut sim = Simulation::with_fixed_delta(1, 0.25);
+        let world = sim.world_mut();
+        let entity = world.spawn();
+        world.insert(entity, Position::new(0.0, 0.0));
+        world.insert(entity, Velocity::new(4.0, 0.0));
+        (sim, entity)
+    }
+
+    #[test]
+    fn test_step_runs_fixed_ticks() {
+        let (mut sim, entity) = moving_simulation();
+
+        assert_eq!(sim.step(0.5), 2);
+        assert_eq!(sim.tick(), 2);
+        assert_eq!(sim.world().get::<Position>(entity).unwrap().x, 2.0);
+
+        // Leftover time carries into the next step
+        assert_eq!(sim.step(0.125), 0);
+        assert_eq!(sim.step(0.125), 1);
+        assert_eq!(sim.tick(), 3);
+    }
+
+    #[test]
+    fn test_step_while_paused_does_nothing() {
+        let (mut sim, entity) = moving_simulation();
+        sim.pause();
+
+        assert_eq!(sim.step(10.0), 0);
+        assert_eq!(sim.tick(), 0);
+        assert_eq!(sim.world().get::<Position>(entity).unwrap().x, 0.0);
+
+        // Paused time isn't banked for later either
+        sim.resume();
+        assert_eq!(sim.step(0.0), 0);
+    }
+
+    #[test]
+    fn test_input_moves_player() {
+        let mut sim = Simulation::with_fixed_delta(1, 0.5);
+        let player = sim.world_mut().spawn();
+        sim.world_mut().insert(player, Position::new(0.0, 0.0));
+        sim.world_mut().insert(player, EntityFlags::PLAYER);
+
+        let mut input = InputState::new();
+        input.update(&[
+            InputEvent::Move(Vec2::new(0.0, -1.0)),
+            InputEvent::ButtonDown(Button::Fire),
+        ]);
+        sim.set_input(input);
+
+        assert_eq!(sim.step(1.0), 2);
+        let position = sim.world().get::<Position>(player).unwrap();
+        assert_eq!(position.y, -PLAYER_SPEED);
+
+        // The press edge was consumed by the first tick; the hold remains
+        assert!(!sim.input().fire.pressed);
+        assert!(sim.input().fire.held);
+    }
+
+    #[test]
+    fn test_time_scale_slows_movement() {
+        let (mut normal, entity) = moving_simulation();
+        let (mut slowed, _) = moving_simulation();
+        slowed.set_time_scale(0.5);
+
+        for _ in 0..4 {
+            normal.step(0.25);
+            slowed.step(0.25);
+        }
+
+        let normal_x = normal.world().get::<Position>(entity).unwrap().x;
+        let slowed_x = slowed.world().get::<Position>(entity).unwrap().x;
+        assert_eq!(normal_x, 4.0);
+        assert_eq!(slowed_x, normal_x * 0.5);
+    }
+
+    #[test]
+    fn test_zero_time_scale_freezes_simulation() {
+        let (mut sim, entity) = moving_simulation();
+        sim.set_time_scale(0.0);
+
+        for _ in 0..10 {
+            assert_eq!(sim.step(0.25), 0);
+        }
+        assert_eq!(sim.frame(), 10);
+        assert_eq!(sim.tick(), 0);
+        assert_eq!(sim.world().get::<Position>(entity).unwrap().x, 0.0);
+
+        sim.set_time_scale(1.0);
+        assert_eq!(sim.step(0.25), 1);
+    }
+
+    #[test]
+    fn test_queued_despawn_applies_after_tick() {
+        let (mut sim, entity) = moving_simulation();
+
+        sim.queue_despawn(entity);
+        assert!(sim.world().is_alive(entity));
+
+        assert_eq!(sim.step(0.25), 1);
+        assert!(!sim.world().is_alive(entity));
+    }
+
+    #[test]
+    fn test_step_once_advances_single_tick() {
+        let (mut sim, entity) = moving_simulation();
+
+        // Only valid while paused
+        assert!(!sim.step_once());
+        assert_eq!(sim.tick(), 0);
+
+        sim.pause();
+        assert!(sim.step_once());
+        assert_eq!(sim.tick(), 1);
+        assert_eq!(sim.world().get::<Position>(entity).unwrap().x, 1.0);
+
+        assert!(sim.step_once());
+        assert_eq!(sim.tick(), 2);
+        assert_eq!(sim.world().get::<Position>(entity).unwrap().x, 2.0);
+    }
+
+    #[test]
+    fn test_enemies_fire_their_type_weapon() {
+        let mut sim = Simulation::with_fixed_delta(1, 0.1);
+        let player = sim.world_mut().spawn();
+        sim.world_mut().insert(player, Position::new(0.0, 300.0));
+        sim.world_mut().insert(player, EntityFlags::PLAYER);
+
+        let ace = sim.world_mut().spawn();
+        sim.world_mut().insert(ace, Position::new(0.0, 0.0));
+        sim.world_mut().insert(ace, EntityFlags::ENEMY);
+        sim.ai_mut().register_enemy(ace, EnemyType::Ace);
+
+        assert_eq!(sim.step(0.1), 1);
+        let fired = sim.projectiles().len();
+        assert!(fired > 0);
+        let rapid_gun = weapon_definition(ENEMY_RAPID_GUN).unwrap();
+        assert!(sim
+            .projectiles()
+            .iter()
+            .all(|p| p.owner == ProjectileOwner::Enemy && p.damage == rapid_gun.base_damage));
+
+        // The weapon's fire rate limits follow-up shots
+        assert_eq!(sim.step(0.1), 1);
+        assert_eq!(sim.projectiles().len(), fired);
+    }
+
+    #[test]
+    fn test_expiring_rocket_damages_nearby_entities() {
+        let mut sim = Simulation::with_fixed_delta(1, 0.1);
+        let collider = Collider::circle(5.0);
+        let mut spawn = |x: f32, flags: EntityFlags| {
+            let world = sim.world_mut();
+            let entity = world.spawn();
+            world.insert(entity, Position::new(x, 0.0));
+            world.insert(entity, collider);
+            world.insert(entity, Health::new(100));
+            world.insert(entity, flags);
+            entity
+        };
+        let near = spawn(10.0, EntityFlags::ENEMY);
+        let far = spawn(500.0, EntityFlags::ENEMY);
+        let player = spawn(-10.0, EntityFlags::PLAYER);
+
+        sim.projectiles.push(Projectile {
+            damage: 50.0,
+            projectile_type: ProjectileType::Rocket,
+            owner: ProjectileOwner::Player,
+            lifetime: 0.05,
+            ..Projectile::default()
+        });
+
+        assert_eq!(sim.step(0.1), 1);
+        assert!(sim.projectiles().is_empty());
+        let health = |entity| sim.world().get::<Health>(entity).unwrap().current;
+        assert!(health(near) < 100);
+        assert_eq!(health(far), 100);
+        // The shooter's own side is spared
+        assert_eq!(health(player), 100);
+    }
+
+    #[test]
+    fn test_dash_moves_player_farther() {
+        let run = |dash: bool| {
+            let mut sim = Simulation::with_fixed_delta(1, 0.1);
+            sim.abilities_mut().unlock(DASH_ABILITY);
+            let player = sim.world_mut().spawn();
+            sim.world_mut().insert(player, Position::new(0.0, 0.0));
+            sim.world_mut().insert(player, EntityFlags::PLAYER);
+
+            let mut events = vec![InputEvent::Move(Vec2::new(1.0, 0.0))];
+            if dash {
+                events.push(InputEvent::ButtonDown(Button::Dash));
+            }
+            let mut input = InputState::new();
+            input.update(&events);
+            sim.set_input(input);
+
+            assert_eq!(sim.step(0.1), 1);
+            sim.world().get::<Position>(player).unwrap().x
+        };
+
+        let walked = run(false);
+        let dashed = run(true);
+        assert_eq!(walked, PLAYER_SPEED * 0.1);
+        assert!(dashed > walked);
+    }
+
+    #[test]
+    fn test_no_wave_released_while_choosing_upgrade() {
+        let zone = ProceduralGenerator::new(3).generate_zone(ZoneType::Sky, 1);
+        let first_wave = zone.waves[0].enemy_composition.len();
+
+        let mut run = RunState::new(3, AircraftType::Spitfire);
+        run.apply_event(&GameEvent::ZoneCleared { zone_number: 0 });
+        assert_eq!(run.phase, RunPhase::ChoosingUpgrade);
+
+        let mut sim = Simulation::with_fixed_delta(1, 0.1);
+        sim.set_run(run);
+        sim.start_zone(&zone);
+        for _ in 0..10 {
+            sim.step(0.1);
+        }
+        assert_eq!(sim.world().entity_count(), 0);
+        assert_eq!(sim.zone_runner().unwrap().progress().waves_spawned(), 0);
+
+        // Once the player is back in a zone the first wave goes out
+        let run = sim.run_mut().unwrap();
+        assert!(run.choose_upgrade(UpgradeId(1)));
+        assert!(run.enter_zone());
+        assert_eq!(sim.step(0.1), 1);
+        assert_eq!(sim.zone_runner().unwrap().progress().waves_spawned(), 1);
+        assert!(sim.telegraphs().len() >= first_wave);
+    }
+
+    fn count_flagged(sim: &Simulation, flags: EntityFlags) -> usize {
+        sim.world()
+            .entities()
+            .filter(|e| sim.world().has_flags(*e, flags))
+            .count()
+    }
+
+    #[test]
+    fn test_spawns_wait_for_their_telegraph() {
+        let mut zone = ProceduralGenerator::new(8).generate_zone(ZoneType::Ocean, 2);
+        zone.waves[0].spawn_schedule.clear();
+        zone.hazards.truncate(1);
+        let first_wave = zone.waves[0].enemy_composition.len();
+        let hazards = zone.hazards.len();
+        assert_eq!(hazards, 1);
+
+        let mut sim = Simulation::with_fixed_delta(1, 0.25);
+        sim.start_zone(&zone);
+        assert_eq!(sim.telegraphs().len(), hazards);
+
+        // The wave is released on the first tick, but only as warnings
+        assert_eq!(sim.step(0.75), 3);
+        assert_eq!(sim.telegraphs().len(), first_wave + hazards);
+        assert_eq!(sim.world().entity_count(), 0);
+
+        // A full telegraph duration in, everything has appeared
+        assert_eq!(sim.step(0.25), 1);
+        assert!(sim.telegraphs().is_empty());
+        assert_eq!(count_flagged(&sim, EntityFlags::ENEMY), first_wave);
+        assert_eq!(count_flagged(&sim, EntityFlags::HAZARD), hazards);
+    }
+
+    fn bomber_formation_zone(count: usize) -> Zone {
+        let mut zone = Zone::new(ZoneType::Sky, 1);
+        zone.waves.push(Wave {
+            enemy_composition: vec![EnemyType::HeavyBomber; count],
+            spawn_positions: (0..count)
+                .map(|i| Vec2::new(i as f32 * 100.0, -200.0))
+                .collect(),
+            health_multiplier: 1.0,
+            damage_multiplier: 1.0,
+            speed_multiplier: 1.0,
+            spawn_delay: 0.0,
+            spawn_schedule: Vec::new(),
+            has_elite: false,
+            elite_index: None,
+            is_boss: false,
+        });
+        zone
+    }
+
+    #[test]
+    fn test_kills_thin_the_formation_and_clear_the_zone() {
+        let mut sim = Simulation::with_fixed_delta(1, 0.5);
+        sim.set_run(RunState::new(1, AircraftType::Spitfire));
+        sim.start_zone(&bomber_formation_zone(4));
+
+        // Released on the first tick, spawned once the telegraph runs out
+        assert_eq!(sim.step(1.0), 2);
+        let mut bombers: Vec<Entity> = sim.world().entities().collect();
+        bombers.sort_by_key(|e| e.id);
+        assert_eq!(bombers.len(), 4);
+        assert_eq!(
+            sim.ai.formation_status(bombers[1]),
+            Some(FormationStatus::new(4, 4))
+        );
+
+        let shoot_down = |sim: &mut Simulation, entity: Entity| {
+            sim.world_mut().get_mut::<Health>(entity).unwrap().current = 0;
+        };
+        shoot_down(&mut sim, bombers[0]);
+        sim.step(0.5);
+        assert!(!sim.world().is_alive(bombers[0]));
+        assert_eq!(
+            sim.ai.formation_status(bombers[1]),
+            Some(FormationStatus::new(3, 4))
+        );
+        assert_eq!(sim.zone_runner().unwrap().progress().enemies_remaining(), 3);
+
+        for &bomber in &bombers[1..] {
+            shoot_down(&mut sim, bomber);
+        }
+        sim.step(0.5);
+        assert_eq!(sim.world().entity_count(), 0);
+        let run = sim.run().unwrap();
+        assert_eq!(run.phase, RunPhase::ChoosingUpgrade);
+        assert!(run.score > 0);
+    }
+}
@@ -1,13 +1,45 @@
 //! Game state management and serialization
 
 use serde::{Deserialize, Serialize};
-use crate::game::entities::AircraftType;
+use crate::game::balance::BalanceConfig;
+use crate::game::entities::{AircraftType, EnemyType};
+use crate::game::events::GameEvent;
+use crate::game::scoring::kill_score;
+use crate::game::systems::procedural::{ProceduralGenerator, RngState};
 use std::collections::{HashMap, HashSet};
 
 /// Upgrade identifier
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct UpgradeId(pub u32);
 
+/// Meta-shop item identifier
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ShopItemId(pub u32);
+
+/// Score needed to earn one unit of meta-currency
+pub const SCORE_PER_CURRENCY: u64 = 100;
+
+/// Lives a new run starts with
+pub const STARTING_LIVES: u32 = 3;
+/// Most lives a run can bank from extra-life pickups
+pub const MAX_LIVES: u32 = 9;
+
+fn default_lives() -> u32 {
+    STARTING_LIVES
+}
+
+/// Marks the encoded part of a share string, versioned so the format can
+/// change without misreading old strings
+const SHARE_PREFIX: &str = "AH1:";
+
+/// Leads every binary save. bincode is positional and ignores serde
+/// defaults, so the version byte must be bumped whenever a saved struct
+/// gains, loses or reorders a field.
+#[cfg(feature = "binary-saves")]
+const BINARY_SAVE_MAGIC: &[u8; 3] = b"AHS";
+#[cfg(feature = "binary-saves")]
+pub const BINARY_SAVE_VERSION: u8 = 1;
+
 /// Complete game state
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GameState {
@@ -34,6 +66,73 @@ impl GameState {
     pub fn deserialize_from_json(json: &str) -> Result<Self, serde_json::Error> {
         serde_json::from_str(json)
     }
+    
+    /// Compact binary encoding, much smaller than JSON for browser storage,
+    /// behind a format and version header
+    #[cfg(feature = "binary-saves")]
+    pub fn serialize_to_bytes(&self) -> Result<Vec<u8>, bincode::Error> {
+        let mut bytes = BINARY_SAVE_MAGIC.to_vec();
+        bytes.push(BINARY_SAVE_VERSION);
+        bincode::serialize_into(&mut bytes, self)?;
+        Ok(bytes)
+    }
+    
+    /// Reads a `serialize_to_bytes` save, rejecting data without the header
+    /// or from another save version rather than misreading it
+    #[cfg(feature = "binary-saves")]
+    pub fn deserialize_from_bytes(bytes: &[u8]) -> Result<Self, BinarySaveError> {
+        let payload = bytes
+            .strip_prefix(BINARY_SAVE_MAGIC.as_slice())
+            .ok_or(BinarySaveError::NotASave)?;
+        match payload.split_first() {
+            Some((&BINARY_SAVE_VERSION, data)) => {
+                bincode::deserialize(data).map_err(BinarySaveError::Decode)
+            }
+            Some((&version, _)) => Err(BinarySaveError::UnsupportedVersion(version)),
+            None => Err(BinarySaveError::NotASave),
+        }
+    }
+}
+
+/// Why a binary save could not be loaded
+#[cfg(feature = "binary-saves")]
+#[derive(Debug)]
+pub enum BinarySaveError {
+    /// The data doesn't start with the save header
+    NotASave,
+    /// Written by a save format version this build can't read
+    UnsupportedVersion(u8),
+    Decode(bincode::Error),
+}
+
+#[cfg(feature = "binary-saves")]
+impl std::fmt::Display for BinarySaveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BinarySaveError::NotASave => write!(f, "not a binary save"),
+            BinarySaveError::UnsupportedVersion(version) => write!(
+                f,
+                "save version {} is not supported (expected {})",
+                version, BINARY_SAVE_VERSION
+            ),
+            BinarySaveError::Decode(err) => write!(f, "corrupt save: {}", err),
+        }
+    }
+}
+
+#[cfg(feature = "binary-saves")]
+impl std::error::Error for BinarySaveError {}
+
+/// Where a run is in the zone -> upgrade -> next zone loop
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum RunPhase {
+    /// Fighting through a zone; the only phase in which enemies spawn
+    #[default]
+    InZone,
+    /// Zone cleared, waiting for the player to pick an upgrade
+    ChoosingUpgrade,
+    /// Upgrade picked, moving into the next zone
+    ZoneTransition,
 }
 
 /// Current run state
@@ -46,6 +145,20 @@ pub struct RunState {
     pub time_elapsed: f32,
     pub current_health: i32,
     pub max_health: i32,
+    /// Seconds left on the active power-up buff
+    #[serde(default)]
+    pub power_up_remaining: f32,
+    #[serde(default)]
+    pub phase: RunPhase,
+    /// Procedural generator position for resuming mid-zone
+    #[serde(default)]
+    pub generator_rng: Option<RngState>,
+    /// Lives left, counting the current one
+    #[serde(default = "default_lives")]
+    pub lives: u32,
+    /// Upgrades picked between zones, in order
+    #[serde(default)]
+    pub upgrades: Vec<UpgradeId>,
 }
 
 impl RunState {
@@ -58,8 +171,226 @@ impl RunState {
             time_elapsed: 0.0,
             current_health: 100,
             max_health: 100,
+            power_up_remaining: 0.0,
+            phase: RunPhase::InZone,
+            generator_rng: None,
+            lives: STARTING_LIVES,
+            upgrades: Vec::new(),
         }
     }
+    
+    /// Clearing the current zone pauses the run for upgrade selection
+    pub fn apply_event(&mut self, event: &GameEvent) {
+        if let GameEvent::ZoneCleared { .. } = event {
+            if self.phase == RunPhase::InZone {
+                self.phase = RunPhase::ChoosingUpgrade;
+            }
+        }
+    }
+    
+    /// Records the upgrade pick and advances to the next zone. Returns false
+    /// outside of upgrade selection.
+    pub fn choose_upgrade(&mut self, upgrade: UpgradeId) -> bool {
+        if self.phase != RunPhase::ChoosingUpgrade {
+            return false;
+        }
+        self.upgrades.push(upgrade);
+        self.zone += 1;
+        self.phase = RunPhase::ZoneTransition;
+        true
+    }
+    
+    /// Ends the transition once the next zone is ready to play
+    pub fn enter_zone(&mut self) -> bool {
+        if self.phase != RunPhase::ZoneTransition {
+            return false;
+        }
+        self.phase = RunPhase::InZone;
+        true
+    }
+    
+    pub fn can_spawn(&self) -> bool {
+        self.phase == RunPhase::InZone
+    }
+    
+    /// Advances run time, running down any active power-up
+    pub fn advance_time(&mut self, delta: f32) {
+        self.time_elapsed += delta;
+        self.power_up_remaining = (self.power_up_remaining - delta).max(0.0);
+    }
+    
+    pub fn is_powered_up(&self) -> bool {
+        self.power_up_remaining > 0.0
+    }
+    
+    /// Generator for this run under `balance`, continuing its exact
+    /// procedural sequence when the save recorded an RNG position
+    pub fn resume_generator(&self, balance: &BalanceConfig) -> ProceduralGenerator {
+        let mut generator = ProceduralGenerator::with_balance(self.seed, balance);
+        if let Some(state) = self.generator_rng {
+            generator.restore_rng(state);
+        }
+        generator
+    }
+    
+    /// Records the generator's RNG position so loading resumes there
+    pub fn save_generator(&mut self, generator: &ProceduralGenerator) {
+        self.generator_rng = Some(generator.rng_state());
+    }
+    
+    /// Awards the score for a kill and returns the points gained
+    pub fn award_kill(&mut self, enemy_type: EnemyType, is_elite: bool, is_boss: bool) -> u32 {
+        let points = kill_score(enemy_type, is_elite, is_boss);
+        self.score += points as u64;
+        points
+    }
+    
+    /// Spends a life after the player is shot down. With lives to spare the
+    /// player respawns at full health; returns true once the last life is
+    /// gone and the run is over.
+    pub fn lose_life(&mut self) -> bool {
+        self.lives = self.lives.saturating_sub(1);
+        if self.lives == 0 {
+            self.current_health = 0;
+            return true;
+        }
+        self.current_health = self.max_health;
+        false
+    }
+    
+    /// Grants an extra life, up to `MAX_LIVES`. Returns whether one was added.
+    pub fn add_life(&mut self) -> bool {
+        if self.lives >= MAX_LIVES {
+            return false;
+        }
+        self.lives += 1;
+        true
+    }
+    
+    /// One-line run summary for sharing, e.g. "Spitfire reached zone 4 with
+    /// 12500 points and 3 upgrades [AH1:...]". The bracketed code carries the
+    /// seed and picks for `from_share_string`.
+    pub fn to_share_string(&self) -> String {
+        let upgrades: Vec<String> = self.upgrades.iter().map(|id| id.0.to_string()).collect();
+        let code = format!(
+            "{}{}:{}:{}:{:?}:{}",
+            SHARE_PREFIX,
+            seed_to_code(self.seed),
+            self.zone,
+            self.score,
+            self.aircraft,
+            upgrades.join(",")
+        );
+        format!(
+            "{:?} reached zone {} with {} points and {} upgrades [{}]",
+            self.aircraft,
+            self.zone,
+            self.score,
+            self.upgrades.len(),
+            code
+        )
+    }
+    
+    /// Rebuilds a run from a share string, or just its code, with the seed,
+    /// aircraft, zone, score and upgrades set. Returns `None` if no valid
+    /// code is found.
+    pub fn from_share_string(s: &str) -> Option<RunState> {
+        let code = &s[s.find(SHARE_PREFIX)? + SHARE_PREFIX.len()..];
+        let code = code.split(']').next()?.trim();
+        let fields: Vec<&str> = code.split(':').collect();
+        let [seed, zone, score, aircraft, upgrades] = fields[..] else {
+            return None;
+        };
+        
+        let mut run = RunState::new(decode_seed_code(seed)?, aircraft_from_name(aircraft)?);
+        run.zone = zone.parse().ok()?;
+        run.score = score.parse().ok()?;
+        run.upgrades = upgrades
+            .split(',')
+            .filter(|id| !id.is_empty())
+            .map(|id| id.parse().ok().map(UpgradeId))
+            .collect::<Option<_>>()?;
+        Some(run)
+    }
+}
+
+/// Aircraft named by its `Debug` form, as written in share strings, or
+/// `None` for a name this build doesn't know. Parsed through serde so new
+/// variants are picked up without a list to keep in sync.
+fn aircraft_from_name(name: &str) -> Option<AircraftType> {
+    let value = serde_json::Value::String(name.to_string());
+    let aircraft: AircraftType = serde_json::from_value(value).ok()?;
+    // Serde maps unrecognized names to `Unknown` for old saves; only accept
+    // it here when that is what was written
+    (aircraft != AircraftType::Unknown || name == "Unknown").then_some(aircraft)
+}
+
+/// Crockford base-32 digits used in shareable seed codes
+const SEED_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+/// Base-32 digits needed to hold a u64
+const SEED_CODE_LEN: usize = 13;
+/// Where `seed_to_code` splits the code with a dash
+const SEED_CODE_SPLIT: usize = 6;
+
+/// Shareable code for a seed, e.g. "0000G0-0000000". Round-trips through
+/// `parse_seed`.
+pub fn seed_to_code(seed: u64) -> String {
+    let mut code = String::with_capacity(SEED_CODE_LEN + 1);
+    for i in (0..SEED_CODE_LEN).rev() {
+        if code.len() == SEED_CODE_SPLIT {
+            code.push('-');
+        }
+        let digit = (seed >> (i * 5)) & 0x1F;
+        code.push(SEED_ALPHABET[digit as usize] as char);
+    }
+    code
+}
+
+/// Turns player-entered text into a seed. Accepts decimal, `0x` hex and
+/// codes from `seed_to_code`; any other text is hashed, so every input is
+/// a valid, stable seed.
+pub fn parse_seed(s: &str) -> u64 {
+    let text = s.trim();
+
+    if let Ok(seed) = text.parse::<u64>() {
+        return seed;
+    }
+    if let Some(hex) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        if let Ok(seed) = u64::from_str_radix(hex, 16) {
+            return seed;
+        }
+    }
+    if let Some(seed) = decode_seed_code(text) {
+        return seed;
+    }
+
+    // FNV-1a, which unlike std's hasher is stable across builds
+    text.bytes().fold(0xCBF2_9CE4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01B3)
+    })
+}
+
+/// Decodes a base-32 seed code, ignoring dashes and case and reading the
+/// lookalikes O, I and L as 0, 1 and 1
+fn decode_seed_code(text: &str) -> Option<u64> {
+    let digits: Vec<char> = text.chars().filter(|c| *c != '-').collect();
+    if digits.len() != SEED_CODE_LEN {
+        return None;
+    }
+
+    digits.iter().try_fold(0u64, |seed, c| {
+        let c = match c.to_ascii_uppercase() {
+            'O' => '0',
+            'I' | 'L' => '1',
+            other => other,
+        };
+        let digit = SEED_ALPHABET.iter().position(|&a| a as char == c)? as u64;
+        // 13 digits hold 65 bits; reject codes past u64::MAX
+        if seed >> 59 != 0 {
+            return None;
+        }
+        Some(seed << 5 | digit)
+    })
 }
 
 /// Meta-progression system
@@ -70,6 +401,11 @@ pub struct MetaProgression {
     pub unlocked_aircraft: HashSet<AircraftType>,
     pub total_score: u64,
     pub total_runs: u32,
+    /// Spendable between runs in the `MetaShop`
+    #[serde(default)]
+    pub currency: u32,
+    #[serde(default)]
+    pub purchased_items: HashSet<ShopItemId>,
 }
 
 impl MetaProgression {
@@ -83,9 +419,24 @@ impl MetaProgression {
             unlocked_aircraft: unlocked,
             total_score: 0,
             total_runs: 0,
+            currency: 0,
+            purchased_items: HashSet::new(),
         }
     }
     
+    /// Banks a finished run and returns the currency it earned
+    pub fn complete_run(&mut self, run: &RunState) -> u32 {
+        let earned = (run.score / SCORE_PER_CURRENCY).min(u32::MAX as u64) as u32;
+        self.currency = self.currency.saturating_add(earned);
+        self.total_score += run.score;
+        self.total_runs += 1;
+        earned
+    }
+    
+    pub fn has_purchased(&self, item: ShopItemId) -> bool {
+        self.purchased_items.contains(&item)
+    }
+    
     pub fn add_xp(&mut self, amount: u32) {
         self.squadron_xp += amount;
         self.check_level_up();
@@ -136,13 +487,21 @@ pub enum GraphicsQuality {
     Ultra,
 }
 
-/// Game statistics
+/// Game statistics, built by folding the game event stream so they stay
+/// current mid-run and can be rebuilt from a replay
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GameStatistics {
     pub total_playtime: f32,
     pub enemies_defeated: u32,
     pub highest_score: u64,
     pub highest_zone: u32,
+    #[serde(default)]
+    pub zones_cleared: u32,
+    #[serde(default)]
+    pub damage_taken: u64,
+    /// Score of the run in progress
+    #[serde(default)]
+    pub run_score: u64,
 }
 
 impl GameStatistics {
@@ -152,23 +511,53 @@ impl GameStatistics {
             enemies_defeated: 0,
             highest_score: 0,
             highest_zone: 0,
+            zones_cleared: 0,
+            damage_taken: 0,
+            run_score: 0,
         }
     }
     
-    pub fn update_from_run(&mut self, run: &RunState) {
-        if run.score > self.highest_score {
-            self.highest_score = run.score;
+    /// Rebuilds statistics from scratch by folding an event stream
+    pub fn from_events<'a>(events: impl IntoIterator<Item = &'a GameEvent>) -> Self {
+        let mut stats = Self::new();
+        for event in events {
+            stats.apply_event(event);
         }
-        if run.zone > self.highest_zone {
-            self.highest_zone = run.zone;
+        stats
+    }
+    
+    pub fn apply_event(&mut self, event: &GameEvent) {
+        match *event {
+            GameEvent::EnemyKilled {
+                enemy_type,
+                is_elite,
+                is_boss,
+            } => {
+                self.enemies_defeated += 1;
+                self.run_score += kill_score(enemy_type, is_elite, is_boss) as u64;
+                self.highest_score = self.highest_score.max(self.run_score);
+            }
+            GameEvent::DamageTaken { amount } => {
+                self.damage_taken += amount.max(0) as u64;
+            }
+            GameEvent::ZoneCleared { zone_number } => {
+                self.zones_cleared += 1;
+                self.highest_zone = self.highest_zone.max(zone_number);
+            }
+            GameEvent::RunStarted => self.run_score = 0,
+            GameEvent::TimeElapsed { seconds } => {
+                self.total_playtime += seconds.max(0.0);
+            }
+            GameEvent::DamageNumber(_) | GameEvent::SpawnNow(_) => {}
         }
-        self.total_playtime += run.time_elapsed;
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::game::systems::procedural::ZoneType;
+    use crate::game::systems::upgrade::Rarity;
     use wasm_bindgen_test::*;
     
     wasm_bindgen_test_configure!(run_in_browser);
@@ -226,6 +615,41 @@ mod tests {
         assert_eq!(run.score, 0);
     }
     
+    #[test]
+    fn test_run_state_award_kill() {
+        let mut run = RunState::new(12345, AircraftType::Spitfire);
+        
+        let fighter = run.award_kill(EnemyType::Fighter, false, false);
+        let elite = run.award_kill(EnemyType::Fighter, true, false);
+        
+        assert_eq!(elite, fighter * 3);
+        assert_eq!(run.score, (fighter + elite) as u64);
+    }
+    
+    #[test]
+    fn test_losing_a_life_respawns_until_last() {
+        let mut run = RunState::new(1, AircraftType::Spitfire);
+        assert_eq!(run.lives, STARTING_LIVES);
+        
+        run.current_health = 0;
+        assert!(!run.lose_life());
+        assert_eq!(run.lives, STARTING_LIVES - 1);
+        assert_eq!(run.current_health, run.max_health);
+        
+        run.lives = 1;
+        run.current_health = 0;
+        assert!(run.lose_life());
+        assert_eq!(run.lives, 0);
+        assert_eq!(run.current_health, 0);
+    }
+    
+    #[test]
+    fn test_extra_lives_are_capped() {
+        let mut run = RunState::new(1, AircraftType::Spitfire);
+        while run.add_life() {}
+        assert_eq!(run.lives, MAX_LIVES);
+    }
+    
     #[wasm_bindgen_test]
     fn test_run_state_creation_wasm() {
         let run = RunState::new(12345, AircraftType::Spitfire);
@@ -268,40 +692,171 @@ mod tests {
         assert!(meta.is_aircraft_unlocked(AircraftType::Mustang));
     }
     
+    fn kill(enemy_type: EnemyType) -> GameEvent {
+        GameEvent::EnemyKilled {
+            enemy_type,
+            is_elite: false,
+            is_boss: false,
+        }
+    }
+    
+    fn sample_events() -> Vec<GameEvent> {
+        vec![
+            GameEvent::RunStarted,
+            kill(EnemyType::Ace),
+            GameEvent::DamageTaken { amount: 20 },
+            GameEvent::ZoneCleared { zone_number: 1 },
+            kill(EnemyType::Fighter),
+            GameEvent::TimeElapsed { seconds: 120.0 },
+            GameEvent::DamageTaken { amount: 15 },
+            GameEvent::ZoneCleared { zone_number: 2 },
+        ]
+    }
+    
     #[test]
     fn test_statistics_update() {
-        let mut stats = GameStatistics::new();
-        let run = RunState {
-            seed: 123,
-            aircraft: AircraftType::Spitfire,
-            zone: 5,
-            score: 1000,
-            time_elapsed: 120.0,
-            current_health: 50,
-            max_health: 100,
-        };
+        let stats = GameStatistics::from_events(&sample_events());
         
-        stats.update_from_run(&run);
-        assert_eq!(stats.highest_score, 1000);
-        assert_eq!(stats.highest_zone, 5);
+        let expected_score =
+            kill_score(EnemyType::Ace, false, false) + kill_score(EnemyType::Fighter, false, false);
+        assert_eq!(stats.highest_score, expected_score as u64);
+        assert_eq!(stats.enemies_defeated, 2);
+        assert_eq!(stats.damage_taken, 35);
+        assert_eq!(stats.zones_cleared, 2);
+        assert_eq!(stats.highest_zone, 2);
         assert_eq!(stats.total_playtime, 120.0);
     }
     
     #[wasm_bindgen_test]
     fn test_statistics_update_wasm() {
-        let mut stats = GameStatistics::new();
-        let run = RunState {
-            seed: 123,
-            aircraft: AircraftType::Spitfire,
-            zone: 5,
-            score: 1000,
-            time_elapsed: 120.0,
-            current_health: 50,
-            max_health: 100,
-        };
+        let stats = GameStatistics::from_events(&sample_events());
+        assert_eq!(stats.enemies_defeated, 2);
+    }
+    
+    #[test]
+    fn test_statistics_counters_are_order_independent() {
+        let events = sample_events();
+        let forward = GameStatistics::from_events(&events);
+        let reversed = GameStatistics::from_events(events.iter().rev());
+        
+        assert_eq!(forward.enemies_defeated, reversed.enemies_defeated);
+        assert_eq!(forward.damage_taken, reversed.damage_taken);
+        assert_eq!(forward.zones_cleared, reversed.zones_cleared);
+        assert_eq!(forward.highest_zone, reversed.highest_zone);
+        assert_eq!(forward.total_playtime, reversed.total_playtime);
+    }
+    
+    #[test]
+    fn test_highest_score_survives_new_run() {
+        let mut stats = GameStatistics::from_events(&sample_events());
+        let best = stats.highest_score;
+        
+        stats.apply_event(&GameEvent::RunStarted);
+        stats.apply_event(&kill(EnemyType::Fighter));
+        assert_eq!(
+            stats.run_score,
+            kill_score(EnemyType::Fighter, false, false) as u64
+        );
+        assert_eq!(stats.highest_score, best);
+    }
+    
+    #[test]
+    fn test_saved_rng_resumes_exact_sequence() {
+        let mut uninterrupted = ProceduralGenerator::new(2024);
+        let mut saved = ProceduralGenerator::new(2024);
+        for generator in [&mut uninterrupted, &mut saved] {
+            generator.generate_zone(ZoneType::Sky, 1);
+            generator.generate_wave(ZoneType::Ocean, 0.7);
+        }
+        
+        let mut run = RunState::new(2024, AircraftType::Spitfire);
+        run.save_generator(&saved);
+        let json = serde_json::to_string(&run).unwrap();
+        let loaded: RunState = serde_json::from_str(&json).unwrap();
+        let mut resumed = loaded.resume_generator(&BalanceConfig::default());
+        
+        assert_eq!(resumed.rng_state(), uninterrupted.rng_state());
+        for zone_number in 2..4 {
+            assert_eq!(
+                resumed.generate_zone(ZoneType::Desert, zone_number),
+                uninterrupted.generate_zone(ZoneType::Desert, zone_number)
+            );
+        }
+        assert_eq!(
+            resumed.generate_wave(ZoneType::Mountains, 0.9),
+            uninterrupted.generate_wave(ZoneType::Mountains, 0.9)
+        );
+        
+        // A save without an RNG position starts over from the seed
+        let fresh =
+            RunState::new(2024, AircraftType::Spitfire).resume_generator(&BalanceConfig::default());
+        assert_eq!(fresh.rng_state().words_drawn, 0);
+        
+        // Tuning comes from the balance passed in, not the save
+        let mut balance = BalanceConfig::default();
+        balance.waves.special_event_chance = 1.0;
+        let mut eventful = loaded.resume_generator(&balance);
+        assert_eq!(eventful.generate_zone(ZoneType::Sky, 5).events.len(), 1);
+    }
+    
+    #[test]
+    fn test_run_phase_flow() {
+        let mut run = RunState::new(1, AircraftType::Spitfire);
+        assert_eq!(run.phase, RunPhase::InZone);
+        assert!(run.can_spawn());
+        
+        // Picking before the zone is cleared does nothing
+        assert!(!run.choose_upgrade(UpgradeId(1)));
+        
+        run.apply_event(&GameEvent::ZoneCleared { zone_number: 0 });
+        assert_eq!(run.phase, RunPhase::ChoosingUpgrade);
+        assert!(!run.can_spawn());
+        
+        // A duplicate clear doesn't skip the choice
+        run.apply_event(&GameEvent::ZoneCleared { zone_number: 0 });
+        assert_eq!(run.phase, RunPhase::ChoosingUpgrade);
+        
+        assert!(run.choose_upgrade(UpgradeId(1)));
+        assert_eq!(run.zone, 1);
+        assert_eq!(run.upgrades, vec![UpgradeId(1)]);
+        assert_eq!(run.phase, RunPhase::ZoneTransition);
+        assert!(!run.can_spawn());
+        
+        assert!(run.enter_zone());
+        assert_eq!(run.phase, RunPhase::InZone);
+        assert!(run.can_spawn());
+    }
+    
+    #[test]
+    fn test_unknown_enum_variants_fall_back() {
+        let enemies: Vec<EnemyType> = serde_json::from_str(r#"["Ace", "StealthBomber"]"#).unwrap();
+        assert_eq!(enemies, vec![EnemyType::Ace, EnemyType::Unknown]);
         
-        stats.update_from_run(&run);
-        assert_eq!(stats.highest_score, 1000);
+        let aircraft: AircraftType = serde_json::from_str(r#""JetFighter""#).unwrap();
+        assert_eq!(aircraft, AircraftType::Unknown);
+        
+        let zone: ZoneType = serde_json::from_str(r#""Volcano""#).unwrap();
+        assert_eq!(zone, ZoneType::Unknown);
+        
+        let rarity: Rarity = serde_json::from_str(r#""Mythic""#).unwrap();
+        assert_eq!(rarity, Rarity::Unknown);
+    }
+    
+    #[test]
+    fn test_save_with_unknown_aircraft_still_loads() {
+        let mut state = GameState::new();
+        state.current_run = Some(RunState::new(7, AircraftType::Corsair));
+        let json = state
+            .serialize_to_json()
+            .unwrap()
+            .replace("Corsair", "Skyhawk");
+        
+        let restored = GameState::deserialize_from_json(&json).unwrap();
+        assert_eq!(
+            restored.current_run.unwrap().aircraft,
+            AircraftType::Unknown
+        );
+        assert_eq!(restored.meta_progression, state.meta_progression);
     }
     
     #[test]
@@ -316,6 +871,52 @@ mod tests {
         assert_eq!(state, restored);
     }
     
+    #[cfg(feature = "binary-saves")]
+    #[test]
+    fn test_binary_serialization_cycle() {
+        let mut state = GameState::new();
+        let mut run = RunState::new(42, AircraftType::Mustang);
+        run.generator_rng = Some(RngState {
+            seed: 42,
+            words_drawn: 1234,
+        });
+        state.current_run = Some(run);
+        state.meta_progression.add_xp(500);
+        state.statistics = GameStatistics::from_events(&sample_events());
+        
+        let bytes = state.serialize_to_bytes().unwrap();
+        let restored = GameState::deserialize_from_bytes(&bytes).unwrap();
+        assert_eq!(state, restored);
+        
+        let json = state.serialize_to_json().unwrap();
+        assert!(bytes.len() < json.len());
+    }
+    
+    #[cfg(feature = "binary-saves")]
+    #[test]
+    fn test_binary_save_header_mismatch_rejected() {
+        let mut bytes = GameState::new().serialize_to_bytes().unwrap();
+        assert_eq!(&bytes[..3], BINARY_SAVE_MAGIC);
+        assert_eq!(bytes[3], BINARY_SAVE_VERSION);
+        
+        bytes[3] = BINARY_SAVE_VERSION + 1;
+        assert!(matches!(
+            GameState::deserialize_from_bytes(&bytes),
+            Err(BinarySaveError::UnsupportedVersion(v)) if v == BINARY_SAVE_VERSION + 1
+        ));
+        
+        // Headerless bincode, as written before the header existed
+        let legacy = bincode::serialize(&GameState::new()).unwrap();
+        assert!(matches!(
+            GameState::deserialize_from_bytes(&legacy),
+            Err(BinarySaveError::NotASave)
+        ));
+        assert!(matches!(
+            GameState::deserialize_from_bytes(b"AH"),
+            Err(BinarySaveError::NotASave)
+        ));
+    }
+    
     #[wasm_bindgen_test]
     fn test_complete_serialization_cycle_wasm() {
         let mut state = GameState::new();
@@ -326,4 +927,65 @@ mod tests {
         
         assert_eq!(state, restored);
     }
+    
+    #[test]
+    fn test_parse_numeric_seeds() {
+        assert_eq!(parse_seed("12345"), 12345);
+        assert_eq!(parse_seed("  42 "), 42);
+        assert_eq!(parse_seed("0xff"), 255);
+        assert_eq!(parse_seed("0XDEADBEEF"), 0xDEAD_BEEF);
+    }
+    
+    #[test]
+    fn test_seed_code_round_trip() {
+        for seed in [0, 1, 12345, 0xDEAD_BEEF_CAFE, u64::MAX] {
+            let code = seed_to_code(seed);
+            assert_eq!(code.len(), 14);
+            assert_eq!(parse_seed(&code), seed, "{}", code);
+            assert_eq!(parse_seed(&code.to_lowercase()), seed);
+        }
+        assert_eq!(parse_seed("00000o-000000l"), 1);
+    }
+    
+    #[test]
+    fn test_share_string_round_trip() {
+        let mut run = RunState::new(0xDEAD_BEEF, AircraftType::Corsair);
+        run.zone = 4;
+        run.score = 12500;
+        run.upgrades = vec![UpgradeId(1), UpgradeId(7)];
+        
+        let shared = run.to_share_string();
+        assert!(shared.contains("zone 4"), "{}", shared);
+        assert!(shared.contains("12500 points"), "{}", shared);
+        assert!(shared.contains("Corsair"), "{}", shared);
+        
+        let restored = RunState::from_share_string(&shared).unwrap();
+        assert_eq!(restored.seed, run.seed);
+        assert_eq!(restored.aircraft, run.aircraft);
+        assert_eq!(restored.zone, 4);
+        assert_eq!(restored.score, 12500);
+        assert_eq!(restored.upgrades, run.upgrades);
+        
+        // The code alone is enough, and a run without upgrades round-trips
+        let fresh = RunState::new(9, AircraftType::Spitfire);
+        let shared = fresh.to_share_string();
+        let code = &shared[shared.find('[').unwrap() + 1..shared.len() - 1];
+        assert_eq!(RunState::from_share_string(code), Some(fresh));
+        
+        // A code naming an aircraft this build doesn't have is rejected
+        let renamed = code.replace("Spitfire", "Hurricane");
+        assert_eq!(RunState::from_share_string(&renamed), None);
+        
+        assert_eq!(RunState::from_share_string("Spitfire reached zone 2"), None);
+        assert_eq!(RunState::from_share_string("AH1:nonsense"), None);
+    }
+    
+    #[test]
+    fn test_arbitrary_text_seed_is_stable() {
+        let seed = parse_seed("red baron");
+        assert_eq!(seed, parse_seed("red baron"));
+        assert_eq!(seed, parse_seed(" red baron\n"));
+        assert_ne!(seed, parse_seed("blue baron"));
+        assert_ne!(parse_seed("0xZZ"), parse_seed("0xZY"));
+    }
 }
@@ -0,0 +1,213 @@
+use crate::game::systems::upgrade::{AbilityId, PlayerBuild, Stat};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Ability granted by the Shield Generator upgrade
+pub const SHIELD_ABILITY: AbilityId = AbilityId(1);
+/// Ability granted by the Evasive Maneuvers upgrade
+pub const DASH_ABILITY: AbilityId = AbilityId(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum AbilityEffect {
+    /// Burst of speed along the current heading, in units per second
+    Dash { impulse: f32 },
+    /// Absorbs up to `amount` damage for `duration` seconds
+    Shield { amount: i32, duration: f32 },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ability {
+    pub id: AbilityId,
+    /// Base cooldown in seconds, before the `AbilityCooldown` stat
+    pub cooldown: f32,
+    pub effect: AbilityEffect,
+}
+
+pub struct AbilitySystem {
+    abilities: HashMap<AbilityId, Ability>,
+    unlocked: HashSet<AbilityId>,
+    last_used: HashMap<AbilityId, f32>,
+    cooldown_modifier: f32,
+    pending_impulse: f32,
+    shield: i32,
+    shield_expires: f32,
+}
+
+impl AbilitySystem {
+    pub fn new() -> Self {
+        let mut system = Self {
+            abilities: HashMap::new(),
+            unlocked: HashSet::new(),
+            last_used: HashMap::new(),
+            cooldown_modifier: 1.0,
+            pending_impulse: 0.0,
+            shield: 0,
+            shield_expires: 0.0,
+        };
+
+        system.init_default_abilities();
+        system
+    }
+
+    fn init_default_abilities(&mut self) {
+        self.register(Ability {
+            id: SHIELD_ABILITY,
+            cooldown: 10.0,
+            effect: AbilityEffect::Shield {
+                amount: 50,
+                duration: 3.0,
+            },
+        });
+
+        self.register(Ability {
+            id: DASH_ABILITY,
+            cooldown: 2.0,
+            effect: AbilityEffect::Dash { impulse: 600.0 },
+        });
+    }
+
+    pub fn register(&mut self, ability: Ability) {
+        self.abilities.insert(ability.id, ability);
+    }
+
+    /// Makes an ability usable, e.g. when its `UnlockAbility` upgrade is taken
+    pub fn unlock(&mut self, id: AbilityId) {
+        self.unlocked.insert(id);
+    }
+
+    pub fn is_unlocked(&self, id: AbilityId) -> bool {
+        self.unlocked.contains(&id)
+    }
+
+    /// Picks up the player's `AbilityCooldown` stat
+    pub fn sync_with_build(&mut self, build: &PlayerBuild) {
+        self.cooldown_modifier = build.get_stat_modifier(Stat::AbilityCooldown).max(0.0);
+    }
+
+    /// Cooldown after stat modifiers, or `None` for an unregistered ability
+    pub fn effective_cooldown(&self, id: AbilityId) -> Option<f32> {
+        self.abilities
+            .get(&id)
+            .map(|ability| ability.cooldown * self.cooldown_modifier)
+    }
+
+    /// Seconds until the ability can be used again at time `now`
+    pub fn remaining_cooldown(&self, id: AbilityId, now: f32) -> f32 {
+        match (self.last_used.get(&id), self.effective_cooldown(id)) {
+            (Some(last_used), Some(cooldown)) => (last_used + cooldown - now).max(0.0),
+            _ => 0.0,
+        }
+    }
+
+    /// Activates an unlocked ability if it is off cooldown at time `now`
+    pub fn try_activate(&mut self, id: AbilityId, now: f32) -> bool {
+        if !self.is_unlocked(id) || self.remaining_cooldown(id, now) > 0.0 {
+            return false;
+        }
+        let effect = match self.abilities.get(&id) {
+            Some(ability) => ability.effect,
+            None => return false,
+        };
+
+        match effect {
+            AbilityEffect::Dash { impulse } => {
+                self.pending_impulse += impulse;
+            }
+            AbilityEffect::Shield { amount, duration } => {
+                self.shield = amount;
+                self.shield_expires = now + duration;
+            }
+        }
+
+        self.last_used.insert(id, now);
+        true
+    }
+
+    /// Dash impulse to add to the player's speed this tick, consumed on read
+    pub fn take_impulse(&mut self) -> Option<f32> {
+        if self.pending_impulse > 0.0 {
+            Some(std::mem::take(&mut self.pending_impulse))
+        } else {
+            None
+        }
+    }
+
+    /// Shield left at time `now`
+    pub fn shield(&self, now: f32) -> i32 {
+        if now < self.shield_expires {
+            self.shield
+        } else {
+            0
+        }
+    }
+
+    /// Soaks `damage` with the active shield and returns what gets through
+    pub fn absorb(&mut self, damage: i32, now: f32) -> i32 {
+        let absorbed = damage.min(self.shield(now)).max(0);
+        self.shield -= absorbed;
+        damage - absorbed
+    }
+}
+
+impl Default for AbilitySystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::systems::upgrade::Modifier;
+
+    #[test]
+    fn test_locked_ability_does_nothing() {
+        let mut system = AbilitySystem::new();
+        assert!(!system.try_activate(DASH_ABILITY, 0.0));
+        assert_eq!(system.take_impulse(), None);
+    }
+
+    #[test]
+    fn test_ability_respects_cooldown() {
+        let mut system = AbilitySystem::new();
+        system.unlock(DASH_ABILITY);
+
+        assert!(system.try_activate(DASH_ABILITY, 0.0));
+        assert_eq!(system.take_impulse(), Some(600.0));
+        assert_eq!(system.take_impulse(), None);
+
+        assert!(!system.try_activate(DASH_ABILITY, 1.0));
+        assert_eq!(system.remaining_cooldown(DASH_ABILITY, 1.0), 1.0);
+        assert!(system.try_activate(DASH_ABILITY, 2.0));
+    }
+
+    #[test]
+    fn test_cooldown_stat_shortens_wait() {
+        let mut build = PlayerBuild::new();
+        build.apply_stat_modifier(Stat::AbilityCooldown, Modifier::Multiply(0.5));
+
+        let mut system = AbilitySystem::new();
+        system.unlock(DASH_ABILITY);
+        system.sync_with_build(&build);
+
+        assert_eq!(system.effective_cooldown(DASH_ABILITY), Some(1.0));
+        assert!(system.try_activate(DASH_ABILITY, 0.0));
+        assert!(!system.try_activate(DASH_ABILITY, 0.5));
+        assert!(system.try_activate(DASH_ABILITY, 1.0));
+    }
+
+    #[test]
+    fn test_shield_absorbs_until_expiry() {
+        let mut system = AbilitySystem::new();
+        system.unlock(SHIELD_ABILITY);
+
+        assert!(system.try_activate(SHIELD_ABILITY, 0.0));
+        assert_eq!(system.absorb(30, 1.0), 0);
+        assert_eq!(system.shield(1.0), 20);
+        assert_eq!(system.absorb(30, 1.5), 10);
+
+        // Expired shields absorb nothing
+        system.try_activate(SHIELD_ABILITY, 10.0);
+        assert_eq!(system.absorb(30, 14.0), 30);
+    }
+}
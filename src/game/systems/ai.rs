@@ -1,20 +1,42 @@
 use crate::game::components::Position;
 use crate::game::entities::{Entity, EnemyType};
+use crate::game::systems::procedural::{Hazard, Obstacle};
+use crate::game::systems::weapon::SpreadPattern;
 use crate::utils::Vec2;
 use cgmath::InnerSpace;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
+
+/// Speed used when steering around a hazard
+const AVOIDANCE_SPEED: f32 = 180.0;
 
 pub struct AISystem {
     behavior_trees: HashMap<EnemyType, BehaviorTree>,
     enemy_states: HashMap<Entity, AIState>,
+    danger_zones: Vec<DangerZone>,
+    rng: StdRng,
 }
 
 impl AISystem {
     pub fn new() -> Self {
+        Self::with_rng(StdRng::from_entropy())
+    }
+
+    /// Creates a system whose randomness (aim jitter etc.) is fully
+    /// determined by `seed`, so seeded runs replay identically
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_rng(StdRng::seed_from_u64(seed))
+    }
+
+    fn with_rng(rng: StdRng) -> Self {
         let mut system = Self {
             behavior_trees: HashMap::new(),
             enemy_states: HashMap::new(),
+            danger_zones: Vec::new(),
+            rng,
         };
 
         // Initialize default behavior trees for each enemy type
@@ -76,13 +98,15 @@ impl AISystem {
             },
         );
 
-        // Heavy Bomber: formation flying
+        // Heavy Bomber: formation flying until the formation is broken
         self.behavior_trees.insert(
             EnemyType::HeavyBomber,
             BehaviorTree {
                 root: AIBehavior::Sequence(vec![
-                    AIBehavior::FormationFly {
+                    AIBehavior::HoldOrBreak {
                         pattern: FormationPattern::VFormation,
+                        break_below: 0.5,
+                        speed: 100.0,
                     },
                     AIBehavior::FireAtPlayer { accuracy: 0.5 },
                 ]),
@@ -98,46 +122,115 @@ impl AISystem {
                 state_timer: 0.0,
                 target_position: None,
                 formation_offset: Vec2::new(0.0, 0.0),
+                formation: FormationStatus::default(),
             },
         );
     }
 
+    /// Tells an enemy how much of its formation is still flying
+    pub fn set_formation_status(&mut self, entity: Entity, formation: FormationStatus) {
+        if let Some(state) = self.enemy_states.get_mut(&entity) {
+            state.formation = formation;
+        }
+    }
+
+    pub fn formation_status(&self, entity: Entity) -> Option<FormationStatus> {
+        self.enemy_states.get(&entity).map(|state| state.formation)
+    }
+
     pub fn unregister_enemy(&mut self, entity: Entity) {
         self.enemy_states.remove(&entity);
     }
 
+    pub fn is_registered(&self, entity: Entity) -> bool {
+        self.enemy_states.contains_key(&entity)
+    }
+
+    pub fn enemy_type(&self, entity: Entity) -> Option<EnemyType> {
+        self.enemy_states.get(&entity).map(|state| state.enemy_type)
+    }
+
+    /// Replaces the hazards and obstacles the AI steers around this frame
+    pub fn set_danger_zones(&mut self, zones: Vec<DangerZone>) {
+        self.danger_zones = zones;
+    }
+
+    /// Resolves the effective target from `targets` using `policy`, then
+    /// evaluates the enemy's behavior tree against it.
     pub fn update(
         &mut self,
         entity: Entity,
         position: &Position,
-        player_position: &Position,
+        targets: &[AITarget],
+        policy: TargetingPolicy,
         delta: f32,
     ) -> AICommand {
-        if let Some(state) = self.enemy_states.get_mut(&entity) {
-            state.state_timer += delta;
-
-            if let Some(behavior_tree) = self.behavior_trees.get(&state.enemy_type) {
-                let context = AIContext {
-                    entity,
-                    position: *position,
-                    player_position: *player_position,
-                    state,
-                    delta,
-                };
-
-                return self.execute_behavior(&behavior_tree.root, context);
-            }
+        let state = match self.enemy_states.get_mut(&entity) {
+            Some(state) => state,
+            None => return AICommand::None,
+        };
+        // Timed behaviors keep cycling while no target is around
+        state.state_timer += delta;
+
+        let target = match Self::select_target(policy, position, targets) {
+            Some(target) => *target,
+            None => return AICommand::None,
+        };
+        state.target_position = Some(target.position.as_vec2());
+        let enemy_type = state.enemy_type;
+
+        if let (Some(behavior_tree), Some(state)) = (
+            self.behavior_trees.get(&enemy_type),
+            self.enemy_states.get(&entity),
+        ) {
+            let context = AIContext {
+                entity,
+                position: *position,
+                target_position: target.position,
+                state,
+                dangers: &self.danger_zones,
+                delta,
+            };
+
+            return Self::execute_behavior(&behavior_tree.root, context, &mut self.rng);
         }
 
         AICommand::None
     }
 
-    fn execute_behavior(&self, behavior: &AIBehavior, context: AIContext) -> AICommand {
+    /// Runs `tree` against a hand-built context, bypassing entity and
+    /// target lookup. Aim jitter still draws from the system's RNG.
+    pub fn evaluate(&mut self, tree: &BehaviorTree, context: AIContext) -> AICommand {
+        Self::execute_behavior(&tree.root, context, &mut self.rng)
+    }
+
+    /// Picks the target an enemy at `position` should engage
+    pub fn select_target<'t>(
+        policy: TargetingPolicy,
+        position: &Position,
+        targets: &'t [AITarget],
+    ) -> Option<&'t AITarget> {
+        let distance_sq = |target: &AITarget| (target.position - *position).magnitude2();
+
+        match policy {
+            TargetingPolicy::Player => targets.iter().find(|target| target.is_player),
+            TargetingPolicy::Nearest => targets
+                .iter()
+                .min_by(|a, b| distance_sq(a).total_cmp(&distance_sq(b))),
+            TargetingPolicy::LowestHealth => targets.iter().min_by(|a, b| {
+                a.health
+                    .cmp(&b.health)
+                    .then_with(|| distance_sq(a).total_cmp(&distance_sq(b)))
+            }),
+        }
+    }
+
+    fn execute_behavior(behavior: &AIBehavior, context: AIContext, rng: &mut StdRng) -> AICommand {
         match behavior {
             AIBehavior::Sequence(behaviors) => {
                 // Execute all behaviors in sequence, return first non-None
                 for behavior in behaviors {
-                    let result = self.execute_behavior(behavior, context);
+                    let result = Self::execute_behavior(behavior, context, rng);
                     if !matches!(result, AICommand::None) {
                         return result;
                     }
@@ -148,7 +241,7 @@ impl AISystem {
             AIBehavior::Selector(behaviors) => {
                 // Execute first successful behavior
                 for behavior in behaviors {
-                    let result = self.execute_behavior(behavior, context);
+                    let result = Self::execute_behavior(behavior, context, rng);
                     if !matches!(result, AICommand::None) {
                         return result;
                     }
@@ -160,7 +253,7 @@ impl AISystem {
                 // Execute all behaviors and combine results
                 let mut commands = Vec::new();
                 for behavior in behaviors {
-                    let result = self.execute_behavior(behavior, context);
+                    let result = Self::execute_behavior(behavior, context, rng);
                     if !matches!(result, AICommand::None) {
                         commands.push(result);
                     }
@@ -174,8 +267,7 @@ impl AISystem {
             }
 
             AIBehavior::MoveToPlayer { speed } => {
-                let direction = (context.player_position.as_vec2() - context.position.as_vec2())
-                    .normalize();
+                let direction = (context.target_position - context.position).normalize();
                 AICommand::Move {
                     direction,
                     speed: *speed,
@@ -183,7 +275,7 @@ impl AISystem {
             }
 
             AIBehavior::CircleStrafe { radius, speed } => {
-                let to_player = context.player_position.as_vec2() - context.position.as_vec2();
+                let to_player = context.target_position - context.position;
                 let distance = to_player.magnitude();
 
                 if distance < *radius * 0.8 {
@@ -209,14 +301,13 @@ impl AISystem {
             }
 
             AIBehavior::FireAtPlayer { accuracy } => {
-                let direction = (context.player_position.as_vec2() - context.position.as_vec2())
-                    .normalize();
+                let direction = (context.target_position - context.position).normalize();
 
                 // Add inaccuracy
                 let inaccuracy = (1.0 - accuracy) * 0.5;
                 let random_offset = Vec2::new(
-                    (rand::random::<f32>() - 0.5) * inaccuracy,
-                    (rand::random::<f32>() - 0.5) * inaccuracy,
+                    (rng.gen::<f32>() - 0.5) * inaccuracy,
+                    (rng.gen::<f32>() - 0.5) * inaccuracy,
                 );
 
                 AICommand::Fire {
@@ -227,8 +318,7 @@ impl AISystem {
             AIBehavior::Evade { duration } => {
                 if context.state.state_timer % (duration + 2.0) < *duration {
                     // Evade by moving perpendicular to player
-                    let to_player =
-                        context.player_position.as_vec2() - context.position.as_vec2();
+                    let to_player = context.target_position - context.position;
                     let perpendicular = Vec2::new(-to_player.y, to_player.x).normalize();
                     let sign = if (context.state.state_timer as i32) % 2 == 0 {
                         1.0
@@ -245,23 +335,68 @@ impl AISystem {
                 }
             }
 
-            AIBehavior::FormationFly { pattern } => {
-                let target = self.calculate_formation_position(
-                    context.player_position.as_vec2(),
-                    context.state.formation_offset,
-                    pattern,
-                );
+            AIBehavior::FormationFly { pattern } => Self::fly_formation(context, pattern),
 
-                let direction = (target - context.position.as_vec2()).normalize();
+            AIBehavior::HoldOrBreak {
+                pattern,
+                break_below,
+                speed,
+            } => {
+                if context.state.formation.strength() >= *break_below {
+                    return Self::fly_formation(context, pattern);
+                }
+                let direction = (context.target_position - context.position).normalize();
                 AICommand::Move {
                     direction,
-                    speed: 120.0,
+                    speed: *speed,
+                }
+            }
+
+            AIBehavior::AvoidHazards { lookahead } => {
+                let heading = context.target_position - context.position;
+                if heading.magnitude2() <= f32::EPSILON {
+                    return AICommand::None;
+                }
+                let heading = heading.normalize();
+                let origin = context.position.as_vec2();
+
+                // Nearest danger whose radius the projected path enters
+                let threat = context
+                    .dangers
+                    .iter()
+                    .filter_map(|danger| {
+                        let along = (danger.position - origin)
+                            .dot(heading)
+                            .clamp(0.0, *lookahead);
+                        let closest = origin + heading * along;
+                        let clearance_sq = (danger.position - closest).magnitude2();
+                        if clearance_sq < danger.radius * danger.radius {
+                            Some((along, danger))
+                        } else {
+                            None
+                        }
+                    })
+                    .min_by(|a, b| a.0.total_cmp(&b.0));
+
+                match threat {
+                    Some((_, danger)) => {
+                        let perpendicular = Vec2::new(-heading.y, heading.x);
+                        let side = if perpendicular.dot(danger.position - origin) > 0.0 {
+                            -1.0
+                        } else {
+                            1.0
+                        };
+                        AICommand::Move {
+                            direction: perpendicular * side,
+                            speed: AVOIDANCE_SPEED,
+                        }
+                    }
+                    None => AICommand::None,
                 }
             }
 
             AIBehavior::KamikazeDive => {
-                let direction = (context.player_position.as_vec2() - context.position.as_vec2())
-                    .normalize();
+                let direction = (context.target_position - context.position).normalize();
                 AICommand::Move {
                     direction,
                     speed: 300.0,
@@ -270,12 +405,21 @@ impl AISystem {
         }
     }
 
-    fn calculate_formation_position(
-        &self,
-        base: Vec2,
-        offset: Vec2,
-        pattern: &FormationPattern,
-    ) -> Vec2 {
+    fn fly_formation(context: AIContext, pattern: &FormationPattern) -> AICommand {
+        let target = Self::calculate_formation_position(
+            context.target_position.as_vec2(),
+            context.state.formation_offset,
+            pattern,
+        );
+
+        let direction = (target - context.position.as_vec2()).normalize();
+        AICommand::Move {
+            direction,
+            speed: 120.0,
+        }
+    }
+
+    fn calculate_formation_position(base: Vec2, offset: Vec2, pattern: &FormationPattern) -> Vec2 {
         match pattern {
             FormationPattern::VFormation => base + Vec2::new(offset.x * 50.0, offset.y * -30.0),
             FormationPattern::Line => base + Vec2::new(offset.x * 80.0, 0.0),
@@ -296,12 +440,64 @@ impl Default for AISystem {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BehaviorTree {
     pub root: AIBehavior,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl BehaviorTree {
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// Loads a tree from data, rejecting malformed JSON and trees that
+    /// parse but can't run sensibly
+    pub fn from_json(json: &str) -> Result<Self, BehaviorTreeError> {
+        let tree: BehaviorTree = serde_json::from_str(json)?;
+        tree.validate()?;
+        Ok(tree)
+    }
+
+    pub fn validate(&self) -> Result<(), BehaviorTreeError> {
+        self.root.validate()
+    }
+}
+
+#[derive(Debug)]
+pub enum BehaviorTreeError {
+    Json(serde_json::Error),
+    /// A sequence, selector or parallel node with no children
+    EmptyComposite,
+    InvalidParameter {
+        node: &'static str,
+        parameter: &'static str,
+        value: f32,
+    },
+}
+
+impl fmt::Display for BehaviorTreeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BehaviorTreeError::Json(error) => write!(f, "invalid behavior tree JSON: {}", error),
+            BehaviorTreeError::EmptyComposite => write!(f, "composite node has no children"),
+            BehaviorTreeError::InvalidParameter {
+                node,
+                parameter,
+                value,
+            } => write!(f, "{} has invalid {} {}", node, parameter, value),
+        }
+    }
+}
+
+impl std::error::Error for BehaviorTreeError {}
+
+impl From<serde_json::Error> for BehaviorTreeError {
+    fn from(error: serde_json::Error) -> Self {
+        BehaviorTreeError::Json(error)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum AIBehavior {
     // Composite nodes
     Sequence(Vec<AIBehavior>),
@@ -314,10 +510,70 @@ pub enum AIBehavior {
     FireAtPlayer { accuracy: f32 },
     Evade { duration: f32 },
     FormationFly { pattern: FormationPattern },
+    /// Flies `pattern` while at least `break_below` of the formation is
+    /// alive, then breaks off and pursues the target alone at `speed`
+    HoldOrBreak { pattern: FormationPattern, break_below: f32, speed: f32 },
     KamikazeDive,
+    AvoidHazards { lookahead: f32 },
+}
+
+impl AIBehavior {
+    /// Checks this node and its children: composites need children, and
+    /// parameters must be finite and in range
+    pub fn validate(&self) -> Result<(), BehaviorTreeError> {
+        let check = |node, parameter, value: f32, valid: bool| {
+            if value.is_finite() && valid {
+                Ok(())
+            } else {
+                Err(BehaviorTreeError::InvalidParameter {
+                    node,
+                    parameter,
+                    value,
+                })
+            }
+        };
+
+        match self {
+            AIBehavior::Sequence(children)
+            | AIBehavior::Selector(children)
+            | AIBehavior::Parallel(children) => {
+                if children.is_empty() {
+                    return Err(BehaviorTreeError::EmptyComposite);
+                }
+                children.iter().try_for_each(AIBehavior::validate)
+            }
+            AIBehavior::MoveToPlayer { speed } => {
+                check("MoveToPlayer", "speed", *speed, *speed >= 0.0)
+            }
+            AIBehavior::CircleStrafe { radius, speed } => {
+                check("CircleStrafe", "radius", *radius, *radius > 0.0)?;
+                check("CircleStrafe", "speed", *speed, *speed >= 0.0)
+            }
+            AIBehavior::FireAtPlayer { accuracy } => check(
+                "FireAtPlayer",
+                "accuracy",
+                *accuracy,
+                (0.0..=1.0).contains(accuracy),
+            ),
+            AIBehavior::Evade { duration } => {
+                check("Evade", "duration", *duration, *duration >= 0.0)
+            }
+            AIBehavior::AvoidHazards { lookahead } => {
+                check("AvoidHazards", "lookahead", *lookahead, *lookahead >= 0.0)
+            }
+            AIBehavior::HoldOrBreak {
+                break_below, speed, ..
+            } => {
+                let fraction = (0.0..=1.0).contains(break_below);
+                check("HoldOrBreak", "break_below", *break_below, fraction)?;
+                check("HoldOrBreak", "speed", *speed, *speed >= 0.0)
+            }
+            AIBehavior::FormationFly { .. } | AIBehavior::KamikazeDive => Ok(()),
+        }
+    }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum FormationPattern {
     VFormation,
     Line,
@@ -325,20 +581,115 @@ pub enum FormationPattern {
     Diamond,
 }
 
+/// How an enemy chooses between candidate targets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TargetingPolicy {
+    Nearest,
+    LowestHealth,
+    Player,
+}
+
+/// A candidate target (player, ally, decoy, turret...)
+#[derive(Debug, Clone, Copy)]
+pub struct AITarget {
+    pub entity: Entity,
+    pub position: Position,
+    pub health: i32,
+    pub is_player: bool,
+}
+
+impl AITarget {
+    pub fn new(entity: Entity, position: Position, health: i32) -> Self {
+        Self {
+            entity,
+            position,
+            health,
+            is_player: false,
+        }
+    }
+
+    pub fn player(entity: Entity, position: Position, health: i32) -> Self {
+        Self {
+            entity,
+            position,
+            health,
+            is_player: true,
+        }
+    }
+}
+
+/// A circular region enemies should steer around
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DangerZone {
+    pub position: Vec2,
+    pub radius: f32,
+}
+
+impl DangerZone {
+    pub fn new(position: Vec2, radius: f32) -> Self {
+        Self { position, radius }
+    }
+}
+
+impl From<&Hazard> for DangerZone {
+    fn from(hazard: &Hazard) -> Self {
+        Self::new(hazard.position, hazard.radius)
+    }
+}
+
+impl From<&Obstacle> for DangerZone {
+    fn from(obstacle: &Obstacle) -> Self {
+        // Bounding circle around the obstacle's box
+        let radius =
+            (obstacle.size.x * obstacle.size.x + obstacle.size.y * obstacle.size.y).sqrt() / 2.0;
+        Self::new(obstacle.position, radius)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct AIState {
     pub enemy_type: EnemyType,
     pub state_timer: f32,
     pub target_position: Option<Vec2>,
     pub formation_offset: Vec2,
+    pub formation: FormationStatus,
+}
+
+/// How many of an enemy's formation, itself included, are still alive.
+/// Enemies flying alone count as a full formation of one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormationStatus {
+    pub alive: u32,
+    pub size: u32,
+}
+
+impl FormationStatus {
+    pub fn new(alive: u32, size: u32) -> Self {
+        Self { alive, size }
+    }
+
+    /// Fraction of the formation still flying, from 0 to 1
+    pub fn strength(&self) -> f32 {
+        if self.size == 0 {
+            return 1.0;
+        }
+        (self.alive as f32 / self.size as f32).min(1.0)
+    }
+}
+
+impl Default for FormationStatus {
+    fn default() -> Self {
+        Self::new(1, 1)
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
 pub struct AIContext<'a> {
     pub entity: Entity,
     pub position: Position,
-    pub player_position: Position,
+    pub target_position: Position,
     pub state: &'a AIState,
+    pub dangers: &'a [DangerZone],
     pub delta: f32,
 }
 
@@ -347,6 +698,10 @@ pub enum AICommand {
     None,
     Move { direction: Vec2, speed: f32 },
     Fire { direction: Vec2 },
+    /// Fires a volley shaped by a weapon spread pattern
+    FirePattern { direction: Vec2, pattern: SpreadPattern },
+    /// Summons an enemy at an offset from the issuing entity
+    Spawn { enemy_type: EnemyType, offset: Vec2 },
     Multiple(Vec<AICommand>),
 }
 
@@ -364,6 +719,8 @@ pub enum Formation {
     V { spacing: f32 },
     Line { spacing: f32, angle: f32 },
     Circle { radius: f32 },
+    /// Positions spread along an arc; angles are in degrees
+    Arc { radius: f32, start_angle: f32, sweep: f32 },
     Diamond,
     Custom(Vec<Vec2>),
 }
@@ -424,6 +781,92 @@ impl Path {
 mod tests {
     use super::*;
 
+    fn every_node_tree() -> BehaviorTree {
+        let formations = [
+            FormationPattern::VFormation,
+            FormationPattern::Line,
+            FormationPattern::Circle,
+            FormationPattern::Diamond,
+        ];
+
+        BehaviorTree {
+            root: AIBehavior::Selector(vec![
+                AIBehavior::Sequence(vec![
+                    AIBehavior::Parallel(vec![
+                        AIBehavior::Selector(
+                            formations
+                                .iter()
+                                .map(|&pattern| AIBehavior::FormationFly { pattern })
+                                .collect(),
+                        ),
+                        AIBehavior::FireAtPlayer { accuracy: 0.73 },
+                        AIBehavior::AvoidHazards { lookahead: 0.35 },
+                        AIBehavior::HoldOrBreak {
+                            pattern: FormationPattern::Diamond,
+                            break_below: 0.25,
+                            speed: 180.0,
+                        },
+                    ]),
+                    AIBehavior::CircleStrafe {
+                        radius: 123.456,
+                        speed: 0.1,
+                    },
+                ]),
+                AIBehavior::Parallel(vec![
+                    AIBehavior::Evade {
+                        duration: 1.0 / 3.0,
+                    },
+                    AIBehavior::MoveToPlayer { speed: 150.0 },
+                ]),
+                AIBehavior::KamikazeDive,
+            ]),
+        }
+    }
+
+    #[test]
+    fn test_behavior_tree_json_round_trip() {
+        let tree = every_node_tree();
+        let json = tree.to_json().unwrap();
+        let restored = BehaviorTree::from_json(&json).unwrap();
+        assert_eq!(restored, tree);
+
+        // The default trees are data-loadable too
+        let system = AISystem::new();
+        for tree in system.behavior_trees.values() {
+            let json = tree.to_json().unwrap();
+            assert_eq!(&BehaviorTree::from_json(&json).unwrap(), tree);
+        }
+    }
+
+    #[test]
+    fn test_behavior_tree_validation() {
+        assert!(matches!(
+            BehaviorTree::from_json("{\"root\": {\"Sequence\": []}}"),
+            Err(BehaviorTreeError::EmptyComposite)
+        ));
+        assert!(matches!(
+            BehaviorTree::from_json("{\"root\": {\"FireAtPlayer\": {\"accuracy\": 1.5}}}"),
+            Err(BehaviorTreeError::InvalidParameter {
+                parameter: "accuracy",
+                ..
+            })
+        ));
+        assert!(matches!(
+            BehaviorTree::from_json("{\"root\": \"Teleport\"}"),
+            Err(BehaviorTreeError::Json(_))
+        ));
+
+        let nested_bad = BehaviorTree {
+            root: AIBehavior::Selector(vec![AIBehavior::Sequence(vec![
+                AIBehavior::CircleStrafe {
+                    radius: 0.0,
+                    speed: 10.0,
+                },
+            ])]),
+        };
+        assert!(nested_bad.validate().is_err());
+    }
+
     #[test]
     fn test_ai_system_creation() {
         let ai_system = AISystem::new();
@@ -442,6 +885,330 @@ mod tests {
         assert!(!ai_system.enemy_states.contains_key(&entity));
     }
 
+    fn move_direction(command: &AICommand) -> Vec2 {
+        match command {
+            AICommand::Move { direction, .. } => *direction,
+            other => panic!("expected move command, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_nearest_targeting() {
+        let mut ai_system = AISystem::new();
+        let entity = Entity::new(1);
+        ai_system.register_enemy(entity, EnemyType::Fighter);
+
+        let targets = [
+            AITarget::player(Entity::new(10), Position::new(0.0, 500.0), 100),
+            AITarget::new(Entity::new(11), Position::new(100.0, 0.0), 50),
+        ];
+
+        let selected =
+            AISystem::select_target(TargetingPolicy::Nearest, &Position::new(0.0, 0.0), &targets);
+        assert_eq!(selected.unwrap().entity, Entity::new(11));
+
+        let command = ai_system.update(
+            entity,
+            &Position::new(0.0, 0.0),
+            &targets,
+            TargetingPolicy::Nearest,
+            0.016,
+        );
+        let direction = move_direction(&command);
+        assert!(direction.x > 0.99);
+    }
+
+    #[test]
+    fn test_player_targeting_ignores_decoys() {
+        let mut ai_system = AISystem::new();
+        let entity = Entity::new(1);
+        ai_system.register_enemy(entity, EnemyType::Fighter);
+
+        let targets = [
+            AITarget::new(Entity::new(11), Position::new(10.0, 0.0), 1),
+            AITarget::player(Entity::new(10), Position::new(0.0, 500.0), 100),
+        ];
+
+        let command = ai_system.update(
+            entity,
+            &Position::new(0.0, 0.0),
+            &targets,
+            TargetingPolicy::Player,
+            0.016,
+        );
+        let direction = move_direction(&command);
+        assert!(direction.y > 0.99);
+
+        let selected = AISystem::select_target(
+            TargetingPolicy::LowestHealth,
+            &Position::new(0.0, 0.0),
+            &targets,
+        );
+        assert_eq!(selected.unwrap().entity, Entity::new(11));
+    }
+
+    #[test]
+    fn test_no_targets_yields_no_command() {
+        let mut ai_system = AISystem::new();
+        let entity = Entity::new(1);
+        ai_system.register_enemy(entity, EnemyType::Fighter);
+
+        let command = ai_system.update(
+            entity,
+            &Position::new(0.0, 0.0),
+            &[],
+            TargetingPolicy::Nearest,
+            0.016,
+        );
+        assert!(matches!(command, AICommand::None));
+
+        // The state timer still runs so timed behaviors don't freeze
+        assert_eq!(ai_system.enemy_states[&entity].state_timer, 0.016);
+    }
+
+    /// Evaluates `root` for an enemy at the origin facing a target straight
+    /// ahead, `state_timer` seconds into its current state
+    fn evaluate_at(root: AIBehavior, state_timer: f32) -> AICommand {
+        let state = AIState {
+            enemy_type: EnemyType::Fighter,
+            state_timer,
+            target_position: None,
+            formation_offset: Vec2::new(0.0, 0.0),
+            formation: FormationStatus::default(),
+        };
+        evaluate_with(root, &state)
+    }
+
+    fn evaluate_with(root: AIBehavior, state: &AIState) -> AICommand {
+        let mut ai_system = AISystem::with_seed(0);
+        let context = AIContext {
+            entity: Entity::new(1),
+            position: Position::new(0.0, 0.0),
+            target_position: Position::new(0.0, 500.0),
+            state,
+            dangers: &[],
+            delta: 0.016,
+        };
+
+        ai_system.evaluate(&BehaviorTree { root }, context)
+    }
+
+    #[test]
+    fn test_sequence_returns_first_command() {
+        // With no dangers, hazard avoidance yields nothing and is skipped
+        let command = evaluate_at(
+            AIBehavior::Sequence(vec![
+                AIBehavior::AvoidHazards { lookahead: 200.0 },
+                AIBehavior::MoveToPlayer { speed: 150.0 },
+                AIBehavior::FireAtPlayer { accuracy: 1.0 },
+            ]),
+            0.0,
+        );
+        assert!(matches!(command, AICommand::Move { speed, .. } if speed == 150.0));
+
+        let idle = AIBehavior::Sequence(vec![AIBehavior::AvoidHazards { lookahead: 200.0 }]);
+        assert!(matches!(evaluate_at(idle, 0.0), AICommand::None));
+    }
+
+    #[test]
+    fn test_selector_falls_through_to_first_success() {
+        let command = evaluate_at(
+            AIBehavior::Selector(vec![
+                AIBehavior::Evade { duration: 1.0 },
+                AIBehavior::KamikazeDive,
+                AIBehavior::MoveToPlayer { speed: 150.0 },
+            ]),
+            // Evade's off phase
+            1.5,
+        );
+        assert!(matches!(command, AICommand::Move { speed, .. } if speed == 300.0));
+        assert!((move_direction(&command) - Vec2::new(0.0, 1.0)).magnitude() < 0.001);
+
+        let idle = AIBehavior::Selector(vec![
+            AIBehavior::Evade { duration: 1.0 },
+            AIBehavior::AvoidHazards { lookahead: 200.0 },
+        ]);
+        assert!(matches!(evaluate_at(idle, 1.5), AICommand::None));
+    }
+
+    #[test]
+    fn test_parallel_combines_commands() {
+        let command = evaluate_at(
+            AIBehavior::Parallel(vec![
+                AIBehavior::MoveToPlayer { speed: 150.0 },
+                AIBehavior::AvoidHazards { lookahead: 200.0 },
+                AIBehavior::FireAtPlayer { accuracy: 1.0 },
+            ]),
+            0.0,
+        );
+        let commands = match command {
+            AICommand::Multiple(commands) => commands,
+            other => panic!("expected multiple commands, got {:?}", other),
+        };
+        assert_eq!(commands.len(), 2);
+        assert!(matches!(commands[0], AICommand::Move { .. }));
+        match commands[1] {
+            // Perfect accuracy aims straight at the target
+            AICommand::Fire { direction } => {
+                assert!((direction - Vec2::new(0.0, 1.0)).magnitude() < 0.001)
+            }
+            ref other => panic!("expected fire command, got {:?}", other),
+        }
+
+        let idle = AIBehavior::Parallel(vec![AIBehavior::AvoidHazards { lookahead: 200.0 }]);
+        assert!(matches!(evaluate_at(idle, 0.0), AICommand::None));
+    }
+
+    #[test]
+    fn test_evade_toggles_over_its_cycle() {
+        // Evades for `duration`, then rests for two seconds
+        let evade = || AIBehavior::Evade { duration: 1.0 };
+
+        let first = evaluate_at(evade(), 0.5);
+        assert!(matches!(first, AICommand::Move { speed, .. } if speed == 250.0));
+        assert!(matches!(evaluate_at(evade(), 1.5), AICommand::None));
+        assert!(matches!(evaluate_at(evade(), 2.9), AICommand::None));
+
+        // The next cycle evades again, sidestepping the other way on odd seconds
+        let second = evaluate_at(evade(), 3.5);
+        let (a, b) = (move_direction(&first), move_direction(&second));
+        assert!(a.x.abs() > 0.99 && a.y.abs() < 0.001);
+        assert!((a + b).magnitude() < 0.001);
+    }
+
+    #[test]
+    fn test_formation_breaks_when_mostly_destroyed() {
+        let hold_or_break = || AIBehavior::HoldOrBreak {
+            pattern: FormationPattern::Line,
+            break_below: 0.5,
+            speed: 200.0,
+        };
+        let mut state = AIState {
+            enemy_type: EnemyType::HeavyBomber,
+            state_timer: 0.0,
+            target_position: None,
+            formation_offset: Vec2::new(1.0, 0.0),
+            formation: FormationStatus::new(4, 4),
+        };
+
+        // Full group: hold the slot beside the target
+        let command = evaluate_with(hold_or_break(), &state);
+        let slot = Vec2::new(80.0, 500.0).normalize();
+        assert!(matches!(command, AICommand::Move { speed, .. } if speed == 120.0));
+        assert!((move_direction(&command) - slot).magnitude() < 0.001);
+
+        state.formation = FormationStatus::new(2, 4);
+        let command = evaluate_with(hold_or_break(), &state);
+        assert!(matches!(command, AICommand::Move { speed, .. } if speed == 120.0));
+
+        // Sole survivor: break off and go straight for the target
+        state.formation = FormationStatus::new(1, 4);
+        let command = evaluate_with(hold_or_break(), &state);
+        assert!(matches!(command, AICommand::Move { speed, .. } if speed == 200.0));
+        assert!((move_direction(&command) - Vec2::new(0.0, 1.0)).magnitude() < 0.001);
+    }
+
+    #[test]
+    fn test_formation_status_reaches_state() {
+        let mut ai_system = AISystem::new();
+        let entity = Entity::new(1);
+        ai_system.register_enemy(entity, EnemyType::HeavyBomber);
+        assert_eq!(ai_system.enemy_states[&entity].formation.strength(), 1.0);
+
+        ai_system.set_formation_status(entity, FormationStatus::new(1, 5));
+        assert_eq!(ai_system.enemy_states[&entity].formation.strength(), 0.2);
+    }
+
+    fn avoidance_command(dangers: &[DangerZone]) -> AICommand {
+        let mut rng = StdRng::seed_from_u64(0);
+        let state = AIState {
+            enemy_type: EnemyType::Fighter,
+            state_timer: 0.0,
+            target_position: None,
+            formation_offset: Vec2::new(0.0, 0.0),
+            formation: FormationStatus::default(),
+        };
+        let context = AIContext {
+            entity: Entity::new(1),
+            position: Position::new(0.0, 0.0),
+            target_position: Position::new(0.0, 500.0),
+            state: &state,
+            dangers,
+            delta: 0.016,
+        };
+
+        AISystem::execute_behavior(
+            &AIBehavior::AvoidHazards { lookahead: 200.0 },
+            context,
+            &mut rng,
+        )
+    }
+
+    #[test]
+    fn test_avoid_hazard_in_path() {
+        let dangers = [DangerZone::new(Vec2::new(10.0, 100.0), 50.0)];
+        let direction = move_direction(&avoidance_command(&dangers));
+
+        // Lateral steer, away from the side the hazard is on
+        assert!(direction.y.abs() < 0.001);
+        assert!(direction.x < -0.99);
+    }
+
+    #[test]
+    fn test_avoid_hazard_clear_path() {
+        let dangers = [
+            DangerZone::new(Vec2::new(300.0, 100.0), 50.0),
+            // Directly ahead but beyond the lookahead distance
+            DangerZone::new(Vec2::new(0.0, 400.0), 50.0),
+        ];
+
+        assert!(matches!(avoidance_command(&dangers), AICommand::None));
+    }
+
+    fn collect_fire(command: &AICommand, out: &mut Vec<Vec2>) {
+        match command {
+            AICommand::Fire { direction } => out.push(*direction),
+            AICommand::Multiple(commands) => {
+                for command in commands {
+                    collect_fire(command, out);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn fire_directions(seed: u64) -> Vec<Vec2> {
+        let mut ai_system = AISystem::with_seed(seed);
+        let entity = Entity::new(1);
+        ai_system.register_enemy(entity, EnemyType::Ace);
+        let player = Position::new(0.0, 200.0);
+        let targets = [AITarget::player(Entity::new(10), player, 100)];
+
+        let mut directions = Vec::new();
+        for _ in 0..10 {
+            let command = ai_system.update(
+                entity,
+                &Position::new(0.0, 0.0),
+                &targets,
+                TargetingPolicy::Player,
+                0.016,
+            );
+            collect_fire(&command, &mut directions);
+        }
+        directions
+    }
+
+    #[test]
+    fn test_seeded_fire_is_deterministic() {
+        let first = fire_directions(42);
+        let second = fire_directions(42);
+        assert_eq!(first.len(), 10);
+        assert_eq!(first, second);
+
+        // Jitter actually varies shot to shot
+        assert!(first.windows(2).any(|pair| pair[0] != pair[1]));
+    }
+
     #[test]
     fn test_path_linear() {
         let path = Path::new(vec![
@@ -0,0 +1,212 @@
+//! Scripted boss fights: timed attack patterns split into health phases
+
+use crate::game::components::Health;
+use crate::game::systems::ai::AICommand;
+
+/// A command issued at a fixed point in a phase's timeline
+#[derive(Debug, Clone)]
+pub struct TimedAction {
+    /// Seconds after the start of the phase's current cycle
+    pub time: f32,
+    pub command: AICommand,
+}
+
+impl TimedAction {
+    pub fn new(time: f32, command: AICommand) -> Self {
+        Self { time, command }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Phase {
+    /// The phase starts once health drops to this fraction of max
+    pub health_threshold: f32,
+    pub pattern: Vec<TimedAction>,
+    /// Seconds before the pattern repeats; zero plays it once. Actions timed
+    /// after the cycle never fire.
+    pub cycle: f32,
+}
+
+impl Phase {
+    pub fn new(health_threshold: f32, mut pattern: Vec<TimedAction>, cycle: f32) -> Self {
+        pattern.sort_by(|a, b| a.time.total_cmp(&b.time));
+        Self {
+            health_threshold,
+            pattern,
+            cycle,
+        }
+    }
+}
+
+/// Plays a boss's phases in order of descending health threshold. The first
+/// phase runs from the start; each later one takes over when health falls to
+/// its threshold and restarts the timeline. Phases never revert on healing.
+#[derive(Debug, Clone)]
+pub struct BossScript {
+    phases: Vec<Phase>,
+    current: usize,
+    elapsed: f32,
+    next_action: usize,
+}
+
+impl BossScript {
+    pub fn new(mut phases: Vec<Phase>) -> Self {
+        phases.sort_by(|a, b| b.health_threshold.total_cmp(&a.health_threshold));
+        Self {
+            phases,
+            current: 0,
+            elapsed: 0.0,
+            next_action: 0,
+        }
+    }
+
+    pub fn current_phase(&self) -> usize {
+        self.current
+    }
+
+    pub fn phase_count(&self) -> usize {
+        self.phases.len()
+    }
+
+    /// Advances the timeline by `delta` and returns the commands that came
+    /// due, in timeline order
+    pub fn update(&mut self, health: &Health, delta: f32) -> Vec<AICommand> {
+        self.advance_phase(health.fraction());
+
+        let phase = match self.phases.get(self.current) {
+            Some(phase) => phase,
+            None => return Vec::new(),
+        };
+
+        self.elapsed += delta;
+        let mut commands = Vec::new();
+        loop {
+            while let Some(action) = phase.pattern.get(self.next_action) {
+                if action.time > self.elapsed {
+                    break;
+                }
+                commands.push(action.command.clone());
+                self.next_action += 1;
+            }
+
+            if phase.cycle <= 0.0 || self.elapsed < phase.cycle {
+                break;
+            }
+            self.elapsed -= phase.cycle;
+            self.next_action = 0;
+        }
+
+        commands
+    }
+
+    /// Moves to the last phase whose threshold has been crossed. Returns
+    /// whether the phase changed.
+    fn advance_phase(&mut self, health_fraction: f32) -> bool {
+        let mut next = self.current;
+        while self
+            .phases
+            .get(next + 1)
+            .is_some_and(|phase| health_fraction <= phase.health_threshold)
+        {
+            next += 1;
+        }
+
+        if next == self.current {
+            return false;
+        }
+        self.current = next;
+        self.elapsed = 0.0;
+        self.next_action = 0;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::entities::EnemyType;
+    use crate::game::systems::weapon::SpreadPattern;
+    use crate::utils::Vec2;
+
+    fn sweep() -> AICommand {
+        AICommand::Move {
+            direction: Vec2::new(1.0, 0.0),
+            speed: 120.0,
+        }
+    }
+
+    fn barrage() -> AICommand {
+        AICommand::FirePattern {
+            direction: Vec2::new(0.0, 1.0),
+            pattern: SpreadPattern::Spread {
+                count: 5,
+                angle: 60.0,
+            },
+        }
+    }
+
+    fn summon() -> AICommand {
+        AICommand::Spawn {
+            enemy_type: EnemyType::Fighter,
+            offset: Vec2::new(0.0, -40.0),
+        }
+    }
+
+    fn script() -> BossScript {
+        BossScript::new(vec![
+            Phase::new(0.5, vec![TimedAction::new(0.5, summon())], 0.0),
+            Phase::new(
+                1.0,
+                vec![
+                    TimedAction::new(1.0, barrage()),
+                    TimedAction::new(0.0, sweep()),
+                ],
+                2.0,
+            ),
+        ])
+    }
+
+    fn health_at(current: i32) -> Health {
+        let mut health = Health::new(100);
+        health.current = current;
+        health
+    }
+
+    #[test]
+    fn test_actions_fire_on_timeline() {
+        let mut script = script();
+        let health = health_at(100);
+
+        let commands = script.update(&health, 0.5);
+        assert!(matches!(commands.as_slice(), [AICommand::Move { .. }]));
+        assert!(script.update(&health, 0.4).is_empty());
+
+        let commands = script.update(&health, 0.2);
+        assert!(matches!(
+            commands.as_slice(),
+            [AICommand::FirePattern { .. }]
+        ));
+
+        // The cycle wraps and the sweep comes round again
+        let commands = script.update(&health, 1.0);
+        assert!(matches!(commands.as_slice(), [AICommand::Move { .. }]));
+        assert_eq!(script.current_phase(), 0);
+    }
+
+    #[test]
+    fn test_phase_advances_when_health_drops() {
+        let mut script = script();
+        script.update(&health_at(100), 0.8);
+
+        let wounded = health_at(40);
+        assert!(script.update(&wounded, 0.25).is_empty());
+        assert_eq!(script.current_phase(), 1);
+
+        let commands = script.update(&wounded, 0.25);
+        assert!(matches!(commands.as_slice(), [AICommand::Spawn { .. }]));
+
+        // Non-repeating phases go quiet, and healing does not revert them
+        assert!(script.update(&health_at(100), 5.0).is_empty());
+        assert_eq!(script.current_phase(), 1);
+    }
+}
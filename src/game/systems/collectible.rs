@@ -0,0 +1,358 @@
+use crate::game::components::Health;
+use crate::game::state::RunState;
+use crate::game::systems::procedural::{Collectible, CollectibleType};
+use crate::game::systems::upgrade::PlayerBuild;
+use crate::game::systems::weapon::WeaponSystem;
+use crate::utils::Vec2;
+use cgmath::InnerSpace;
+
+/// Distance at which a collectible counts as picked up
+pub const COLLECT_DISTANCE: f32 = 8.0;
+
+/// Seconds of power-up buff granted per point of a PowerUp's value
+pub const POWER_UP_SECONDS_PER_VALUE: f32 = 0.5;
+
+/// A collectible in play, with the speed it has built up while being pulled
+/// toward the player
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActiveCollectible {
+    pub collectible: Collectible,
+    pub speed: f32,
+}
+
+impl ActiveCollectible {
+    pub fn new(collectible: Collectible) -> Self {
+        Self {
+            collectible,
+            speed: 0.0,
+        }
+    }
+}
+
+/// Pulls collectibles inside the pickup radius toward the player, speeding
+/// them up every tick. Collectibles that reach the player are removed and
+/// returned for collection.
+pub fn update_collectibles(
+    collectibles: &mut Vec<ActiveCollectible>,
+    player_pos: Vec2,
+    pickup_radius: f32,
+    magnet_strength: f32,
+    delta: f32,
+) -> Vec<Collectible> {
+    let mut collected = Vec::new();
+
+    let mut i = 0;
+    while i < collectibles.len() {
+        let active = &mut collectibles[i];
+        let to_player = player_pos - active.collectible.position;
+        let distance = to_player.magnitude();
+
+        if distance > pickup_radius {
+            active.speed = 0.0;
+            i += 1;
+            continue;
+        }
+
+        if distance > COLLECT_DISTANCE {
+            active.speed += magnet_strength * delta;
+            let step = (active.speed * delta).min(distance);
+            active.collectible.position += to_player / distance * step;
+        }
+
+        if (player_pos - active.collectible.position).magnitude() <= COLLECT_DISTANCE {
+            collected.push(collectibles.swap_remove(i).collectible);
+        } else {
+            i += 1;
+        }
+    }
+
+    collected
+}
+
+/// Applies a picked-up collectible: HealthPacks heal by their value, Ammo
+/// refills weapon reserves, PowerUps extend the power-up buff and ExtraLifes
+/// grant a life. Every pickup's value is also credited to the run score.
+pub fn apply_collectible(
+    collectible: &Collectible,
+    build: &PlayerBuild,
+    health: &mut Health,
+    weapons: &mut WeaponSystem,
+    run: &mut RunState,
+) {
+    let value = pickup_value(collectible.value, build.pickup_bonus());
+    match collectible.collectible_type {
+        CollectibleType::HealthPack => {
+            health.heal(value as i32);
+            run.current_health = health.current;
+        }
+        CollectibleType::Ammo => weapons.refill_ammo(value),
+        CollectibleType::PowerUp => {
+            run.power_up_remaining += value as f32 * POWER_UP_SECONDS_PER_VALUE;
+        }
+        CollectibleType::ExtraLife => {
+            run.add_life();
+        }
+    }
+
+    run.score += value as u64;
+}
+
+/// Slack for float error in `value * bonus`, so products that are whole in
+/// decimal, like 100 * 1.15, don't round down to one less
+const PICKUP_ROUNDING_EPSILON: f32 = 1e-3;
+
+/// `value` scaled by a pickup bonus multiplier, rounded down to a whole
+/// credit: 10 * 1.25 credits 12, and 100 * 1.15 credits 115
+pub fn pickup_value(value: u32, bonus: f32) -> u32 {
+    (value as f32 * bonus + PICKUP_ROUNDING_EPSILON).floor() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::components::DamageType;
+    use crate::game::entities::AircraftType;
+    use crate::game::systems::upgrade::PassiveEffectType;
+    use crate::game::systems::weapon::{
+        FireMode, ProjectileType, SpreadPattern, WeaponDefinition, WeaponId,
+    };
+
+    fn collectible_at(x: f32, y: f32) -> Collectible {
+        Collectible {
+            collectible_type: CollectibleType::Ammo,
+            position: Vec2::new(x, y),
+            value: 10,
+        }
+    }
+
+    fn active_at(x: f32, y: f32) -> ActiveCollectible {
+        ActiveCollectible::new(collectible_at(x, y))
+    }
+
+    #[test]
+    fn test_collectible_in_radius_accelerates_toward_player() {
+        let player = Vec2::new(0.0, 0.0);
+        let mut collectibles = vec![active_at(100.0, 0.0)];
+
+        let mut last_distance = 100.0;
+        let mut last_step = 0.0;
+        for _ in 0..5 {
+            let collected = update_collectibles(&mut collectibles, player, 150.0, 200.0, 0.1);
+            assert!(collected.is_empty());
+
+            let distance = (collectibles[0].collectible.position - player).magnitude();
+            let step = last_distance - distance;
+            assert!(step > last_step);
+            last_distance = distance;
+            last_step = step;
+        }
+    }
+
+    #[test]
+    fn test_collectible_outside_radius_stays_put() {
+        let player = Vec2::new(0.0, 0.0);
+        let mut collectibles = vec![active_at(300.0, 0.0)];
+
+        for _ in 0..5 {
+            update_collectibles(&mut collectibles, player, 150.0, 200.0, 0.1);
+        }
+
+        assert_eq!(collectibles[0].collectible.position, Vec2::new(300.0, 0.0));
+        assert_eq!(collectibles[0].speed, 0.0);
+    }
+
+    #[test]
+    fn test_collectible_reaching_player_is_collected() {
+        let player = Vec2::new(0.0, 0.0);
+        let mut collectibles = vec![active_at(0.0, 50.0), active_at(500.0, 0.0)];
+
+        let mut collected = Vec::new();
+        for _ in 0..100 {
+            collected.extend(update_collectibles(
+                &mut collectibles,
+                player,
+                150.0,
+                400.0,
+                0.05,
+            ));
+        }
+
+        assert_eq!(collected.len(), 1);
+        assert_eq!(collected[0].value, 10);
+        assert_eq!(collectibles.len(), 1);
+        assert_eq!(collectibles[0].collectible.position, Vec2::new(500.0, 0.0));
+    }
+
+    fn pickup(collectible_type: CollectibleType, value: u32) -> Collectible {
+        Collectible {
+            collectible_type,
+            value,
+            ..collectible_at(0.0, 0.0)
+        }
+    }
+
+    fn launcher(id: u32, ammo_consumption: Option<u32>) -> WeaponDefinition {
+        WeaponDefinition {
+            id: WeaponId(id),
+            name: "Launcher".to_string(),
+            base_damage: 40.0,
+            fire_rate: 1.0,
+            projectile_speed: 400.0,
+            projectile_type: ProjectileType::Missile,
+            spread_pattern: SpreadPattern::Single,
+            ammo_consumption,
+            fire_mode: FireMode::Auto,
+            max_range: None,
+            inherit_velocity: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_health_pack_heals_by_value() {
+        let mut health = Health::new(100);
+        health.take_damage(50.0, DamageType::Kinetic);
+        let mut weapons = WeaponSystem::new();
+        let mut run = RunState::new(1, AircraftType::Spitfire);
+
+        apply_collectible(
+            &pickup(CollectibleType::HealthPack, 20),
+            &PlayerBuild::new(),
+            &mut health,
+            &mut weapons,
+            &mut run,
+        );
+        assert_eq!(health.current, 70);
+        assert_eq!(run.current_health, 70);
+
+        // Healing never exceeds max health
+        apply_collectible(
+            &pickup(CollectibleType::HealthPack, 50),
+            &PlayerBuild::new(),
+            &mut health,
+            &mut weapons,
+            &mut run,
+        );
+        assert_eq!(health.current, 100);
+    }
+
+    #[test]
+    fn test_ammo_refills_reserves() {
+        let mut health = Health::new(100);
+        let mut weapons = WeaponSystem::new();
+        weapons.register_weapon(launcher(1, Some(1)));
+        weapons.register_weapon(launcher(2, None));
+        let mut run = RunState::new(1, AircraftType::Spitfire);
+
+        assert_eq!(weapons.ammo(WeaponId(1)), Some(0));
+        apply_collectible(
+            &pickup(CollectibleType::Ammo, 15),
+            &PlayerBuild::new(),
+            &mut health,
+            &mut weapons,
+            &mut run,
+        );
+        apply_collectible(
+            &pickup(CollectibleType::Ammo, 15),
+            &PlayerBuild::new(),
+            &mut health,
+            &mut weapons,
+            &mut run,
+        );
+
+        assert_eq!(weapons.ammo(WeaponId(1)), Some(30));
+        assert_eq!(weapons.ammo(WeaponId(2)), None);
+    }
+
+    #[test]
+    fn test_power_up_grants_timed_buff() {
+        let mut health = Health::new(100);
+        let mut weapons = WeaponSystem::new();
+        let mut run = RunState::new(1, AircraftType::Spitfire);
+
+        apply_collectible(
+            &pickup(CollectibleType::PowerUp, 10),
+            &PlayerBuild::new(),
+            &mut health,
+            &mut weapons,
+            &mut run,
+        );
+        assert!(run.is_powered_up());
+
+        run.advance_time(4.0);
+        assert!(run.is_powered_up());
+        run.advance_time(1.0);
+        assert!(!run.is_powered_up());
+    }
+
+    #[test]
+    fn test_extra_life_pickup() {
+        let mut health = Health::new(100);
+        let mut weapons = WeaponSystem::new();
+        let mut run = RunState::new(1, AircraftType::Spitfire);
+        let lives = run.lives;
+
+        apply_collectible(
+            &pickup(CollectibleType::ExtraLife, 0),
+            &PlayerBuild::new(),
+            &mut health,
+            &mut weapons,
+            &mut run,
+        );
+        assert_eq!(run.lives, lives + 1);
+    }
+
+    #[test]
+    fn test_pickup_value_credited_to_score() {
+        let mut health = Health::new(100);
+        let mut weapons = WeaponSystem::new();
+        let mut run = RunState::new(1, AircraftType::Spitfire);
+
+        for collectible_type in [
+            CollectibleType::HealthPack,
+            CollectibleType::Ammo,
+            CollectibleType::PowerUp,
+        ] {
+            apply_collectible(
+                &pickup(collectible_type, 10),
+                &PlayerBuild::new(),
+                &mut health,
+                &mut weapons,
+                &mut run,
+            );
+        }
+
+        assert_eq!(run.score, 30);
+    }
+
+    #[test]
+    fn test_pickup_bonus_scales_value() {
+        let mut health = Health::new(100);
+        let mut weapons = WeaponSystem::new();
+        let mut run = RunState::new(1, AircraftType::Spitfire);
+        let mut build = PlayerBuild::new();
+        build.passives.push(PassiveEffectType::PickupBonus(1.25));
+
+        // 10 * 1.25 = 12.5, rounded down
+        apply_collectible(
+            &pickup(CollectibleType::PowerUp, 10),
+            &build,
+            &mut health,
+            &mut weapons,
+            &mut run,
+        );
+        assert_eq!(run.score, 12);
+
+        apply_collectible(
+            &pickup(CollectibleType::PowerUp, 10),
+            &PlayerBuild::new(),
+            &mut health,
+            &mut weapons,
+            &mut run,
+        );
+        assert_eq!(run.score, 22);
+
+        // 100 * 1.15 is 114.99999 in f32 but still credits 115
+        assert_eq!(pickup_value(100, 1.15), 115);
+        assert_eq!(pickup_value(10, 1.0), 10);
+    }
+}
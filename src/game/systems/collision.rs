@@ -1,5 +1,6 @@
-use crate::game::components::{Collider, Position};
+use crate::game::components::{Collider, DamageType, EntityFlags, Health, Position, RigidBody};
 use crate::game::entities::Entity;
+use crate::game::systems::weapon::{Falloff, Projectile};
 use crate::utils::{Vec2, AABB};
 use cgmath::InnerSpace;
 use std::collections::{HashMap, HashSet};
@@ -7,6 +8,7 @@ use std::collections::{HashMap, HashSet};
 pub struct CollisionSystem {
     spatial_grid: SpatialHashGrid,
     collision_pairs: Vec<(Entity, Entity)>,
+    entries: HashMap<Entity, (Position, Collider, EntityFlags)>,
 }
 
 impl CollisionSystem {
@@ -14,27 +16,182 @@ impl CollisionSystem {
         Self {
             spatial_grid: SpatialHashGrid::new(cell_size),
             collision_pairs: Vec::new(),
+            entries: HashMap::new(),
         }
     }
 
     pub fn clear(&mut self) {
         self.spatial_grid.clear();
         self.collision_pairs.clear();
+        self.entries.clear();
+    }
+
+    /// Drops an entity from the grid, its entry and any pending collision pairs
+    pub fn remove(&mut self, entity: Entity) {
+        self.spatial_grid.remove(entity);
+        self.entries.remove(&entity);
+        self.collision_pairs
+            .retain(|&(a, b)| a != entity && b != entity);
+    }
+
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.entries.contains_key(&entity)
     }
 
     pub fn insert(&mut self, entity: Entity, position: &Position, collider: &Collider) {
+        self.insert_with_flags(entity, position, collider, EntityFlags::NONE);
+    }
+
+    /// Inserts a projectile at its current position unless it has expired,
+    /// so it can't deal damage on the frame its lifetime or range runs out.
+    /// Returns whether it was inserted.
+    pub fn insert_if_alive(
+        &mut self,
+        entity: Entity,
+        projectile: &Projectile,
+        collider: &Collider,
+    ) -> bool {
+        if !projectile.is_alive() {
+            return false;
+        }
+        self.insert(entity, &Position::from_vec2(projectile.position), collider);
+        true
+    }
+
+    /// Inserts an entity along with its flags so region summaries can
+    /// classify it without consulting other systems
+    pub fn insert_with_flags(
+        &mut self,
+        entity: Entity,
+        position: &Position,
+        collider: &Collider,
+        flags: EntityFlags,
+    ) {
         let aabb = collider.get_aabb(position);
         self.spatial_grid.insert(entity, aabb);
+        self.entries.insert(entity, (*position, *collider, flags));
+    }
+
+    /// Inserts an entity with its collider resized by `scale`, typically its
+    /// `Sprite::scale`, so the grid and pair tests use the drawn size
+    pub fn insert_scaled(
+        &mut self,
+        entity: Entity,
+        position: &Position,
+        collider: &Collider,
+        scale: Vec2,
+        flags: EntityFlags,
+    ) {
+        self.insert_with_flags(entity, position, &collider.scaled(scale), flags);
     }
 
     pub fn query_region(&self, region: AABB) -> HashSet<Entity> {
         self.spatial_grid.query(region)
     }
 
+    pub fn query_region_excluding(&self, region: AABB, exclude: Entity) -> HashSet<Entity> {
+        self.spatial_grid.query_excluding(region, exclude)
+    }
+
+    /// Entities whose position lies within `radius` of `center`, paired with
+    /// their distance and ordered nearest first
+    pub fn query_radius_sorted(&self, center: Vec2, radius: f32) -> Vec<(Entity, f32)> {
+        let region = AABB::from_center_size(center, Vec2::new(radius * 2.0, radius * 2.0));
+        let mut results: Vec<(Entity, f32)> = self
+            .spatial_grid
+            .query(region)
+            .into_iter()
+            .filter_map(|entity| {
+                let (position, _, _) = self.entries.get(&entity)?;
+                let distance = (position.as_vec2() - center).magnitude();
+                (distance <= radius).then_some((entity, distance))
+            })
+            .collect();
+
+        results.sort_by(|a, b| {
+            a.1.total_cmp(&b.1)
+                .then(entity_order(a.0).cmp(&entity_order(b.0)))
+        });
+        results
+    }
+
+    /// Buckets enemies and hazards within `radius` of `center`, e.g. for the
+    /// minimap. Hazard positions are ordered nearest first.
+    pub fn summarize_region(&self, center: Vec2, radius: f32) -> RegionSummary {
+        let region = AABB::from_center_size(center, Vec2::new(radius * 2.0, radius * 2.0));
+        let radius_sq = radius * radius;
+        let mut summary = RegionSummary::default();
+        let mut nearest: Option<(f32, (u32, u32))> = None;
+        let mut hazards = Vec::new();
+
+        for entity in self.spatial_grid.query(region) {
+            let (position, _, flags) = match self.entries.get(&entity) {
+                Some(entry) => entry,
+                None => continue,
+            };
+            let distance_sq = (position.as_vec2() - center).magnitude2();
+            if distance_sq > radius_sq {
+                continue;
+            }
+
+            if flags.has(EntityFlags::ENEMY) {
+                summary.enemy_count += 1;
+                let key = (distance_sq, entity_order(entity));
+                if nearest.is_none_or(|best| key < best) {
+                    nearest = Some(key);
+                    summary.nearest_enemy = Some((entity, position.as_vec2()));
+                }
+            }
+
+            if flags.has(EntityFlags::HAZARD) {
+                hazards.push((distance_sq, entity_order(entity), position.as_vec2()));
+            }
+        }
+
+        hazards.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+        summary.hazard_positions = hazards.into_iter().map(|(_, _, p)| p).collect();
+        summary
+    }
+
     pub fn get_collisions(&self) -> &[(Entity, Entity)] {
         &self.collision_pairs
     }
 
+    /// Finds every overlapping pair among the inserted entities, replacing
+    /// the previous pairs. Pairs are canonical, per `canonical_pair`, and
+    /// sorted by `entity_order`.
+    pub fn detect_collisions(&mut self) -> &[(Entity, Entity)] {
+        let mut pairs = Vec::new();
+        for (&entity, (position, collider, flags)) in &self.entries {
+            let region = collider.get_aabb(position);
+            for other in self.spatial_grid.query_excluding(region, entity) {
+                // Each unordered pair is tested once, from its first entity
+                let (other_position, other_collider, other_flags) = match self.entries.get(&other) {
+                    Some(entry) if entity_order(entity) < entity_order(other) => entry,
+                    _ => continue,
+                };
+                if Self::layers_interact(*flags, *other_flags)
+                    && Self::test_collision(position, collider, other_position, other_collider)
+                {
+                    pairs.push(canonical_pair(entity, other));
+                }
+            }
+        }
+
+        pairs.sort_by_key(|&(a, b)| (entity_order(a), entity_order(b)));
+        self.collision_pairs = pairs;
+        &self.collision_pairs
+    }
+
+    /// Collectibles only ever pair with the player; everything else may
+    /// collide with anything
+    fn layers_interact(a: EntityFlags, b: EntityFlags) -> bool {
+        if a.has(EntityFlags::COLLECTIBLE) || b.has(EntityFlags::COLLECTIBLE) {
+            return a.has(EntityFlags::PLAYER) || b.has(EntityFlags::PLAYER);
+        }
+        true
+    }
+
     pub fn test_collision(
         pos1: &Position,
         col1: &Collider,
@@ -61,6 +218,166 @@ impl CollisionSystem {
         }
     }
 
+    /// Penetration of `col2` into `col1`, with the normal pointing from the
+    /// first entity toward the second. `None` if they don't overlap.
+    pub fn compute_manifold(
+        pos1: &Position,
+        col1: &Collider,
+        pos2: &Position,
+        col2: &Collider,
+    ) -> Option<Manifold> {
+        let p1 = pos1.as_vec2();
+        let p2 = pos2.as_vec2();
+
+        match (col1, col2) {
+            (Collider::Circle { radius: r1 }, Collider::Circle { radius: r2 }) => {
+                let delta = p2 - p1;
+                let distance = delta.magnitude();
+                let depth = r1 + r2 - distance;
+                if depth <= 0.0 {
+                    return None;
+                }
+                let normal = if distance > f32::EPSILON {
+                    delta / distance
+                } else {
+                    Vec2::new(1.0, 0.0)
+                };
+                Some(Manifold { normal, depth })
+            }
+            (Collider::AABB { width: w1, height: h1 }, Collider::AABB { width: w2, height: h2 }) => {
+                let delta = p2 - p1;
+                let overlap_x = (w1 + w2) / 2.0 - delta.x.abs();
+                let overlap_y = (h1 + h2) / 2.0 - delta.y.abs();
+                if overlap_x <= 0.0 || overlap_y <= 0.0 {
+                    return None;
+                }
+                if overlap_x < overlap_y {
+                    let sign = if delta.x < 0.0 { -1.0 } else { 1.0 };
+                    Some(Manifold {
+                        normal: Vec2::new(sign, 0.0),
+                        depth: overlap_x,
+                    })
+                } else {
+                    let sign = if delta.y < 0.0 { -1.0 } else { 1.0 };
+                    Some(Manifold {
+                        normal: Vec2::new(0.0, sign),
+                        depth: overlap_y,
+                    })
+                }
+            }
+            (Collider::Circle { radius }, Collider::AABB { width, height }) => {
+                let aabb = AABB::from_center_size(p2, Vec2::new(*width, *height));
+                Self::circle_aabb_manifold(p1, *radius, &aabb)
+            }
+            (Collider::AABB { width, height }, Collider::Circle { radius }) => {
+                let aabb = AABB::from_center_size(p1, Vec2::new(*width, *height));
+                Self::circle_aabb_manifold(p2, *radius, &aabb).map(|m| Manifold {
+                    normal: -m.normal,
+                    depth: m.depth,
+                })
+            }
+        }
+    }
+
+    /// Manifold with the normal pointing from the circle toward the box
+    fn circle_aabb_manifold(center: Vec2, radius: f32, aabb: &AABB) -> Option<Manifold> {
+        let closest = Vec2::new(
+            center.x.max(aabb.min.x).min(aabb.max.x),
+            center.y.max(aabb.min.y).min(aabb.max.y),
+        );
+        let delta = closest - center;
+        let distance_sq = delta.magnitude2();
+
+        if distance_sq > f32::EPSILON {
+            let distance = distance_sq.sqrt();
+            if distance >= radius {
+                return None;
+            }
+            return Some(Manifold {
+                normal: delta / distance,
+                depth: radius - distance,
+            });
+        }
+
+        // Center is inside the box: exit through the nearest face
+        let exits = [
+            (center.x - aabb.min.x, Vec2::new(1.0, 0.0)),
+            (aabb.max.x - center.x, Vec2::new(-1.0, 0.0)),
+            (center.y - aabb.min.y, Vec2::new(0.0, 1.0)),
+            (aabb.max.y - center.y, Vec2::new(0.0, -1.0)),
+        ];
+        exits
+            .iter()
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(face_distance, normal)| Manifold {
+                normal: *normal,
+                depth: face_distance + radius,
+            })
+    }
+
+    /// Damages every entity within `radius` of `center`, scaled by distance
+    /// according to `falloff`. Explosive resistance and armor apply as for a
+    /// direct hit. Returns the raw damage dealt to each entity, nearest first.
+    pub fn explode(
+        &self,
+        center: Vec2,
+        radius: f32,
+        max_damage: f32,
+        falloff: Falloff,
+        healths: &mut HashMap<Entity, Health>,
+    ) -> Vec<(Entity, f32)> {
+        let mut hits = Vec::new();
+        for (entity, distance) in self.query_radius_sorted(center, radius) {
+            let damage = max_damage * falloff.scale(distance, radius);
+            if damage <= 0.0 {
+                continue;
+            }
+            if let Some(health) = healths.get_mut(&entity) {
+                health.take_damage(damage, DamageType::Explosive);
+                hits.push((entity, damage));
+            }
+        }
+        hits
+    }
+
+    /// Pushes overlapping solid entities apart along the collision normal,
+    /// splitting the correction by inverse mass. Entities without a solid
+    /// `RigidBody` (projectiles, triggers) are left untouched.
+    pub fn resolve_overlaps(
+        positions: &mut HashMap<Entity, Position>,
+        colliders: &HashMap<Entity, Collider>,
+        bodies: &HashMap<Entity, RigidBody>,
+        pairs: &[(Entity, Entity)],
+    ) {
+        for (a, b) in pairs {
+            let (body_a, body_b) = match (bodies.get(a), bodies.get(b)) {
+                (Some(body_a), Some(body_b)) if body_a.solid && body_b.solid => (body_a, body_b),
+                _ => continue,
+            };
+
+            let total_inverse_mass = body_a.inverse_mass + body_b.inverse_mass;
+            if total_inverse_mass <= 0.0 {
+                continue;
+            }
+
+            let (pos_a, pos_b, col_a, col_b) = match (
+                positions.get(a),
+                positions.get(b),
+                colliders.get(a),
+                colliders.get(b),
+            ) {
+                (Some(pa), Some(pb), Some(ca), Some(cb)) => (*pa, *pb, ca, cb),
+                _ => continue,
+            };
+
+            if let Some(manifold) = Self::compute_manifold(&pos_a, col_a, &pos_b, col_b) {
+                let correction = manifold.normal * (manifold.depth / total_inverse_mass);
+                positions.insert(*a, pos_a - correction * body_a.inverse_mass);
+                positions.insert(*b, pos_b + correction * body_b.inverse_mass);
+            }
+        }
+    }
+
     fn test_circle_circle(pos1: Vec2, r1: f32, pos2: Vec2, r2: f32) -> bool {
         let dist_sq = (pos1 - pos2).magnitude2();
         let radius_sum = r1 + r2;
@@ -79,15 +396,35 @@ impl CollisionSystem {
     }
 }
 
+/// Summary of what lies within a circular region
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RegionSummary {
+    pub enemy_count: u32,
+    pub nearest_enemy: Option<(Entity, Vec2)>,
+    pub hazard_positions: Vec<Vec2>,
+}
+
+/// Overlap between two colliders
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Manifold {
+    /// Unit vector from the first entity toward the second
+    pub normal: Vec2,
+    pub depth: f32,
+}
+
 impl Default for CollisionSystem {
     fn default() -> Self {
         Self::new(100.0) // Default cell size
     }
 }
 
+/// Clears between pruning cells that went unused for a whole frame
+const GRID_PRUNE_INTERVAL: u32 = 60;
+
 pub struct SpatialHashGrid {
     cell_size: f32,
     cells: HashMap<(i32, i32), Vec<Entity>>,
+    clears_since_prune: u32,
 }
 
 impl SpatialHashGrid {
@@ -95,11 +432,24 @@ impl SpatialHashGrid {
         Self {
             cell_size,
             cells: HashMap::new(),
+            clears_since_prune: 0,
         }
     }
 
+    /// Empties every cell but keeps its allocation for the next frame.
+    /// Periodically drops cells that stayed empty so the map doesn't grow
+    /// without bound as entities move around.
     pub fn clear(&mut self) {
-        self.cells.clear();
+        self.clears_since_prune += 1;
+        if self.clears_since_prune >= GRID_PRUNE_INTERVAL {
+            self.clears_since_prune = 0;
+            self.cells
+                .retain(|_, cell_entities| !cell_entities.is_empty());
+        }
+
+        for cell_entities in self.cells.values_mut() {
+            cell_entities.clear();
+        }
     }
 
     pub fn insert(&mut self, entity: Entity, aabb: AABB) {
@@ -116,7 +466,22 @@ impl SpatialHashGrid {
         }
     }
 
+    pub fn remove(&mut self, entity: Entity) {
+        for cell_entities in self.cells.values_mut() {
+            cell_entities.retain(|e| *e != entity);
+        }
+    }
+
     pub fn query(&self, aabb: AABB) -> HashSet<Entity> {
+        self.collect(aabb, None)
+    }
+
+    /// Like `query`, but never returns `exclude`, e.g. the querying entity
+    pub fn query_excluding(&self, aabb: AABB, exclude: Entity) -> HashSet<Entity> {
+        self.collect(aabb, Some(exclude))
+    }
+
+    fn collect(&self, aabb: AABB, exclude: Option<Entity>) -> HashSet<Entity> {
         let mut entities = HashSet::new();
         let min_cell = self.world_to_cell(aabb.min);
         let max_cell = self.world_to_cell(aabb.max);
@@ -124,7 +489,7 @@ impl SpatialHashGrid {
         for x in min_cell.0..=max_cell.0 {
             for y in min_cell.1..=max_cell.1 {
                 if let Some(cell_entities) = self.cells.get(&(x, y)) {
-                    entities.extend(cell_entities);
+                    entities.extend(cell_entities.iter().filter(|e| Some(**e) != exclude));
                 }
             }
         }
@@ -145,6 +510,22 @@ impl SpatialHashGrid {
     }
 }
 
+/// Orders a pair by entity id, then generation, so the same two entities
+/// always report as the same pair
+pub fn canonical_pair(a: Entity, b: Entity) -> (Entity, Entity) {
+    if entity_order(a) <= entity_order(b) {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+/// Tie-break key for results gathered from hash maps and grid cells, so
+/// equal distances and pair lists come out the same regardless of hash order
+fn entity_order(entity: Entity) -> (u32, u32) {
+    (entity.id, entity.generation)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -203,6 +584,266 @@ mod tests {
         assert!(!CollisionSystem::test_collision(&pos1, &col1, &pos3, &col3));
     }
 
+    #[test]
+    fn test_resolve_overlaps_equal_mass() {
+        let a = Entity::new(1);
+        let b = Entity::new(2);
+        let mut positions = HashMap::new();
+        positions.insert(a, Position::new(0.0, 0.0));
+        positions.insert(b, Position::new(15.0, 0.0));
+        let mut colliders = HashMap::new();
+        colliders.insert(a, Collider::circle(10.0));
+        colliders.insert(b, Collider::circle(10.0));
+        let mut bodies = HashMap::new();
+        bodies.insert(a, RigidBody::dynamic(1.0));
+        bodies.insert(b, RigidBody::dynamic(1.0));
+
+        CollisionSystem::resolve_overlaps(&mut positions, &colliders, &bodies, &[(a, b)]);
+
+        assert!((positions[&a].x - -2.5).abs() < 0.001);
+        assert!((positions[&b].x - 17.5).abs() < 0.001);
+        let distance = (positions[&b] - positions[&a]).magnitude();
+        assert!((distance - 20.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_resolve_overlaps_static_body() {
+        let wall = Entity::new(1);
+        let player = Entity::new(2);
+        let mut positions = HashMap::new();
+        positions.insert(wall, Position::new(0.0, 0.0));
+        positions.insert(player, Position::new(12.0, 0.0));
+        let mut colliders = HashMap::new();
+        colliders.insert(wall, Collider::aabb(20.0, 20.0));
+        colliders.insert(player, Collider::circle(5.0));
+        let mut bodies = HashMap::new();
+        bodies.insert(wall, RigidBody::fixed());
+        bodies.insert(player, RigidBody::dynamic(1.0));
+
+        CollisionSystem::resolve_overlaps(&mut positions, &colliders, &bodies, &[(wall, player)]);
+
+        assert_eq!(positions[&wall], Position::new(0.0, 0.0));
+        assert!((positions[&player].x - 15.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_resolve_overlaps_skips_triggers() {
+        let a = Entity::new(1);
+        let bullet = Entity::new(2);
+        let mut positions = HashMap::new();
+        positions.insert(a, Position::new(0.0, 0.0));
+        positions.insert(bullet, Position::new(5.0, 0.0));
+        let mut colliders = HashMap::new();
+        colliders.insert(a, Collider::circle(10.0));
+        colliders.insert(bullet, Collider::circle(2.0));
+        let mut bodies = HashMap::new();
+        bodies.insert(a, RigidBody::dynamic(1.0));
+        bodies.insert(bullet, RigidBody::trigger());
+
+        CollisionSystem::resolve_overlaps(&mut positions, &colliders, &bodies, &[(a, bullet)]);
+
+        assert_eq!(positions[&a], Position::new(0.0, 0.0));
+        assert_eq!(positions[&bullet], Position::new(5.0, 0.0));
+    }
+
+    #[test]
+    fn test_expired_projectile_never_collides() {
+        let mut system = CollisionSystem::new(50.0);
+        let target = Entity::new(1);
+        let target_position = Position::new(0.0, 0.0);
+        let collider = Collider::circle(10.0);
+        system.insert(target, &target_position, &collider);
+
+        let bullet = Collider::circle(2.0);
+        let mut projectile = Projectile {
+            position: Vec2::new(3.0, 0.0),
+            lifetime: 0.0,
+            ..Projectile::default()
+        };
+        assert!(!system.insert_if_alive(Entity::new(2), &projectile, &bullet));
+        assert!(!system.contains(Entity::new(2)));
+        let region = collider.get_aabb(&target_position);
+        assert!(system.query_region_excluding(region, target).is_empty());
+        assert!(system.get_collisions().is_empty());
+
+        projectile.lifetime = 0.5;
+        assert!(system.insert_if_alive(Entity::new(3), &projectile, &bullet));
+        let hits = system.query_region_excluding(region, target);
+        assert_eq!(hits, HashSet::from([Entity::new(3)]));
+    }
+
+    #[test]
+    fn test_collectibles_only_pair_with_player() {
+        let mut system = CollisionSystem::new(50.0);
+        let collider = Collider::circle(10.0);
+        let mut add = |id: u32, x: f32, flags: EntityFlags| {
+            system.insert_with_flags(Entity::new(id), &Position::new(x, 0.0), &collider, flags);
+        };
+
+        // A collectible sitting on an enemy and a projectile
+        add(1, 0.0, EntityFlags::COLLECTIBLE);
+        add(2, 5.0, EntityFlags::ENEMY);
+        add(3, -5.0, EntityFlags::NONE);
+        // ...and another touching the player
+        add(4, 200.0, EntityFlags::COLLECTIBLE);
+        add(5, 210.0, EntityFlags::PLAYER);
+
+        let pairs = system.detect_collisions().to_vec();
+        assert_eq!(
+            pairs,
+            vec![
+                (Entity::new(2), Entity::new(3)),
+                (Entity::new(4), Entity::new(5)),
+            ]
+        );
+        assert_eq!(system.get_collisions(), &pairs[..]);
+    }
+
+    #[test]
+    fn test_scaled_collider_widens_contact() {
+        let collider = Collider::circle(5.0);
+        let detect = |scale: Vec2| {
+            let mut system = CollisionSystem::new(50.0);
+            let (near, far) = (Position::new(0.0, 0.0), Position::new(12.0, 0.0));
+            system.insert_scaled(Entity::new(1), &near, &collider, scale, EntityFlags::ENEMY);
+            system.insert_with_flags(Entity::new(2), &far, &collider, EntityFlags::PLAYER);
+            system.detect_collisions().to_vec()
+        };
+
+        assert!(detect(Vec2::new(1.0, 1.0)).is_empty());
+        assert_eq!(
+            detect(Vec2::new(2.0, 2.0)),
+            vec![(Entity::new(1), Entity::new(2))]
+        );
+    }
+
+    #[test]
+    fn test_collision_pairs_are_canonical_and_stable() {
+        let collider = Collider::circle(10.0);
+        let recycled = Entity {
+            id: 3,
+            generation: 1,
+        };
+        let placements = [
+            (Entity::new(7), 0.0),
+            (Entity::new(3), 5.0),
+            (recycled, 12.0),
+            (Entity::new(1), 300.0),
+            (Entity::new(9), 305.0),
+        ];
+
+        let detect = |order: &[usize]| {
+            let mut system = CollisionSystem::new(50.0);
+            for &i in order {
+                let (entity, x) = placements[i];
+                let position = Position::new(x, 0.0);
+                system.insert_with_flags(entity, &position, &collider, EntityFlags::ENEMY);
+            }
+            system.detect_collisions().to_vec()
+        };
+
+        let expected = vec![
+            (Entity::new(1), Entity::new(9)),
+            (Entity::new(3), recycled),
+            (Entity::new(3), Entity::new(7)),
+            (recycled, Entity::new(7)),
+        ];
+        // Fresh systems hash differently, and insertion order varies too
+        for order in [[0, 1, 2, 3, 4], [4, 3, 2, 1, 0], [2, 0, 4, 1, 3]] {
+            for _ in 0..5 {
+                assert_eq!(detect(&order), expected);
+            }
+        }
+
+        assert_eq!(
+            canonical_pair(Entity::new(7), recycled),
+            (recycled, Entity::new(7))
+        );
+    }
+
+    #[test]
+    fn test_summarize_region() {
+        let mut system = CollisionSystem::new(50.0);
+        let collider = Collider::circle(5.0);
+        let mut add = |id: u32, x: f32, y: f32, flags: EntityFlags| {
+            system.insert_with_flags(Entity::new(id), &Position::new(x, y), &collider, flags);
+        };
+
+        add(1, 30.0, 0.0, EntityFlags::ENEMY);
+        add(2, -10.0, 5.0, EntityFlags::ENEMY);
+        // Outside the radius, though inside the query's bounding box
+        add(3, 90.0, 90.0, EntityFlags::ENEMY);
+        add(4, 0.0, 60.0, EntityFlags::HAZARD);
+        add(5, 0.0, 20.0, EntityFlags::HAZARD);
+        // Unflagged entities are ignored
+        add(6, 1.0, 1.0, EntityFlags::NONE);
+
+        let summary = system.summarize_region(Vec2::new(0.0, 0.0), 100.0);
+        assert_eq!(summary.enemy_count, 2);
+        assert_eq!(summary.nearest_enemy.unwrap().0, Entity::new(2));
+        assert_eq!(
+            summary.hazard_positions,
+            vec![Vec2::new(0.0, 20.0), Vec2::new(0.0, 60.0)]
+        );
+
+        let empty = system.summarize_region(Vec2::new(1000.0, 1000.0), 50.0);
+        assert_eq!(empty, RegionSummary::default());
+    }
+
+    #[test]
+    fn test_query_radius_sorted() {
+        let mut system = CollisionSystem::new(50.0);
+        let collider = Collider::circle(5.0);
+        for (id, x, y) in [
+            (1, 40.0, 0.0),
+            (2, 0.0, -10.0),
+            (3, 70.0, 70.0),
+            (4, -25.0, 0.0),
+        ] {
+            system.insert(Entity::new(id), &Position::new(x, y), &collider);
+        }
+
+        let results = system.query_radius_sorted(Vec2::new(0.0, 0.0), 60.0);
+        let ids: Vec<u32> = results.iter().map(|(e, _)| e.id).collect();
+        assert_eq!(ids, vec![2, 4, 1]);
+        assert!(results.windows(2).all(|w| w[0].1 <= w[1].1));
+        assert!((results[0].1 - 10.0).abs() < 1e-5);
+
+        assert!(system
+            .query_radius_sorted(Vec2::new(500.0, 500.0), 10.0)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_explosion_damage_falls_off() {
+        let mut system = CollisionSystem::new(50.0);
+        let collider = Collider::circle(5.0);
+        let mut healths = HashMap::new();
+        for (id, x) in [(1, 0.0), (2, 99.0), (3, 150.0)] {
+            system.insert(Entity::new(id), &Position::new(x, 0.0), &collider);
+            healths.insert(Entity::new(id), Health::new(100));
+        }
+
+        let hits = system.explode(
+            Vec2::new(0.0, 0.0),
+            100.0,
+            80.0,
+            Falloff::Linear,
+            &mut healths,
+        );
+
+        assert_eq!(hits[0], (Entity::new(1), 80.0));
+        assert_eq!(healths[&Entity::new(1)].current, 20);
+
+        // Right at the edge the blast barely registers
+        assert_eq!(hits[1].0, Entity::new(2));
+        assert!(hits[1].1 < 1.0);
+
+        // Outside the radius nothing happens
+        assert_eq!(hits.len(), 2);
+        assert_eq!(healths[&Entity::new(3)].current, 100);
+    }
+
     #[test]
     fn test_spatial_hash_grid() {
         let mut grid = SpatialHashGrid::new(100.0);
@@ -226,6 +867,29 @@ mod tests {
         assert!(query_result.contains(&entity2));
     }
 
+    #[test]
+    fn test_spatial_hash_grid_query_excluding() {
+        let mut grid = SpatialHashGrid::new(100.0);
+        let me = Entity::new(1);
+        let neighbour = Entity::new(2);
+
+        // Large enough to occupy several cells, all of them queried
+        let large = AABB::new(Vec2::new(-150.0, -150.0), Vec2::new(150.0, 150.0));
+        grid.insert(me, large);
+        let small = AABB::new(Vec2::new(10.0, 10.0), Vec2::new(20.0, 20.0));
+        grid.insert(neighbour, small);
+
+        let region = AABB::new(Vec2::new(-200.0, -200.0), Vec2::new(200.0, 200.0));
+        let result = grid.query_excluding(region, me);
+        assert!(!result.contains(&me));
+        assert!(result.contains(&neighbour));
+        assert_eq!(grid.query(region).len(), 2);
+
+        // Excluding an entity that isn't present changes nothing
+        let unrelated = Entity::new(3);
+        assert_eq!(grid.query_excluding(region, unrelated), grid.query(region));
+    }
+
     #[test]
     fn test_spatial_hash_grid_clear() {
         let mut grid = SpatialHashGrid::new(100.0);
@@ -238,4 +902,41 @@ mod tests {
         grid.clear();
         assert!(grid.query(aabb).is_empty());
     }
+
+    #[test]
+    fn test_spatial_hash_grid_reuses_cells() {
+        let mut grid = SpatialHashGrid::new(100.0);
+        let aabb = AABB::new(Vec2::new(10.0, 10.0), Vec2::new(20.0, 20.0));
+        for id in 0..16 {
+            grid.insert(Entity::new(id), aabb);
+        }
+        let capacity = grid.cells[&(0, 0)].capacity();
+
+        grid.clear();
+        assert!(grid.query(aabb).is_empty());
+        assert_eq!(grid.cells[&(0, 0)].capacity(), capacity);
+
+        // Reinserting after a clear gives correct results
+        grid.insert(Entity::new(99), aabb);
+        let result = grid.query(aabb);
+        assert_eq!(result.len(), 1);
+        assert!(result.contains(&Entity::new(99)));
+    }
+
+    #[test]
+    fn test_spatial_hash_grid_prunes_unused_cells() {
+        let mut grid = SpatialHashGrid::new(100.0);
+        grid.insert(
+            Entity::new(1),
+            AABB::new(Vec2::new(10.0, 10.0), Vec2::new(20.0, 20.0)),
+        );
+
+        // Cells emptied by a clear survive until the next prune
+        grid.clear();
+        assert!(grid.cells.contains_key(&(0, 0)));
+        for _ in 0..GRID_PRUNE_INTERVAL {
+            grid.clear();
+        }
+        assert!(grid.cells.is_empty());
+    }
 }
@@ -0,0 +1,105 @@
+//! Applying hits to entities, with crit rolls and floating damage numbers
+
+use crate::game::components::{DamageType, Health, Position};
+use crate::game::events::{DamageNumberEvent, GameEvent};
+use crate::game::systems::upgrade::{PlayerBuild, Stat};
+use rand::Rng;
+
+/// Damage multiplier for a critical hit
+pub const CRIT_MULTIPLIER: f32 = 2.0;
+
+/// Chance in [0, 1] that a hit crits. Stat modifiers start at 1.0, so only
+/// the bonus above that counts.
+pub fn crit_chance(build: &PlayerBuild) -> f32 {
+    (build.get_stat_modifier(Stat::CritChance) - 1.0).clamp(0.0, 1.0)
+}
+
+/// Rolls for a crit, damages `target` and queues a damage number at its
+/// position. Returns the damage dealt before resistances and armor.
+pub fn apply_damage<R: Rng>(
+    target: &mut Health,
+    position: &Position,
+    damage: f32,
+    damage_type: DamageType,
+    build: &PlayerBuild,
+    rng: &mut R,
+    events: &mut Vec<GameEvent>,
+) -> f32 {
+    let crit = rng.gen::<f32>() < crit_chance(build);
+    let amount = if crit {
+        damage * CRIT_MULTIPLIER
+    } else {
+        damage
+    };
+
+    target.take_damage(amount, damage_type);
+    events.push(GameEvent::DamageNumber(DamageNumberEvent {
+        position: position.as_vec2(),
+        amount,
+        crit,
+    }));
+    amount
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::systems::upgrade::Modifier;
+    use crate::utils::Vec2;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn hit(build: &PlayerBuild, seed: u64) -> (Health, Vec<GameEvent>) {
+        let mut health = Health::new(100);
+        let mut events = Vec::new();
+        let mut rng = StdRng::seed_from_u64(seed);
+        apply_damage(
+            &mut health,
+            &Position::new(30.0, -20.0),
+            10.0,
+            DamageType::Kinetic,
+            build,
+            &mut rng,
+            &mut events,
+        );
+        (health, events)
+    }
+
+    #[test]
+    fn test_damage_emits_number_at_target() {
+        let build = PlayerBuild::new();
+        assert_eq!(crit_chance(&build), 0.0);
+
+        for seed in 0..20 {
+            let (health, events) = hit(&build, seed);
+            assert_eq!(health.current, 90);
+            assert_eq!(
+                events,
+                vec![GameEvent::DamageNumber(DamageNumberEvent {
+                    position: Vec2::new(30.0, -20.0),
+                    amount: 10.0,
+                    crit: false,
+                })]
+            );
+        }
+    }
+
+    #[test]
+    fn test_guaranteed_crit_multiplies_damage() {
+        let mut build = PlayerBuild::new();
+        build.apply_stat_modifier(Stat::CritChance, Modifier::Add(1.0));
+        assert_eq!(crit_chance(&build), 1.0);
+
+        for seed in 0..20 {
+            let (health, events) = hit(&build, seed);
+            assert_eq!(health.current, 80);
+            match events.as_slice() {
+                [GameEvent::DamageNumber(number)] => {
+                    assert!(number.crit);
+                    assert_eq!(number.amount, 10.0 * CRIT_MULTIPLIER);
+                }
+                other => panic!("expected one damage number, got {:?}", other),
+            }
+        }
+    }
+}
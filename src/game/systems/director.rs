@@ -0,0 +1,127 @@
+use crate::game::events::GameEvent;
+
+/// Seconds over which recent damage and kills fade out
+const MEMORY_SECONDS: f32 = 10.0;
+/// Fraction of max health lost recently above which the director eases off
+const HIGH_STRESS: f32 = 0.3;
+/// Below this stress a player with enough recent kills is dominating
+const LOW_STRESS: f32 = 0.1;
+const DOMINATING_KILLS: f32 = 5.0;
+/// Pacing change per second while outside the target band
+const ADJUST_RATE: f32 = 0.2;
+
+pub const MIN_PACING: f32 = 0.5;
+pub const MAX_PACING: f32 = 2.0;
+
+/// Watches recent player performance and nudges a pacing multiplier to keep
+/// tension in a target band: easing off when the player is struggling and
+/// pushing harder when they're dominating.
+#[derive(Debug, Clone)]
+pub struct Director {
+    player_max_health: f32,
+    recent_damage: f32,
+    recent_kills: f32,
+    pacing: f32,
+}
+
+impl Director {
+    pub fn new(player_max_health: i32) -> Self {
+        Self {
+            player_max_health: player_max_health.max(1) as f32,
+            recent_damage: 0.0,
+            recent_kills: 0.0,
+            pacing: 1.0,
+        }
+    }
+
+    pub fn on_event(&mut self, event: &GameEvent) {
+        match event {
+            GameEvent::DamageTaken { amount } => self.recent_damage += (*amount).max(0) as f32,
+            GameEvent::EnemyKilled { .. } => self.recent_kills += 1.0,
+            GameEvent::ZoneCleared { .. }
+            | GameEvent::RunStarted
+            | GameEvent::TimeElapsed { .. }
+            | GameEvent::DamageNumber(_)
+            | GameEvent::SpawnNow(_) => {}
+        }
+    }
+
+    /// Fades old performance and moves the pacing toward the target band.
+    /// Returns the updated pacing multiplier.
+    pub fn update(&mut self, delta: f32) -> f32 {
+        let stress = self.stress();
+        if stress > HIGH_STRESS {
+            self.pacing -= ADJUST_RATE * delta;
+        } else if stress < LOW_STRESS && self.recent_kills >= DOMINATING_KILLS {
+            self.pacing += ADJUST_RATE * delta;
+        }
+        self.pacing = self.pacing.clamp(MIN_PACING, MAX_PACING);
+
+        let decay = (-delta / MEMORY_SECONDS).exp();
+        self.recent_damage *= decay;
+        self.recent_kills *= decay;
+
+        self.pacing
+    }
+
+    /// Fraction of max health lost recently
+    pub fn stress(&self) -> f32 {
+        self.recent_damage / self.player_max_health
+    }
+
+    /// Multiplier for spawn rate and difficulty; 1.0 is neutral
+    pub fn pacing(&self) -> f32 {
+        self.pacing
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::entities::EnemyType;
+
+    fn kill() -> GameEvent {
+        GameEvent::EnemyKilled {
+            enemy_type: EnemyType::Fighter,
+            is_elite: false,
+            is_boss: false,
+        }
+    }
+
+    #[test]
+    fn test_heavy_damage_eases_off() {
+        let mut director = Director::new(100);
+        for _ in 0..10 {
+            director.on_event(&GameEvent::DamageTaken { amount: 15 });
+            director.update(0.5);
+        }
+
+        assert!(director.pacing() < 1.0);
+        assert!(director.pacing() >= MIN_PACING);
+    }
+
+    #[test]
+    fn test_dominating_player_increases_pacing() {
+        let mut director = Director::new(100);
+        for _ in 0..10 {
+            director.on_event(&kill());
+            director.on_event(&kill());
+            director.update(0.5);
+        }
+
+        assert!(director.pacing() > 1.0);
+        assert!(director.pacing() <= MAX_PACING);
+    }
+
+    #[test]
+    fn test_steady_play_holds_pacing() {
+        let mut director = Director::new(100);
+        for _ in 0..10 {
+            director.on_event(&kill());
+            director.on_event(&GameEvent::DamageTaken { amount: 2 });
+            director.update(0.5);
+        }
+
+        assert_eq!(director.pacing(), 1.0);
+    }
+}
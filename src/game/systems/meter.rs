@@ -0,0 +1,180 @@
+use crate::game::events::GameEvent;
+use std::collections::VecDeque;
+
+/// Default trailing window, in seconds, the HUD meters cover
+pub const DEFAULT_METER_WINDOW: f32 = 5.0;
+
+/// Live DPS and kills-per-minute over a trailing window of session time,
+/// as opposed to the lifetime totals in `GameStatistics`
+pub struct CombatMeter {
+    window: f32,
+    now: f32,
+    /// (timestamp, damage) per hit, oldest first; pruned as they leave the
+    /// window, so every in-window sample counts however fast hits land
+    damage: VecDeque<(f32, f32)>,
+    kills: VecDeque<f32>,
+}
+
+impl CombatMeter {
+    pub fn new(window: f32) -> Self {
+        Self {
+            window: window.max(f32::EPSILON),
+            now: 0.0,
+            damage: VecDeque::new(),
+            kills: VecDeque::new(),
+        }
+    }
+
+    /// Records damage numbers and kills; time only moves in `update`
+    pub fn on_event(&mut self, event: &GameEvent) {
+        match event {
+            GameEvent::DamageNumber(number) => self.record_damage(number.amount),
+            GameEvent::EnemyKilled { .. } => self.record_kill(),
+            GameEvent::DamageTaken { .. }
+            | GameEvent::ZoneCleared { .. }
+            | GameEvent::RunStarted
+            | GameEvent::TimeElapsed { .. }
+            | GameEvent::SpawnNow(_) => {}
+        }
+    }
+
+    pub fn record_damage(&mut self, amount: f32) {
+        self.damage.push_back((self.now, amount.max(0.0)));
+    }
+
+    pub fn record_kill(&mut self) {
+        self.kills.push_back(self.now);
+    }
+
+    pub fn update(&mut self, delta: f32) {
+        self.now += delta.max(0.0);
+
+        let cutoff = self.now - self.window;
+        while self.damage.front().is_some_and(|(time, _)| *time <= cutoff) {
+            self.damage.pop_front();
+        }
+        while self.kills.front().is_some_and(|time| *time <= cutoff) {
+            self.kills.pop_front();
+        }
+    }
+
+    /// Damage per second over the window, or over the session so far if it
+    /// is shorter than the window
+    pub fn dps(&self) -> f32 {
+        let total: f32 = self
+            .damage
+            .iter()
+            .filter(|(time, _)| self.in_window(*time))
+            .map(|(_, amount)| amount)
+            .sum();
+        self.per_second(total)
+    }
+
+    /// Kills per minute, measured the same way as `dps`
+    pub fn kills_per_minute(&self) -> f32 {
+        let kills = self
+            .kills
+            .iter()
+            .filter(|time| self.in_window(**time))
+            .count();
+        self.per_second(kills as f32) * 60.0
+    }
+
+    pub fn clear(&mut self) {
+        self.damage.clear();
+        self.kills.clear();
+    }
+
+    fn in_window(&self, time: f32) -> bool {
+        time > self.now - self.window
+    }
+
+    fn per_second(&self, total: f32) -> f32 {
+        let span = self.window.min(self.now);
+        if span <= 0.0 {
+            return 0.0;
+        }
+        total / span
+    }
+}
+
+impl Default for CombatMeter {
+    fn default() -> Self {
+        Self::new(DEFAULT_METER_WINDOW)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::entities::EnemyType;
+    use crate::game::events::DamageNumberEvent;
+    use crate::utils::Vec2;
+
+    fn hit(amount: f32) -> GameEvent {
+        GameEvent::DamageNumber(DamageNumberEvent {
+            position: Vec2::new(0.0, 0.0),
+            amount,
+            crit: false,
+        })
+    }
+
+    fn kill() -> GameEvent {
+        GameEvent::EnemyKilled {
+            enemy_type: EnemyType::Fighter,
+            is_elite: false,
+            is_boss: false,
+        }
+    }
+
+    #[test]
+    fn test_windowed_dps() {
+        let mut meter = CombatMeter::default();
+        assert_eq!(meter.dps(), 0.0);
+
+        // 10 damage every half second for 10 seconds
+        for _ in 0..20 {
+            meter.update(0.5);
+            meter.on_event(&hit(10.0));
+        }
+        assert!((meter.dps() - 20.0).abs() < 0.001);
+
+        // Before a full window has passed, DPS covers the time played so far
+        let mut fresh = CombatMeter::default();
+        fresh.update(2.0);
+        fresh.on_event(&hit(30.0));
+        assert!((fresh.dps() - 15.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_old_samples_age_out() {
+        let mut meter = CombatMeter::new(5.0);
+        meter.update(1.0);
+        meter.on_event(&hit(100.0));
+        meter.on_event(&kill());
+        meter.on_event(&kill());
+        assert!((meter.kills_per_minute() - 120.0).abs() < 0.001);
+
+        meter.update(4.5);
+        assert!(meter.dps() > 0.0);
+
+        meter.update(1.0);
+        assert_eq!(meter.dps(), 0.0);
+        assert_eq!(meter.kills_per_minute(), 0.0);
+    }
+
+    #[test]
+    fn test_rapid_hits_all_count() {
+        let mut meter = CombatMeter::new(5.0);
+        // 1000 hits a second, far more than a fixed-size buffer would hold
+        for _ in 0..5000 {
+            meter.update(0.001);
+            meter.on_event(&hit(1.0));
+        }
+        assert!((meter.dps() - 1000.0).abs() < 1.0, "{}", meter.dps());
+
+        // Samples are dropped once they leave the window
+        meter.update(5.0);
+        assert!(meter.damage.is_empty());
+    }
+}
@@ -3,9 +3,27 @@ pub mod collision;
 pub mod ai;
 pub mod procedural;
 pub mod upgrade;
+pub mod collectible;
+pub mod director;
+pub mod particle;
+pub mod ability;
+pub mod naming;
+pub mod boss;
+pub mod damage;
+pub mod meter;
+pub mod telegraph;
 
 pub use weapon::*;
 pub use collision::*;
 pub use ai::*;
 pub use procedural::*;
 pub use upgrade::*;
+pub use collectible::*;
+pub use director::*;
+pub use particle::*;
+pub use ability::*;
+pub use naming::*;
+pub use boss::*;
+pub use damage::*;
+pub use meter::*;
+pub use telegraph::*;
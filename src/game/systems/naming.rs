@@ -0,0 +1,134 @@
+use crate::game::entities::EnemyType;
+use crate::game::systems::procedural::ZoneType;
+use rand::Rng;
+
+/// Zones past this number draw from the more ominous adjective table
+const LATE_ZONE: u32 = 3;
+
+const EARLY_ADJECTIVES: &[&str] = &[
+    "Silent", "Golden", "Drifting", "Endless", "Quiet", "Open", "Pale", "Distant",
+];
+
+const LATE_ADJECTIVES: &[&str] = &[
+    "Shattered",
+    "Burning",
+    "Forsaken",
+    "Howling",
+    "Black",
+    "Broken",
+    "Dying",
+    "Iron",
+];
+
+const BOSS_EPITHETS: &[&str] = &[
+    "Crimson", "Iron", "Silent", "Burning", "Black", "Storm", "Ghost", "Grim",
+];
+
+fn zone_nouns(zone_type: ZoneType) -> &'static [&'static str] {
+    match zone_type {
+        ZoneType::Sky | ZoneType::Unknown => &["Expanse", "Heights", "Skies", "Frontier"],
+        ZoneType::Clouds => &["Veil", "Shroud", "Billows", "Haze"],
+        ZoneType::Ocean => &["Deep", "Straits", "Reach", "Tides"],
+        ZoneType::Mountains => &["Peaks", "Spires", "Crags", "Pass"],
+        ZoneType::Desert => &["Wastes", "Dunes", "Barrens", "Sands"],
+    }
+}
+
+fn boss_titles(enemy_type: EnemyType) -> &'static [&'static str] {
+    match enemy_type {
+        EnemyType::Fighter | EnemyType::Unknown => &["Hawk", "Falcon", "Viper"],
+        EnemyType::Bomber => &["Leviathan", "Colossus", "Juggernaut"],
+        EnemyType::Ace => &["Baron", "Phantom", "Knight"],
+        EnemyType::Kamikaze => &["Comet", "Wildfire", "Meteor"],
+        EnemyType::HeavyBomber => &["Fortress", "Behemoth", "Titan"],
+    }
+}
+
+fn pick<R: Rng>(rng: &mut R, table: &[&'static str]) -> &'static str {
+    table[rng.gen_range(0..table.len())]
+}
+
+/// Flavor name for a zone, e.g. "The Shattered Expanse". Deterministic for a
+/// given RNG state; later zones get darker adjectives.
+pub fn generate_zone_name<R: Rng>(zone_type: ZoneType, zone_number: u32, rng: &mut R) -> String {
+    let adjectives = if zone_number > LATE_ZONE {
+        LATE_ADJECTIVES
+    } else {
+        EARLY_ADJECTIVES
+    };
+    let adjective = pick(rng, adjectives);
+    let noun = pick(rng, zone_nouns(zone_type));
+    format!("The {} {}", adjective, noun)
+}
+
+/// Flavor name for a boss, e.g. "The Crimson Baron"
+pub fn generate_boss_name<R: Rng>(enemy_type: EnemyType, rng: &mut R) -> String {
+    let epithet = pick(rng, BOSS_EPITHETS);
+    let title = pick(rng, boss_titles(enemy_type));
+    format!("The {} {}", epithet, title)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const ZONE_TYPES: [ZoneType; 6] = [
+        ZoneType::Sky,
+        ZoneType::Clouds,
+        ZoneType::Ocean,
+        ZoneType::Mountains,
+        ZoneType::Desert,
+        ZoneType::Unknown,
+    ];
+
+    fn zone_name(seed: u64, zone_type: ZoneType, zone_number: u32) -> String {
+        generate_zone_name(zone_type, zone_number, &mut StdRng::seed_from_u64(seed))
+    }
+
+    #[test]
+    fn test_zone_name_is_deterministic() {
+        for zone_type in ZONE_TYPES {
+            assert_eq!(zone_name(42, zone_type, 2), zone_name(42, zone_type, 2));
+        }
+    }
+
+    #[test]
+    fn test_zone_names_vary_by_seed() {
+        let names: std::collections::HashSet<String> = (0..20)
+            .map(|seed| zone_name(seed, ZoneType::Ocean, 1))
+            .collect();
+        assert!(names.len() > 1);
+    }
+
+    #[test]
+    fn test_names_non_empty_for_every_type() {
+        let mut rng = StdRng::seed_from_u64(7);
+        for zone_type in ZONE_TYPES {
+            for zone_number in [1, 5] {
+                let name = generate_zone_name(zone_type, zone_number, &mut rng);
+                assert!(name.starts_with("The ") && name.len() > 4, "{}", name);
+            }
+        }
+
+        for enemy_type in [
+            EnemyType::Fighter,
+            EnemyType::Bomber,
+            EnemyType::Ace,
+            EnemyType::Kamikaze,
+            EnemyType::HeavyBomber,
+            EnemyType::Unknown,
+        ] {
+            let name = generate_boss_name(enemy_type, &mut rng);
+            assert!(name.starts_with("The ") && name.len() > 4, "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_boss_name_is_deterministic() {
+        let a = generate_boss_name(EnemyType::Ace, &mut StdRng::seed_from_u64(3));
+        let b = generate_boss_name(EnemyType::Ace, &mut StdRng::seed_from_u64(3));
+        assert_eq!(a, b);
+    }
+}
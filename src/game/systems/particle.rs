@@ -0,0 +1,254 @@
+use crate::utils::{Color, Gradient, ObjectPool, Vec2};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// A short-lived visual particle (explosion debris, engine trail...)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Particle {
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub lifetime: f32,
+    pub age: f32,
+    pub color_over_life: Gradient,
+    pub size: f32,
+    pub gravity: Vec2,
+    pub drag: f32,
+}
+
+impl Particle {
+    pub fn update(&mut self, delta: f32) {
+        self.velocity += self.gravity * delta;
+        self.velocity *= (1.0 - self.drag * delta).max(0.0);
+        self.position += self.velocity * delta;
+        self.age += delta;
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.age < self.lifetime
+    }
+
+    /// How far through its life the particle is, from 0 (new) to 1 (expired)
+    pub fn life_fraction(&self) -> f32 {
+        if self.lifetime <= 0.0 {
+            return 1.0;
+        }
+        (self.age / self.lifetime).min(1.0)
+    }
+
+    pub fn color(&self) -> Color {
+        self.color_over_life.evaluate(self.life_fraction())
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+impl Default for Particle {
+    fn default() -> Self {
+        Self {
+            position: Vec2::new(0.0, 0.0),
+            velocity: Vec2::new(0.0, 0.0),
+            lifetime: 0.0,
+            age: 0.0,
+            color_over_life: Gradient::new(Vec::new()),
+            size: 1.0,
+            gravity: Vec2::new(0.0, 0.0),
+            drag: 0.0,
+        }
+    }
+}
+
+/// Describes how a burst of particles is emitted
+#[derive(Debug, Clone, PartialEq)]
+pub struct Emitter {
+    pub position: Vec2,
+    /// Central emission angle in radians
+    pub direction: f32,
+    /// Total angle particles are spread over; `TAU` emits in all directions
+    pub spread: f32,
+    pub speed: f32,
+    pub lifetime: f32,
+    pub color_over_life: Gradient,
+    pub size: f32,
+    /// Constant acceleration applied to every particle
+    pub gravity: Vec2,
+    /// Fraction of velocity lost per second
+    pub drag: f32,
+}
+
+pub struct ParticleSystem {
+    pool: ObjectPool<Particle>,
+    particles: Vec<Particle>,
+    rng: StdRng,
+}
+
+impl ParticleSystem {
+    pub fn new(max_particles: usize) -> Self {
+        Self::with_rng(max_particles, StdRng::from_entropy())
+    }
+
+    /// Creates a system whose emission angles are determined by `seed`
+    pub fn with_seed(max_particles: usize, seed: u64) -> Self {
+        Self::with_rng(max_particles, StdRng::seed_from_u64(seed))
+    }
+
+    fn with_rng(max_particles: usize, rng: StdRng) -> Self {
+        Self {
+            pool: ObjectPool::new(Particle::default, Particle::reset, max_particles),
+            particles: Vec::new(),
+            rng,
+        }
+    }
+
+    /// Emits up to `count` particles, fewer if the pool runs dry. Returns
+    /// the number actually emitted.
+    pub fn emit(&mut self, emitter: &Emitter, count: u32) -> u32 {
+        let mut emitted = 0;
+        for _ in 0..count {
+            let mut particle = match self.pool.acquire() {
+                Some(particle) => particle,
+                None => break,
+            };
+
+            let offset = (self.rng.gen::<f32>() - 0.5) * emitter.spread;
+            let angle = emitter.direction + offset;
+            particle.position = emitter.position;
+            particle.velocity = Vec2::new(angle.cos(), angle.sin()) * emitter.speed;
+            particle.lifetime = emitter.lifetime;
+            particle.color_over_life = emitter.color_over_life.clone();
+            particle.size = emitter.size;
+            particle.gravity = emitter.gravity;
+            particle.drag = emitter.drag;
+
+            self.particles.push(particle);
+            emitted += 1;
+        }
+        emitted
+    }
+
+    /// Advances all particles and returns expired ones to the pool
+    pub fn update(&mut self, delta: f32) {
+        let mut i = 0;
+        while i < self.particles.len() {
+            self.particles[i].update(delta);
+            if self.particles[i].is_alive() {
+                i += 1;
+            } else {
+                let particle = self.particles.swap_remove(i);
+                self.pool.release(particle);
+            }
+        }
+    }
+
+    /// Live particles, for rendering
+    pub fn particles(&self) -> &[Particle] {
+        &self.particles
+    }
+
+    pub fn live_count(&self) -> usize {
+        self.particles.len()
+    }
+
+    pub fn available_count(&self) -> usize {
+        self.pool.available_count()
+    }
+
+    pub fn clear(&mut self) {
+        for particle in self.particles.drain(..) {
+            self.pool.release(particle);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::TAU;
+
+    fn fade_out() -> Gradient {
+        Gradient::new(vec![
+            (0.0, Color::new(1.0, 0.5, 0.0, 1.0)),
+            (1.0, Color::new(1.0, 0.5, 0.0, 0.0)),
+        ])
+    }
+
+    fn explosion() -> Emitter {
+        Emitter {
+            position: Vec2::new(10.0, 20.0),
+            direction: 0.0,
+            spread: TAU,
+            speed: 50.0,
+            lifetime: 1.0,
+            color_over_life: fade_out(),
+            size: 2.0,
+            gravity: Vec2::new(0.0, 0.0),
+            drag: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_emit_spawns_requested_count() {
+        let mut system = ParticleSystem::with_seed(100, 1);
+
+        assert_eq!(system.emit(&explosion(), 30), 30);
+        assert_eq!(system.live_count(), 30);
+        for particle in system.particles() {
+            assert_eq!(particle.position, Vec2::new(10.0, 20.0));
+            assert_eq!(particle.size, 2.0);
+        }
+
+        // A full pool caps emission
+        assert_eq!(system.emit(&explosion(), 100), 70);
+        assert_eq!(system.live_count(), 100);
+    }
+
+    #[test]
+    fn test_particles_fade_over_lifetime() {
+        let mut system = ParticleSystem::with_seed(10, 1);
+        system.emit(&explosion(), 1);
+
+        assert_eq!(system.particles()[0].color().a, 1.0);
+
+        system.update(0.25);
+        assert!((system.particles()[0].color().a - 0.75).abs() < 0.001);
+
+        system.update(0.5);
+        assert!((system.particles()[0].color().a - 0.25).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_gravity_and_drag() {
+        let mut system = ParticleSystem::with_seed(10, 1);
+        let emitter = Emitter {
+            speed: 0.0,
+            gravity: Vec2::new(0.0, 100.0),
+            drag: 0.5,
+            ..explosion()
+        };
+        system.emit(&emitter, 1);
+
+        system.update(0.1);
+        let particle = &system.particles()[0];
+        assert!((particle.velocity.y - 9.5).abs() < 0.001);
+        assert!(particle.position.y > 20.0);
+    }
+
+    #[test]
+    fn test_expired_particles_return_to_pool() {
+        let mut system = ParticleSystem::with_seed(10, 1);
+        system.emit(&explosion(), 10);
+        assert_eq!(system.available_count(), 0);
+
+        system.update(0.5);
+        assert_eq!(system.live_count(), 10);
+
+        system.update(0.6);
+        assert_eq!(system.live_count(), 0);
+        assert_eq!(system.available_count(), 10);
+
+        // Recycled particles are reused rather than hitting the pool limit
+        assert_eq!(system.emit(&explosion(), 10), 10);
+        assert_eq!(system.particles()[0].age, 0.0);
+    }
+}
@@ -1,40 +1,104 @@
+use crate::game::balance::{BalanceConfig, DifficultyBalance, WaveBalance};
+use crate::game::components::Health;
 use crate::game::entities::EnemyType;
 use crate::game::systems::ai::{AIBehavior, Formation, Path, WavePattern};
-use crate::utils::Vec2;
-use rand::{Rng, SeedableRng};
+use crate::game::systems::director::{MAX_PACING, MIN_PACING};
+use crate::game::systems::naming::generate_zone_name;
+use crate::utils::{Vec2, AABB};
+use cgmath::InnerSpace;
+use rand::{Error, Rng, RngCore, SeedableRng};
 use rand::rngs::StdRng;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ops::Range;
+use std::rc::Rc;
 
+type ZoneHook = Rc<dyn Fn(&ZoneTelemetry)>;
+
+#[derive(Clone)]
 pub struct ProceduralGenerator {
-    rng: StdRng,
+    rng: TrackedRng,
     wave_templates: Vec<WaveTemplate>,
     terrain_generator: TerrainGenerator,
     difficulty_manager: DifficultyManager,
+    wave_balance: WaveBalance,
+    play_field: PlayField,
+    /// Shared with clones, so a forked generator reports to the same tooling
+    on_zone_generated: Option<ZoneHook>,
 }
 
 impl ProceduralGenerator {
     pub fn new(seed: u64) -> Self {
+        Self::with_balance(seed, &BalanceConfig::default())
+    }
+
+    pub fn with_balance(seed: u64, balance: &BalanceConfig) -> Self {
         let mut generator = Self {
-            rng: StdRng::seed_from_u64(seed),
+            rng: TrackedRng::new(seed),
             wave_templates: Vec::new(),
             terrain_generator: TerrainGenerator::new(),
-            difficulty_manager: DifficultyManager::new(),
+            difficulty_manager: DifficultyManager::with_balance(balance),
+            wave_balance: balance.waves,
+            play_field: PlayField::default(),
+            on_zone_generated: None,
         };
 
         generator.init_wave_templates();
         generator
     }
 
+    /// Swaps in new balance values, e.g. after the config file is edited.
+    /// Takes effect from the next generated wave.
+    pub fn set_balance(&mut self, balance: &BalanceConfig) {
+        self.wave_balance = balance.waves;
+        self.difficulty_manager.set_balance(balance);
+    }
+
+    /// Snapshot of the RNG so tooling can preview generation and rewind, and
+    /// saves can resume mid-run
+    pub fn rng_state(&self) -> RngState {
+        self.rng.state
+    }
+
+    pub fn restore_rng(&mut self, state: RngState) {
+        self.rng = TrackedRng::restore(state);
+    }
+
+    /// Feeds the director's pacing into zone difficulty and spawn timing
+    pub fn set_pacing(&mut self, pacing: f32) {
+        self.difficulty_manager.set_pacing(pacing);
+    }
+
+    /// Sizes generated content to the visible play field, e.g. after the
+    /// viewport is resized
+    pub fn set_play_field(&mut self, play_field: PlayField) {
+        self.play_field = play_field;
+    }
+
+    pub fn play_field(&self) -> &PlayField {
+        &self.play_field
+    }
+
+    /// Registers a callback run with balancing telemetry after each
+    /// `generate_zone`, replacing any previous one
+    pub fn set_on_zone_generated(&mut self, hook: impl Fn(&ZoneTelemetry) + 'static) {
+        self.on_zone_generated = Some(Rc::new(hook));
+    }
+
+    pub fn clear_on_zone_generated(&mut self) {
+        self.on_zone_generated = None;
+    }
+
     fn init_wave_templates(&mut self) {
         // Basic fighter wave
         self.wave_templates.push(WaveTemplate {
             name: "Fighter Squadron".to_string(),
             enemy_types: vec![EnemyType::Fighter],
             formation: Formation::V { spacing: 50.0 },
-            base_count: 5,
             min_difficulty: 0.0,
             max_difficulty: 1.0,
             zone_types: vec![ZoneType::Sky, ZoneType::Clouds],
+            max_per_type: HashMap::new(),
         });
 
         // Bomber formation
@@ -45,10 +109,10 @@ impl ProceduralGenerator {
                 spacing: 80.0,
                 angle: 0.0,
             },
-            base_count: 3,
             min_difficulty: 0.3,
             max_difficulty: 1.0,
             zone_types: vec![ZoneType::Sky, ZoneType::Ocean],
+            max_per_type: HashMap::new(),
         });
 
         // Mixed assault
@@ -56,10 +120,10 @@ impl ProceduralGenerator {
             name: "Mixed Assault".to_string(),
             enemy_types: vec![EnemyType::Fighter, EnemyType::Bomber],
             formation: Formation::Diamond,
-            base_count: 7,
             min_difficulty: 0.5,
             max_difficulty: 1.0,
             zone_types: vec![ZoneType::Sky, ZoneType::Clouds, ZoneType::Mountains],
+            max_per_type: HashMap::new(),
         });
 
         // Ace encounter
@@ -67,10 +131,10 @@ impl ProceduralGenerator {
             name: "Ace Patrol".to_string(),
             enemy_types: vec![EnemyType::Ace],
             formation: Formation::Circle { radius: 150.0 },
-            base_count: 2,
             min_difficulty: 0.7,
             max_difficulty: 1.0,
             zone_types: vec![ZoneType::Sky, ZoneType::Clouds, ZoneType::Mountains],
+            max_per_type: HashMap::new(),
         });
 
         // Kamikaze rush
@@ -78,10 +142,10 @@ impl ProceduralGenerator {
             name: "Kamikaze Wave".to_string(),
             enemy_types: vec![EnemyType::Kamikaze],
             formation: Formation::V { spacing: 30.0 },
-            base_count: 8,
             min_difficulty: 0.4,
             max_difficulty: 1.0,
             zone_types: vec![ZoneType::Ocean, ZoneType::Desert],
+            max_per_type: HashMap::new(),
         });
     }
 
@@ -97,18 +161,40 @@ impl ProceduralGenerator {
         let wave_count = self.calculate_wave_count(difficulty);
         for i in 0..wave_count {
             let wave_difficulty = difficulty * (1.0 + i as f32 * 0.1);
-            let wave = self.generate_wave(zone_type, wave_difficulty);
+            let mut wave = self.generate_wave(zone_type, wave_difficulty);
+            let pacing = self.difficulty_manager.pacing();
+            wave.spawn_delay /= pacing;
+            for time in &mut wave.spawn_schedule {
+                *time /= pacing;
+            }
             zone.waves.push(wave);
         }
 
-        // Add hazards
-        let hazards = self.generate_hazards(&zone_type, difficulty);
+        // Hazards and collectibles draw from their own streams, so the
+        // layout doesn't shift with how much RNG the waves used
+        let hazards = self.generate_hazards(&zone_type, zone_number, difficulty);
         zone.hazards = hazards;
 
         // Place collectibles
-        let collectibles = self.generate_collectibles(difficulty);
+        let collectibles = self.generate_collectibles(zone_number, difficulty);
         zone.collectibles = collectibles;
 
+        zone.events = self.generate_events(zone_number, zone.waves.len());
+
+        zone.name = generate_zone_name(zone_type, zone_number, &mut self.rng);
+
+        if let Some(hook) = &self.on_zone_generated {
+            hook(&ZoneTelemetry {
+                zone_type,
+                zone_number,
+                seed: self.rng.state.seed,
+                difficulty,
+                wave_count: zone.waves.len(),
+                total_threat: zone.waves.iter().map(Wave::total_threat).sum(),
+                hazard_count: zone.hazards.len(),
+            });
+        }
+
         zone
     }
 
@@ -140,49 +226,117 @@ impl ProceduralGenerator {
         self.instantiate_wave(template, difficulty)
     }
 
-    fn instantiate_wave(&mut self, template: &WaveTemplate, difficulty: f32) -> Wave {
-        let enemy_count = (template.base_count as f32 * (1.0 + difficulty * 0.3)) as u32;
+    /// Comparable difficulty score for a wave, without spawning it: the
+    /// wave's threat scaled by its stat multipliers, with the elite counted
+    /// at its buffed strength
+    pub fn estimate_wave_difficulty(&self, wave: &Wave) -> f32 {
+        let mut threat = wave.total_threat();
+
+        // Waves saved before `elite_index` assume the strongest enemy is elite
+        if wave.has_elite {
+            let elite_cost = match wave.elite_index.and_then(|i| wave.enemy_composition.get(i)) {
+                Some(&elite) => threat_cost(elite),
+                None => wave
+                    .enemy_composition
+                    .iter()
+                    .map(|&e| threat_cost(e))
+                    .fold(0.0, f32::max),
+            };
+            threat += elite_cost * (ELITE_THREAT_MULTIPLIER - 1.0);
+        }
+
+        threat * wave.health_multiplier * wave.damage_multiplier * wave.speed_multiplier
+    }
 
-        // Select enemy type distribution
+    fn instantiate_wave(&mut self, template: &WaveTemplate, difficulty: f32) -> Wave {
+        // Fill the wave up to its threat budget so composition is balanced by
+        // total threat rather than raw count
+        let mut remaining = wave_threat_budget(difficulty);
         let mut enemy_composition = Vec::new();
-        for _ in 0..enemy_count {
-            let enemy_type =
-                template.enemy_types[self.rng.gen_range(0..template.enemy_types.len())];
+        let mut type_counts: HashMap<EnemyType, u32> = HashMap::new();
+        loop {
+            let affordable: Vec<EnemyType> = template
+                .enemy_types
+                .iter()
+                .copied()
+                .filter(|&enemy_type| threat_cost(enemy_type) <= remaining)
+                .filter(|enemy_type| {
+                    let count = type_counts.get(enemy_type).copied().unwrap_or(0);
+                    template
+                        .max_per_type
+                        .get(enemy_type)
+                        .is_none_or(|&cap| count < cap)
+                })
+                .collect();
+
+            if affordable.is_empty() {
+                break;
+            }
+
+            let enemy_type = affordable[self.rng.gen_range(0..affordable.len())];
+            remaining -= threat_cost(enemy_type);
+            *type_counts.entry(enemy_type).or_insert(0) += 1;
             enemy_composition.push(enemy_type);
         }
+        let enemy_count = enemy_composition.len() as u32;
 
         // Create spawn pattern
         let spawn_positions = self.generate_formation_positions(&template.formation, enemy_count);
+        let spawn_delay = 0.5;
+        let spawn_pattern = match self.rng.gen_range(0..3) {
+            0 => SpawnPattern::AllAtOnce,
+            1 => SpawnPattern::Staggered {
+                interval: spawn_delay,
+            },
+            _ => SpawnPattern::Bursts {
+                size: 3,
+                interval: spawn_delay * 3.0,
+            },
+        };
+
+        // The elite roll and its pick come last, from the seeded rng, so a
+        // replayed seed gets the same elite
+        let elite_chance = self.wave_balance.elite_chance(difficulty);
+        let has_elite = self.rng.gen_bool(elite_chance) && !enemy_composition.is_empty();
+        let elite_index = has_elite.then(|| self.rng.gen_range(0..enemy_composition.len()));
 
         Wave {
             enemy_composition,
             spawn_positions,
-            health_multiplier: 1.0 + difficulty * 0.2,
-            damage_multiplier: 1.0 + difficulty * 0.15,
-            speed_multiplier: 1.0 + difficulty * 0.1,
-            spawn_delay: 0.5,
-            has_elite: self.rng.gen_bool(difficulty as f64 * 0.3),
+            health_multiplier: self.wave_balance.health_multiplier(difficulty),
+            damage_multiplier: self.wave_balance.damage_multiplier(difficulty),
+            speed_multiplier: self.wave_balance.speed_multiplier(difficulty),
+            spawn_delay,
+            spawn_schedule: spawn_pattern.schedule(enemy_count as usize),
+            has_elite,
+            elite_index,
+            is_boss: false,
         }
     }
 
     fn create_default_wave(&mut self, difficulty: f32) -> Wave {
+        let anchor = self.play_field.spawn_anchor();
         Wave {
             enemy_composition: vec![EnemyType::Fighter; 3],
             spawn_positions: vec![
-                Vec2::new(-50.0, -100.0),
-                Vec2::new(0.0, -100.0),
-                Vec2::new(50.0, -100.0),
+                anchor + Vec2::new(-50.0, 0.0),
+                anchor,
+                anchor + Vec2::new(50.0, 0.0),
             ],
-            health_multiplier: 1.0 + difficulty * 0.2,
-            damage_multiplier: 1.0 + difficulty * 0.15,
-            speed_multiplier: 1.0 + difficulty * 0.1,
+            health_multiplier: self.wave_balance.health_multiplier(difficulty),
+            damage_multiplier: self.wave_balance.damage_multiplier(difficulty),
+            speed_multiplier: self.wave_balance.speed_multiplier(difficulty),
             spawn_delay: 0.5,
+            spawn_schedule: SpawnPattern::AllAtOnce.schedule(3),
             has_elite: false,
+            elite_index: None,
+            is_boss: false,
         }
     }
 
     fn generate_formation_positions(&mut self, formation: &Formation, count: u32) -> Vec<Vec2> {
         let mut positions = Vec::new();
+        let anchor = self.play_field.spawn_anchor();
 
         match formation {
             Formation::V { spacing } => {
@@ -193,10 +347,8 @@ impl ProceduralGenerator {
                     } else {
                         -(i as i32 / 2) - 1
                     };
-                    positions.push(Vec2::new(
-                        col as f32 * spacing,
-                        -row as f32 * spacing - 100.0,
-                    ));
+                    let offset = Vec2::new(col as f32 * spacing, -row as f32 * spacing);
+                    positions.push(anchor + offset);
                 }
             }
 
@@ -205,7 +357,7 @@ impl ProceduralGenerator {
                 let dir = Vec2::new(angle_rad.cos(), angle_rad.sin());
                 for i in 0..count {
                     let offset = (i as f32 - count as f32 / 2.0) * spacing;
-                    positions.push(dir * offset + Vec2::new(0.0, -100.0));
+                    positions.push(dir * offset + anchor);
                 }
             }
 
@@ -213,10 +365,32 @@ impl ProceduralGenerator {
                 let angle_step = 2.0 * std::f32::consts::PI / count as f32;
                 for i in 0..count {
                     let angle = angle_step * i as f32;
-                    positions.push(Vec2::new(
-                        angle.cos() * radius,
-                        angle.sin() * radius - 100.0,
-                    ));
+                    positions.push(anchor + Vec2::new(angle.cos() * radius, angle.sin() * radius));
+                }
+            }
+
+            Formation::Arc {
+                radius,
+                start_angle,
+                sweep,
+            } => {
+                let start_rad = start_angle.to_radians();
+                let sweep_rad = sweep.to_radians();
+                // A single enemy sits in the middle of the arc
+                let step = if count > 1 {
+                    sweep_rad / (count - 1) as f32
+                } else {
+                    0.0
+                };
+                let first = if count > 1 {
+                    start_rad
+                } else {
+                    start_rad + sweep_rad / 2.0
+                };
+
+                for i in 0..count {
+                    let angle = first + step * i as f32;
+                    positions.push(anchor + Vec2::new(angle.cos() * radius, angle.sin() * radius));
                 }
             }
 
@@ -228,8 +402,8 @@ impl ProceduralGenerator {
                     } else {
                         (count - i - 1) as f32 * 40.0
                     } - half as f32 * 20.0;
-                    let y = -i as f32 * 40.0 - 100.0;
-                    positions.push(Vec2::new(x, y));
+                    let y = -i as f32 * 40.0;
+                    positions.push(anchor + Vec2::new(x, y));
                 }
             }
 
@@ -238,27 +412,38 @@ impl ProceduralGenerator {
             }
         }
 
+        separate_positions(&mut positions, MIN_SPAWN_SEPARATION);
         positions
     }
 
-    fn generate_hazards(&mut self, zone_type: &ZoneType, difficulty: f32) -> Vec<Hazard> {
+    /// RNG for one part of a zone's layout, seeded from the run seed, the
+    /// stream and the zone alone
+    fn stream_rng(&self, stream: RngStream, zone_number: u32) -> StdRng {
+        StdRng::seed_from_u64(stream_seed(self.rng.state.seed, stream, zone_number))
+    }
+
+    fn generate_hazards(
+        &self,
+        zone_type: &ZoneType,
+        zone_number: u32,
+        difficulty: f32,
+    ) -> Vec<Hazard> {
+        let mut rng = self.stream_rng(RngStream::Hazards, zone_number);
         let mut hazards = Vec::new();
         let hazard_count = (difficulty * 5.0) as u32;
 
         for _ in 0..hazard_count {
             let hazard_type = match zone_type {
-                ZoneType::Sky | ZoneType::Clouds => HazardType::Lightning,
+                ZoneType::Sky | ZoneType::Clouds | ZoneType::Unknown => HazardType::Lightning,
                 ZoneType::Ocean => HazardType::Waterspout,
                 ZoneType::Mountains => HazardType::WindShear,
                 ZoneType::Desert => HazardType::Sandstorm,
             };
 
+            let bounds = self.play_field.bounds;
             hazards.push(Hazard {
                 hazard_type,
-                position: Vec2::new(
-                    self.rng.gen_range(-500.0..500.0),
-                    self.rng.gen_range(-300.0..300.0),
-                ),
+                position: random_point_in(&mut rng, &bounds),
                 radius: 50.0,
                 damage_per_second: 10.0 * (1.0 + difficulty),
             });
@@ -267,31 +452,269 @@ impl ProceduralGenerator {
         hazards
     }
 
-    fn generate_collectibles(&mut self, difficulty: f32) -> Vec<Collectible> {
+    fn generate_collectibles(&self, zone_number: u32, difficulty: f32) -> Vec<Collectible> {
+        let mut rng = self.stream_rng(RngStream::Collectibles, zone_number);
         let mut collectibles = Vec::new();
-        let count = self.rng.gen_range(3..8);
+        let count = rng.gen_range(3..8);
 
         for _ in 0..count {
-            let collectible_type = if self.rng.gen_bool(0.7) {
+            let collectible_type = if rng.gen_bool(0.7) {
                 CollectibleType::HealthPack
-            } else if self.rng.gen_bool(0.5) {
+            } else if rng.gen_bool(0.5) {
                 CollectibleType::Ammo
             } else {
                 CollectibleType::PowerUp
             };
 
+            let area = self.play_field.inner_bounds();
             collectibles.push(Collectible {
                 collectible_type,
-                position: Vec2::new(
-                    self.rng.gen_range(-400.0..400.0),
-                    self.rng.gen_range(-200.0..200.0),
-                ),
+                position: random_point_in(&mut rng, &area),
                 value: (10.0 * (1.0 + difficulty * 0.5)) as u32,
             });
         }
 
         collectibles
     }
+
+    /// Rolls for a special event, placed between two of the zone's waves.
+    /// Uses its own stream like the rest of the layout.
+    fn generate_events(&self, zone_number: u32, wave_count: usize) -> Vec<ScheduledEvent> {
+        let mut rng = self.stream_rng(RngStream::Events, zone_number);
+        if wave_count < 2 || !rng.gen_bool(self.wave_balance.event_chance()) {
+            return Vec::new();
+        }
+
+        let before_wave = rng.gen_range(1..wave_count);
+        let event = match rng.gen_range(0..3) {
+            0 => {
+                let position = random_point_in(&mut rng, &self.play_field.inner_bounds());
+                let mut collectibles = Vec::new();
+                for _ in 0..rng.gen_range(2..5) {
+                    let content = rng.gen_range(0..SUPPLY_DROP_CONTENTS.len());
+                    collectibles.push(SUPPLY_DROP_CONTENTS[content]);
+                }
+                SpecialEvent::SupplyDrop {
+                    position,
+                    collectibles,
+                }
+            }
+            1 => SpecialEvent::EliteAmbush {
+                enemy_type: AMBUSH_ENEMIES[rng.gen_range(0..AMBUSH_ENEMIES.len())],
+                count: rng.gen_range(2..=4),
+            },
+            _ => {
+                let mut obstacles = Vec::new();
+                for _ in 0..rng.gen_range(5..10) {
+                    let size = rng.gen_range(20.0..60.0);
+                    obstacles.push(Obstacle {
+                        position: random_point_in(&mut rng, &self.play_field.bounds),
+                        size: Vec2::new(size, size),
+                        damage_on_collision: 20.0,
+                    });
+                }
+                SpecialEvent::AsteroidField { obstacles }
+            }
+        };
+
+        vec![ScheduledEvent { before_wave, event }]
+    }
+}
+
+/// Closest two enemies in a formation may spawn to each other
+pub const MIN_SPAWN_SEPARATION: f32 = 30.0;
+/// Relaxation passes before `separate_positions` gives up
+const SEPARATION_ITERATIONS: usize = 100;
+
+/// Nudges apart any positions closer than `min_separation`, moving each
+/// pair only as far as needed so the overall shape is kept. Coincident
+/// points are split along a per-pair direction. Deterministic and uses no
+/// RNG, so generation sequences are unaffected.
+fn separate_positions(positions: &mut [Vec2], min_separation: f32) {
+    // Push a little past the minimum so float error can't leave pairs short
+    let target = min_separation * 1.001;
+
+    for _ in 0..SEPARATION_ITERATIONS {
+        let mut moved = false;
+        for i in 0..positions.len() {
+            for j in i + 1..positions.len() {
+                let delta = positions[j] - positions[i];
+                let distance = delta.magnitude();
+                if distance >= min_separation {
+                    continue;
+                }
+
+                let direction = if distance > f32::EPSILON {
+                    delta / distance
+                } else {
+                    // Golden-angle spacing keeps coincident pairs from all
+                    // splitting the same way
+                    let angle = (i * positions.len() + j) as f32 * 2.399_963;
+                    Vec2::new(angle.cos(), angle.sin())
+                };
+                let push = direction * ((target - distance) / 2.0);
+                positions[i] -= push;
+                positions[j] += push;
+                moved = true;
+            }
+        }
+
+        if !moved {
+            break;
+        }
+    }
+}
+
+/// Serializable RNG position: the seed and how many 32-bit words have been
+/// drawn since seeding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RngState {
+    pub seed: u64,
+    pub words_drawn: u64,
+}
+
+/// `StdRng` that counts what it hands out, since its internal state can't
+/// be serialized. Restoring replays the draws from the seed.
+#[derive(Clone)]
+struct TrackedRng {
+    rng: StdRng,
+    state: RngState,
+}
+
+impl TrackedRng {
+    fn new(seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            state: RngState {
+                seed,
+                words_drawn: 0,
+            },
+        }
+    }
+
+    fn restore(state: RngState) -> Self {
+        let mut tracked = Self::new(state.seed);
+        for _ in 0..state.words_drawn {
+            tracked.rng.next_u32();
+        }
+        tracked.state = state;
+        tracked
+    }
+
+    fn advance(&mut self, words: usize) {
+        self.state.words_drawn += words as u64;
+    }
+}
+
+// StdRng is a block RNG: a u64 always takes two words and byte fills round
+// up to whole words, so replaying the same word count lands on the same state
+impl RngCore for TrackedRng {
+    fn next_u32(&mut self) -> u32 {
+        self.advance(1);
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.advance(2);
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.advance(dest.len().div_ceil(4));
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+/// Parts of zone generation that get an RNG of their own rather than
+/// sharing the generator's main sequence
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RngStream {
+    Hazards = 1,
+    Collectibles = 2,
+    Events = 3,
+}
+
+/// Seed for `stream` in zone `zone_number` of a run, mixed with the
+/// SplitMix64 finalizer so neighbouring zones and streams are uncorrelated
+fn stream_seed(seed: u64, stream: RngStream, zone_number: u32) -> u64 {
+    let mut z = seed
+        ^ (stream as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (zone_number as u64).wrapping_mul(0xD1B5_4A32_D192_ED03);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Uniformly random point inside `bounds`; degenerate bounds yield their min
+fn random_point_in<R: Rng>(rng: &mut R, bounds: &AABB) -> Vec2 {
+    let mut axis = |min: f32, max: f32| {
+        if max > min {
+            rng.gen_range(min..max)
+        } else {
+            min
+        }
+    };
+    let x = axis(bounds.min.x, bounds.max.x);
+    let y = axis(bounds.min.y, bounds.max.y);
+    Vec2::new(x, y)
+}
+
+/// The visible play area that generated content is placed in
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PlayField {
+    pub bounds: AABB,
+    /// Distance collectibles keep from the edges, and how far above the
+    /// centre enemy formations are anchored
+    pub spawn_margin: f32,
+}
+
+impl PlayField {
+    pub fn new(bounds: AABB, spawn_margin: f32) -> Self {
+        Self {
+            bounds,
+            spawn_margin,
+        }
+    }
+
+    pub fn center(&self) -> Vec2 {
+        (self.bounds.min + self.bounds.max) * 0.5
+    }
+
+    /// Point enemy formations are laid out around
+    pub fn spawn_anchor(&self) -> Vec2 {
+        self.center() - Vec2::new(0.0, self.spawn_margin)
+    }
+
+    /// Bounds shrunk by the spawn margin, collapsing to the centre on
+    /// fields smaller than twice the margin
+    pub fn inner_bounds(&self) -> AABB {
+        let center = self.center();
+        let inset = |min: f32, max: f32, mid: f32| {
+            if max - min > self.spawn_margin * 2.0 {
+                (min + self.spawn_margin, max - self.spawn_margin)
+            } else {
+                (mid, mid)
+            }
+        };
+        let (min_x, max_x) = inset(self.bounds.min.x, self.bounds.max.x, center.x);
+        let (min_y, max_y) = inset(self.bounds.min.y, self.bounds.max.y, center.y);
+        AABB::new(Vec2::new(min_x, min_y), Vec2::new(max_x, max_y))
+    }
+}
+
+impl Default for PlayField {
+    /// 1000x600 field centred on the origin
+    fn default() -> Self {
+        Self::new(
+            AABB::new(Vec2::new(-500.0, -300.0), Vec2::new(500.0, 300.0)),
+            100.0,
+        )
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -301,16 +724,23 @@ pub enum ZoneType {
     Ocean,
     Mountains,
     Desert,
+    /// Zone from newer data; generated like `Sky`
+    #[serde(other)]
+    Unknown,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Zone {
     pub zone_type: ZoneType,
     pub zone_number: u32,
+    #[serde(default)]
+    pub name: String,
     pub terrain: Terrain,
     pub waves: Vec<Wave>,
     pub hazards: Vec<Hazard>,
     pub collectibles: Vec<Collectible>,
+    #[serde(default)]
+    pub events: Vec<ScheduledEvent>,
 }
 
 impl Zone {
@@ -318,15 +748,30 @@ impl Zone {
         Self {
             zone_type,
             zone_number,
+            name: String::new(),
             terrain: Terrain::default(),
             waves: Vec::new(),
             hazards: Vec::new(),
             collectibles: Vec::new(),
+            events: Vec::new(),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Balancing record for one generated zone
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ZoneTelemetry {
+    pub zone_type: ZoneType,
+    pub zone_number: u32,
+    pub seed: u64,
+    pub difficulty: f32,
+    pub wave_count: usize,
+    /// Summed `threat_cost` of every enemy across the zone's waves
+    pub total_threat: f32,
+    pub hazard_count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Wave {
     pub enemy_composition: Vec<EnemyType>,
     pub spawn_positions: Vec<Vec2>,
@@ -334,7 +779,304 @@ pub struct Wave {
     pub damage_multiplier: f32,
     pub speed_multiplier: f32,
     pub spawn_delay: f32,
+    /// Seconds after the wave starts at which each enemy spawns, in
+    /// nondecreasing order. Empty means everything spawns at once.
+    #[serde(default)]
+    pub spawn_schedule: Vec<f32>,
     pub has_elite: bool,
+    /// Entry of `enemy_composition` that spawns as the elite, set whenever
+    /// `has_elite` is
+    #[serde(default)]
+    pub elite_index: Option<usize>,
+    #[serde(default)]
+    pub is_boss: bool,
+}
+
+impl Wave {
+    pub fn total_threat(&self) -> f32 {
+        self.enemy_composition.iter().map(|&e| threat_cost(e)).sum()
+    }
+
+    /// Indices into `enemy_composition` of the enemies whose spawn time has
+    /// been reached after `elapsed` seconds
+    pub fn enemies_due(&self, elapsed: f32) -> Range<usize> {
+        if self.spawn_schedule.is_empty() {
+            return 0..self.enemy_composition.len();
+        }
+        0..self.spawn_schedule.partition_point(|&time| time <= elapsed)
+    }
+}
+
+/// How a wave's enemies are spread out over time
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SpawnPattern {
+    AllAtOnce,
+    /// One enemy every `interval` seconds
+    Staggered { interval: f32 },
+    /// Groups of `size` enemies, `interval` seconds apart
+    Bursts { size: usize, interval: f32 },
+}
+
+impl SpawnPattern {
+    pub fn schedule(&self, count: usize) -> Vec<f32> {
+        (0..count)
+            .map(|i| match *self {
+                SpawnPattern::AllAtOnce => 0.0,
+                SpawnPattern::Staggered { interval } => i as f32 * interval,
+                SpawnPattern::Bursts { size, interval } => (i / size.max(1)) as f32 * interval,
+            })
+            .collect()
+    }
+}
+
+/// Threat budget for a wave before multipliers are applied
+const BASE_WAVE_THREAT: f32 = 6.0;
+
+/// How much a single enemy of this type counts against a wave's budget
+pub fn threat_cost(enemy_type: EnemyType) -> f32 {
+    match enemy_type {
+        EnemyType::Kamikaze => 0.75,
+        EnemyType::Fighter | EnemyType::Unknown => 1.0,
+        EnemyType::Bomber => 2.0,
+        EnemyType::Ace => 4.0,
+        EnemyType::HeavyBomber => 6.0,
+    }
+}
+
+/// How much more threatening an elite is than a regular enemy of its type,
+/// matching the health and damage buffs of `EnemyStats::elite`
+pub const ELITE_THREAT_MULTIPLIER: f32 = 3.0;
+
+/// Total threat a wave is filled up to at the given difficulty
+pub fn wave_threat_budget(difficulty: f32) -> f32 {
+    BASE_WAVE_THREAT * (1.0 + difficulty.max(0.0))
+}
+
+/// Concrete stats for a spawned enemy
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnemyStats {
+    pub health: Health,
+    pub speed: f32,
+    pub damage: f32,
+}
+
+impl EnemyStats {
+    /// Unscaled stats for an enemy type
+    pub fn base(enemy_type: EnemyType) -> Self {
+        let (max_health, armor, speed, damage) = match enemy_type {
+            EnemyType::Fighter | EnemyType::Unknown => (30, 0.0, 150.0, 10.0),
+            EnemyType::Bomber => (80, 0.1, 80.0, 20.0),
+            EnemyType::Ace => (60, 0.1, 200.0, 15.0),
+            EnemyType::Kamikaze => (15, 0.0, 300.0, 40.0),
+            EnemyType::HeavyBomber => (200, 0.25, 60.0, 30.0),
+        };
+
+        Self {
+            health: Health::with_armor(max_health, armor),
+            speed,
+            damage,
+        }
+    }
+
+    /// Buffed stats for the elite picked out of a wave
+    pub fn elite(self) -> Self {
+        Self {
+            health: Health::with_armor(self.health.max * 2, self.health.armor),
+            speed: self.speed * 1.1,
+            damage: self.damage * 1.5,
+        }
+    }
+}
+
+/// Stats for the wave's `index`th enemy: its type's base stats scaled by the
+/// wave's difficulty multipliers, then buffed if it is the wave's elite.
+/// `None` if the wave has no such enemy.
+pub fn spawn_enemy_stats(wave: &Wave, index: usize) -> Option<EnemyStats> {
+    let base = EnemyStats::base(*wave.enemy_composition.get(index)?);
+    let max_health = (base.health.max as f32 * wave.health_multiplier).round() as i32;
+
+    let stats = EnemyStats {
+        health: Health::with_armor(max_health.max(1), base.health.armor),
+        speed: base.speed * wave.speed_multiplier,
+        damage: base.damage * wave.damage_multiplier,
+    };
+    if wave.elite_index == Some(index) {
+        return Some(stats.elite());
+    }
+    Some(stats)
+}
+
+/// Events raised while progressing through a zone
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ZoneEvent {
+    ZoneCleared { zone_number: u32 },
+}
+
+/// Tracks wave spawning and enemy kills to decide when a zone is cleared
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZoneProgress {
+    zone_number: u32,
+    total_waves: usize,
+    waves_spawned: usize,
+    enemies_remaining: u32,
+    bosses_remaining: u32,
+    cleared_emitted: bool,
+}
+
+impl ZoneProgress {
+    pub fn new(zone: &Zone) -> Self {
+        Self {
+            zone_number: zone.zone_number,
+            total_waves: zone.waves.len(),
+            waves_spawned: 0,
+            enemies_remaining: 0,
+            bosses_remaining: 0,
+            cleared_emitted: false,
+        }
+    }
+
+    pub fn on_wave_spawned(&mut self, wave: &Wave) -> Option<ZoneEvent> {
+        self.waves_spawned = (self.waves_spawned + 1).min(self.total_waves);
+        self.enemies_remaining += wave.enemy_composition.len() as u32;
+        if wave.is_boss {
+            self.bosses_remaining += 1;
+        }
+        self.check_cleared()
+    }
+
+    /// Records a kill. Boss kills also count toward the enemy total; a zone
+    /// with a boss wave is never cleared until that boss is reported dead.
+    pub fn on_enemy_killed(&mut self, is_boss: bool) -> Option<ZoneEvent> {
+        self.enemies_remaining = self.enemies_remaining.saturating_sub(1);
+        if is_boss {
+            self.bosses_remaining = self.bosses_remaining.saturating_sub(1);
+        }
+        self.check_cleared()
+    }
+
+    pub fn is_cleared(&self) -> bool {
+        self.waves_spawned >= self.total_waves
+            && self.enemies_remaining == 0
+            && self.bosses_remaining == 0
+    }
+
+    pub fn waves_spawned(&self) -> usize {
+        self.waves_spawned
+    }
+
+    pub fn enemies_remaining(&self) -> u32 {
+        self.enemies_remaining
+    }
+
+    fn check_cleared(&mut self) -> Option<ZoneEvent> {
+        if self.cleared_emitted || !self.is_cleared() {
+            return None;
+        }
+
+        self.cleared_emitted = true;
+        Some(ZoneEvent::ZoneCleared {
+            zone_number: self.zone_number,
+        })
+    }
+}
+
+/// Share of a wave that must be destroyed before the next is released
+pub const DEFAULT_WAVE_CLEAR_THRESHOLD: f32 = 0.75;
+/// Longest the next wave is held back, in seconds
+pub const DEFAULT_WAVE_TIMEOUT: f32 = 20.0;
+
+/// Paces a zone's waves: each wave after the first is held until the one
+/// before it is mostly destroyed, or until a timeout, so quick players keep
+/// the action going and slower ones get a breather
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZoneRunner {
+    progress: ZoneProgress,
+    wave_sizes: Vec<u32>,
+    next_wave: usize,
+    held_for: f32,
+    clear_threshold: f32,
+    timeout: f32,
+    events: Vec<ScheduledEvent>,
+    triggered_events: Vec<SpecialEvent>,
+}
+
+impl ZoneRunner {
+    pub fn new(zone: &Zone) -> Self {
+        Self::with_timing(zone, DEFAULT_WAVE_CLEAR_THRESHOLD, DEFAULT_WAVE_TIMEOUT)
+    }
+
+    pub fn with_timing(zone: &Zone, clear_threshold: f32, timeout: f32) -> Self {
+        Self {
+            progress: ZoneProgress::new(zone),
+            wave_sizes: zone
+                .waves
+                .iter()
+                .map(|wave| wave.enemy_composition.len() as u32)
+                .collect(),
+            next_wave: 0,
+            held_for: 0.0,
+            clear_threshold: clear_threshold.clamp(0.0, 1.0),
+            timeout,
+            events: zone.events.clone(),
+            triggered_events: Vec::new(),
+        }
+    }
+
+    /// Advances the hold timer and returns the index of the wave to spawn
+    /// now, if one is due. Report each released wave through
+    /// `on_wave_spawned`; until then it counts as not yet cleared.
+    pub fn update(&mut self, delta: f32) -> Option<usize> {
+        if self.next_wave >= self.wave_sizes.len() {
+            return None;
+        }
+
+        self.held_for += delta.max(0.0);
+        if self.next_wave > 0 && !self.previous_wave_cleared() && self.held_for < self.timeout {
+            return None;
+        }
+
+        let index = self.next_wave;
+        self.next_wave += 1;
+        self.held_for = 0.0;
+
+        let (due, later): (Vec<_>, Vec<_>) = self
+            .events
+            .drain(..)
+            .partition(|scheduled| scheduled.before_wave <= index);
+        self.events = later;
+        self.triggered_events
+            .extend(due.into_iter().map(|scheduled| scheduled.event));
+        Some(index)
+    }
+
+    /// Special events triggered since the last call, each due just before
+    /// the wave it was scheduled ahead of
+    pub fn take_events(&mut self) -> Vec<SpecialEvent> {
+        std::mem::take(&mut self.triggered_events)
+    }
+
+    pub fn on_wave_spawned(&mut self, wave: &Wave) -> Option<ZoneEvent> {
+        self.progress.on_wave_spawned(wave)
+    }
+
+    pub fn on_enemy_killed(&mut self, is_boss: bool) -> Option<ZoneEvent> {
+        self.progress.on_enemy_killed(is_boss)
+    }
+
+    pub fn progress(&self) -> &ZoneProgress {
+        &self.progress
+    }
+
+    /// Whether few enough enemies are left that the last released wave,
+    /// and any stragglers before it, count as mostly cleared
+    fn previous_wave_cleared(&self) -> bool {
+        if self.progress.waves_spawned() < self.next_wave {
+            return false;
+        }
+        let size = self.wave_sizes[self.next_wave - 1] as f32;
+        let allowed = (size * (1.0 - self.clear_threshold)).floor() as u32;
+        self.progress.enemies_remaining() <= allowed
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -342,32 +1084,75 @@ pub struct WaveTemplate {
     pub name: String,
     pub enemy_types: Vec<EnemyType>,
     pub formation: Formation,
-    pub base_count: u32,
     pub min_difficulty: f32,
     pub max_difficulty: f32,
     pub zone_types: Vec<ZoneType>,
+    /// Most of each enemy type a single wave may hold; types not listed are
+    /// uncapped. Budget a capped type can't use goes to the other types.
+    pub max_per_type: HashMap<EnemyType, u32>,
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Terrain {
     pub background_layers: Vec<TerrainLayer>,
     pub obstacles: Vec<Obstacle>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl Terrain {
+    /// Scroll offset of every background layer, in layer order
+    pub fn layer_offsets(&self, camera_pos: Vec2, time: f32) -> Vec<Vec2> {
+        self.background_layers
+            .iter()
+            .map(|layer| layer.scroll_offset(camera_pos, time))
+            .collect()
+    }
+}
+
+/// Default tile size of background layer textures in world units
+pub const DEFAULT_LAYER_TEXTURE_WIDTH: f32 = 1024.0;
+
+fn default_layer_texture_width() -> f32 {
+    DEFAULT_LAYER_TEXTURE_WIDTH
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TerrainLayer {
     pub texture_name: String,
     pub scroll_speed: f32,
     pub parallax_factor: f32,
+    #[serde(default = "default_layer_texture_width")]
+    pub texture_width: f32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl TerrainLayer {
+    /// Texture offset for this layer: camera displacement scaled by the
+    /// parallax factor plus constant vertical scrolling, wrapped to the
+    /// (square) texture tile so it never grows unbounded.
+    pub fn scroll_offset(&self, camera_pos: Vec2, time: f32) -> Vec2 {
+        let raw = Vec2::new(
+            camera_pos.x * self.parallax_factor,
+            camera_pos.y * self.parallax_factor + self.scroll_speed * time,
+        );
+
+        if self.texture_width <= 0.0 {
+            return raw;
+        }
+
+        Vec2::new(
+            raw.x.rem_euclid(self.texture_width),
+            raw.y.rem_euclid(self.texture_width),
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Obstacle {
     pub position: Vec2,
     pub size: Vec2,
     pub damage_on_collision: f32,
 }
 
+#[derive(Debug, Clone)]
 pub struct TerrainGenerator {
     // Terrain generation state
 }
@@ -379,16 +1164,18 @@ impl TerrainGenerator {
 
     pub fn generate<R: Rng>(&self, zone_type: &ZoneType, _rng: &mut R) -> Terrain {
         let layers = match zone_type {
-            ZoneType::Sky => vec![
+            ZoneType::Sky | ZoneType::Unknown => vec![
                 TerrainLayer {
                     texture_name: "sky_bg".to_string(),
                     scroll_speed: 10.0,
                     parallax_factor: 0.2,
+                    texture_width: DEFAULT_LAYER_TEXTURE_WIDTH,
                 },
                 TerrainLayer {
                     texture_name: "clouds_far".to_string(),
                     scroll_speed: 20.0,
                     parallax_factor: 0.5,
+                    texture_width: DEFAULT_LAYER_TEXTURE_WIDTH,
                 },
             ],
             ZoneType::Clouds => vec![
@@ -396,11 +1183,13 @@ impl TerrainGenerator {
                     texture_name: "cloud_layer_1".to_string(),
                     scroll_speed: 15.0,
                     parallax_factor: 0.3,
+                    texture_width: DEFAULT_LAYER_TEXTURE_WIDTH,
                 },
                 TerrainLayer {
                     texture_name: "cloud_layer_2".to_string(),
                     scroll_speed: 30.0,
                     parallax_factor: 0.6,
+                    texture_width: DEFAULT_LAYER_TEXTURE_WIDTH,
                 },
             ],
             ZoneType::Ocean => vec![
@@ -408,11 +1197,13 @@ impl TerrainGenerator {
                     texture_name: "ocean_bg".to_string(),
                     scroll_speed: 12.0,
                     parallax_factor: 0.25,
+                    texture_width: DEFAULT_LAYER_TEXTURE_WIDTH,
                 },
                 TerrainLayer {
                     texture_name: "waves".to_string(),
                     scroll_speed: 35.0,
                     parallax_factor: 0.7,
+                    texture_width: DEFAULT_LAYER_TEXTURE_WIDTH,
                 },
             ],
             ZoneType::Mountains => vec![
@@ -420,11 +1211,13 @@ impl TerrainGenerator {
                     texture_name: "mountain_far".to_string(),
                     scroll_speed: 8.0,
                     parallax_factor: 0.15,
+                    texture_width: DEFAULT_LAYER_TEXTURE_WIDTH,
                 },
                 TerrainLayer {
                     texture_name: "mountain_near".to_string(),
                     scroll_speed: 25.0,
                     parallax_factor: 0.5,
+                    texture_width: DEFAULT_LAYER_TEXTURE_WIDTH,
                 },
             ],
             ZoneType::Desert => vec![
@@ -432,11 +1225,13 @@ impl TerrainGenerator {
                     texture_name: "desert_bg".to_string(),
                     scroll_speed: 10.0,
                     parallax_factor: 0.2,
+                    texture_width: DEFAULT_LAYER_TEXTURE_WIDTH,
                 },
                 TerrainLayer {
                     texture_name: "sand_dunes".to_string(),
                     scroll_speed: 28.0,
                     parallax_factor: 0.6,
+                    texture_width: DEFAULT_LAYER_TEXTURE_WIDTH,
                 },
             ],
         };
@@ -454,7 +1249,7 @@ impl Default for TerrainGenerator {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Hazard {
     pub hazard_type: HazardType,
     pub position: Vec2,
@@ -470,7 +1265,7 @@ pub enum HazardType {
     Sandstorm,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Collectible {
     pub collectible_type: CollectibleType,
     pub position: Vec2,
@@ -482,23 +1277,71 @@ pub enum CollectibleType {
     HealthPack,
     Ammo,
     PowerUp,
+    ExtraLife,
+}
+
+/// What a supply drop can contain
+const SUPPLY_DROP_CONTENTS: [CollectibleType; 3] = [
+    CollectibleType::HealthPack,
+    CollectibleType::Ammo,
+    CollectibleType::PowerUp,
+];
+/// Enemies that can spring an elite ambush
+const AMBUSH_ENEMIES: [EnemyType; 3] = [EnemyType::Fighter, EnemyType::Bomber, EnemyType::Ace];
+
+/// One-off set piece that breaks up a zone's waves
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SpecialEvent {
+    /// A crate of pickups dropped at `position`
+    SupplyDrop { position: Vec2, collectibles: Vec<CollectibleType> },
+    /// A group of elites of one type arriving at once
+    EliteAmbush { enemy_type: EnemyType, count: u32 },
+    /// Drifting rocks to weave between
+    AsteroidField { obstacles: Vec<Obstacle> },
 }
 
+/// A special event and the wave it plays out ahead of
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduledEvent {
+    pub before_wave: usize,
+    pub event: SpecialEvent,
+}
+
+#[derive(Debug, Clone)]
 pub struct DifficultyManager {
-    base_difficulty: f32,
+    balance: DifficultyBalance,
+    pacing: f32,
 }
 
 impl DifficultyManager {
     pub fn new() -> Self {
+        Self::with_balance(&BalanceConfig::default())
+    }
+
+    pub fn with_balance(balance: &BalanceConfig) -> Self {
         Self {
-            base_difficulty: 0.1,
+            balance: balance.difficulty,
+            pacing: 1.0,
         }
     }
 
+    pub fn set_balance(&mut self, balance: &BalanceConfig) {
+        self.balance = balance.difficulty;
+    }
+
+    /// Pacing multiplier from the `Director`; 1.0 leaves the curve untouched
+    pub fn set_pacing(&mut self, pacing: f32) {
+        self.pacing = pacing.clamp(MIN_PACING, MAX_PACING);
+    }
+
+    pub fn pacing(&self) -> f32 {
+        self.pacing
+    }
+
     pub fn calculate_difficulty(&self, zone_number: u32) -> f32 {
         // Exponential difficulty curve
-        let zone_factor = zone_number as f32 * 0.15;
-        (self.base_difficulty + zone_factor).min(1.0)
+        let zone_factor = zone_number as f32 * self.balance.per_zone;
+        ((self.balance.base + zone_factor) * self.pacing).min(1.0)
     }
 }
 
@@ -511,6 +1354,8 @@ impl Default for DifficultyManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::game::scoring::score_value;
+    use cgmath::InnerSpace;
 
     #[test]
     fn test_procedural_generator_creation() {
@@ -526,6 +1371,188 @@ mod tests {
         assert_eq!(zone.zone_type, ZoneType::Sky);
         assert_eq!(zone.zone_number, 1);
         assert!(!zone.waves.is_empty());
+        assert!(!zone.name.is_empty());
+    }
+
+    #[test]
+    fn test_generated_content_stays_in_play_field() {
+        let small = PlayField::new(
+            AABB::new(Vec2::new(0.0, 0.0), Vec2::new(320.0, 480.0)),
+            20.0,
+        );
+        let large = PlayField::new(
+            AABB::new(Vec2::new(-1600.0, -900.0), Vec2::new(1600.0, 900.0)),
+            150.0,
+        );
+
+        for play_field in [small, large] {
+            let mut generator = ProceduralGenerator::new(99);
+            generator.set_play_field(play_field);
+            let inner = play_field.inner_bounds();
+
+            for zone_number in 1..6 {
+                let zone = generator.generate_zone(ZoneType::Ocean, zone_number);
+                assert!(!zone.hazards.is_empty());
+                for hazard in &zone.hazards {
+                    assert!(play_field.bounds.contains(hazard.position));
+                }
+                for collectible in &zone.collectibles {
+                    assert!(inner.contains(collectible.position));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_cloned_generator_forks_cleanly() {
+        let mut generator = ProceduralGenerator::new(777);
+        generator.generate_zone(ZoneType::Sky, 1);
+
+        let mut fork = generator.clone();
+        let original_zone = generator.generate_zone(ZoneType::Ocean, 2);
+        let forked_zone = fork.generate_zone(ZoneType::Ocean, 2);
+        assert_eq!(original_zone, forked_zone);
+
+        // Both continue identically afterwards
+        assert_eq!(
+            generator.generate_zone(ZoneType::Desert, 3),
+            fork.generate_zone(ZoneType::Desert, 3)
+        );
+    }
+
+    #[test]
+    fn test_rng_state_restore_previews_next_zone() {
+        let mut generator = ProceduralGenerator::new(31337);
+        let state = generator.rng_state();
+
+        let preview = generator.generate_zone(ZoneType::Clouds, 4);
+        generator.restore_rng(state);
+        let actual = generator.generate_zone(ZoneType::Clouds, 4);
+
+        assert_eq!(preview, actual);
+    }
+
+    #[test]
+    fn test_zone_generated_hook_reports_telemetry() {
+        let records = Rc::new(std::cell::RefCell::new(Vec::new()));
+        let mut generator = ProceduralGenerator::new(555);
+        let sink = Rc::clone(&records);
+        generator.set_on_zone_generated(move |telemetry| sink.borrow_mut().push(*telemetry));
+
+        let zone = generator.generate_zone(ZoneType::Desert, 3);
+        let telemetry = records.borrow()[0];
+        assert_eq!(telemetry.zone_type, ZoneType::Desert);
+        assert_eq!(telemetry.zone_number, 3);
+        assert_eq!(telemetry.seed, 555);
+        assert_eq!(telemetry.wave_count, zone.waves.len());
+        assert_eq!(telemetry.hazard_count, zone.hazards.len());
+        let threat: f32 = zone.waves.iter().map(Wave::total_threat).sum();
+        assert_eq!(telemetry.total_threat, threat);
+        assert!(telemetry.difficulty > 0.0);
+
+        // Clones report to the same hook; clearing it stops reports
+        generator.clone().generate_zone(ZoneType::Sky, 4);
+        assert_eq!(records.borrow().len(), 2);
+        generator.clear_on_zone_generated();
+        generator.generate_zone(ZoneType::Sky, 5);
+        assert_eq!(records.borrow().len(), 2);
+    }
+
+    fn with_event_chance(seed: u64, chance: f64) -> ProceduralGenerator {
+        let mut balance = BalanceConfig::default();
+        balance.waves.special_event_chance = chance;
+        ProceduralGenerator::with_balance(seed, &balance)
+    }
+
+    #[test]
+    fn test_special_event_chance() {
+        let zones_with_events = |chance: f64| {
+            (0..1000)
+                .filter(|&seed| {
+                    let generator = with_event_chance(seed, chance);
+                    !generator.generate_events(1, 6).is_empty()
+                })
+                .count()
+        };
+
+        let default_chance = BalanceConfig::default().waves.special_event_chance;
+        let rolled = zones_with_events(default_chance);
+        assert!((250..350).contains(&rolled), "{} of 1000", rolled);
+        assert_eq!(zones_with_events(0.0), 0);
+        assert_eq!(zones_with_events(1.0), 1000);
+    }
+
+    #[test]
+    fn test_special_events_carry_valid_data() {
+        let mut seen = [false; 3];
+        for seed in 0..200 {
+            let mut generator = with_event_chance(seed, 1.0);
+            let zone = generator.generate_zone(ZoneType::Sky, 2);
+            assert_eq!(zone.events.len(), 1);
+
+            let scheduled = &zone.events[0];
+            // Always between waves, never before the first
+            assert!((1..zone.waves.len()).contains(&scheduled.before_wave));
+
+            let play_field = generator.play_field();
+            match &scheduled.event {
+                SpecialEvent::SupplyDrop {
+                    position,
+                    collectibles,
+                } => {
+                    seen[0] = true;
+                    assert!(play_field.inner_bounds().contains(*position));
+                    assert!((2..5).contains(&collectibles.len()));
+                    assert!(!collectibles.contains(&CollectibleType::ExtraLife));
+                }
+                SpecialEvent::EliteAmbush { enemy_type, count } => {
+                    seen[1] = true;
+                    assert!(AMBUSH_ENEMIES.contains(enemy_type));
+                    assert!((2..=4).contains(count));
+                }
+                SpecialEvent::AsteroidField { obstacles } => {
+                    seen[2] = true;
+                    assert!((5..10).contains(&obstacles.len()));
+                    for obstacle in obstacles {
+                        assert!(play_field.bounds.contains(obstacle.position));
+                        assert!(obstacle.size.x >= 20.0 && obstacle.size.x < 60.0);
+                    }
+                }
+            }
+        }
+        assert_eq!(seen, [true; 3]);
+    }
+
+    #[test]
+    fn test_layout_ignores_wave_rng_draws() {
+        let mut plain = ProceduralGenerator::new(4242);
+        let mut busy = ProceduralGenerator::new(4242);
+        // Extra waves beforehand move the main RNG, and a higher pacing
+        // changes how many waves the zone itself gets
+        for _ in 0..3 {
+            busy.generate_wave(ZoneType::Sky, 0.8);
+        }
+        busy.set_pacing(2.0);
+
+        let a = plain.generate_zone(ZoneType::Ocean, 2);
+        let b = busy.generate_zone(ZoneType::Ocean, 2);
+        assert_ne!(a.waves.len(), b.waves.len());
+
+        let collectibles = |zone: &Zone| -> Vec<(CollectibleType, Vec2)> {
+            let layout = zone.collectibles.iter();
+            layout.map(|c| (c.collectible_type, c.position)).collect()
+        };
+        assert_eq!(collectibles(&a), collectibles(&b));
+
+        // Harder zones add hazards without moving the existing ones
+        assert!(a.hazards.len() < b.hazards.len());
+        for (easy, hard) in a.hazards.iter().zip(&b.hazards) {
+            assert_eq!(easy.position, hard.position);
+        }
+
+        // Other zones lay out differently
+        let other_zone = plain.generate_zone(ZoneType::Ocean, 3);
+        assert_ne!(collectibles(&other_zone), collectibles(&a));
     }
 
     #[test]
@@ -538,6 +1565,244 @@ mod tests {
         assert!(wave.health_multiplier > 1.0);
     }
 
+    #[test]
+    fn test_wave_threat_stays_within_budget() {
+        for seed in 0..20 {
+            let mut generator = ProceduralGenerator::new(seed);
+            for template in generator.wave_templates.clone() {
+                for &difficulty in &[0.0, 0.5, 0.8, 1.0] {
+                    let wave = generator.instantiate_wave(&template, difficulty);
+                    let budget = wave_threat_budget(difficulty);
+                    let cheapest = template
+                        .enemy_types
+                        .iter()
+                        .map(|&e| threat_cost(e))
+                        .fold(f32::INFINITY, f32::min);
+
+                    let total = wave.total_threat();
+                    assert!(total <= budget, "{} over budget", template.name);
+                    assert!(budget - total < cheapest, "{} under-filled", template.name);
+                    assert_eq!(wave.spawn_positions.len(), wave.enemy_composition.len());
+                }
+            }
+        }
+    }
+
+    fn wave_of(enemies: Vec<EnemyType>, multiplier: f32, has_elite: bool) -> Wave {
+        Wave {
+            spawn_positions: vec![Vec2::new(0.0, -100.0); enemies.len()],
+            enemy_composition: enemies,
+            health_multiplier: multiplier,
+            damage_multiplier: multiplier,
+            speed_multiplier: multiplier,
+            spawn_delay: 0.5,
+            spawn_schedule: Vec::new(),
+            has_elite,
+            elite_index: None,
+            is_boss: false,
+        }
+    }
+
+    #[test]
+    fn test_estimate_wave_difficulty() {
+        let generator = ProceduralGenerator::new(1);
+        let sparse = wave_of(vec![EnemyType::Fighter; 2], 1.0, false);
+        let crowded = wave_of(vec![EnemyType::Fighter; 6], 1.0, false);
+        let strong = wave_of(vec![EnemyType::Bomber, EnemyType::Ace], 1.0, false);
+        let scaled = wave_of(vec![EnemyType::Fighter; 2], 1.5, false);
+        let elite = wave_of(vec![EnemyType::Fighter; 2], 1.0, true);
+
+        let base = generator.estimate_wave_difficulty(&sparse);
+        assert_eq!(base, 2.0);
+        assert!(generator.estimate_wave_difficulty(&crowded) > base);
+        assert!(generator.estimate_wave_difficulty(&strong) > base);
+        assert!(generator.estimate_wave_difficulty(&scaled) > base);
+        assert!(generator.estimate_wave_difficulty(&elite) > base);
+
+        // Estimating is pure and repeatable
+        assert_eq!(generator.estimate_wave_difficulty(&sparse), base);
+    }
+
+    #[test]
+    fn test_wave_difficulty_counts_the_actual_elite() {
+        let generator = ProceduralGenerator::new(1);
+        let mut wave = wave_of(vec![EnemyType::Fighter, EnemyType::Ace], 1.0, true);
+        let unknown_elite = generator.estimate_wave_difficulty(&wave);
+        assert_eq!(unknown_elite, 5.0 + 4.0 * (ELITE_THREAT_MULTIPLIER - 1.0));
+
+        wave.elite_index = Some(0);
+        let fighter_elite = generator.estimate_wave_difficulty(&wave);
+        assert_eq!(fighter_elite, 5.0 + ELITE_THREAT_MULTIPLIER - 1.0);
+
+        wave.elite_index = Some(1);
+        assert_eq!(generator.estimate_wave_difficulty(&wave), unknown_elite);
+    }
+
+    #[test]
+    fn test_spawn_schedule_patterns() {
+        assert_eq!(SpawnPattern::AllAtOnce.schedule(3), vec![0.0, 0.0, 0.0]);
+        assert_eq!(
+            SpawnPattern::Staggered { interval: 0.5 }.schedule(3),
+            vec![0.0, 0.5, 1.0]
+        );
+        let pairs = SpawnPattern::Bursts {
+            size: 2,
+            interval: 1.0,
+        };
+        assert_eq!(pairs.schedule(5), vec![0.0, 0.0, 1.0, 1.0, 2.0]);
+    }
+
+    #[test]
+    fn test_enemies_due_over_time() {
+        let mut generator = ProceduralGenerator::new(12345);
+        let mut wave = generator.create_default_wave(0.5);
+        let pairs = SpawnPattern::Bursts {
+            size: 2,
+            interval: 1.0,
+        };
+        wave.spawn_schedule = pairs.schedule(3);
+
+        assert_eq!(wave.enemies_due(0.0), 0..2);
+        assert_eq!(wave.enemies_due(0.99), 0..2);
+        assert_eq!(wave.enemies_due(1.0), 0..3);
+        assert_eq!(wave.enemies_due(100.0), 0..3);
+
+        // Waves without a schedule spawn everything immediately
+        wave.spawn_schedule.clear();
+        assert_eq!(wave.enemies_due(0.0), 0..3);
+    }
+
+    #[test]
+    fn test_generated_schedule_covers_every_enemy() {
+        let mut generator = ProceduralGenerator::new(99);
+        for _ in 0..10 {
+            let wave = generator.generate_wave(ZoneType::Sky, 0.6);
+            assert_eq!(wave.spawn_schedule.len(), wave.enemy_composition.len());
+            assert!(wave.spawn_schedule.windows(2).all(|w| w[0] <= w[1]));
+
+            let first = wave.enemies_due(0.0);
+            assert!(!first.is_empty());
+            let last = *wave.spawn_schedule.last().unwrap();
+            assert_eq!(wave.enemies_due(last), 0..wave.enemy_composition.len());
+        }
+    }
+
+    #[test]
+    fn test_score_value_follows_threat() {
+        let mut enemy_types = vec![
+            EnemyType::Fighter,
+            EnemyType::Bomber,
+            EnemyType::Ace,
+            EnemyType::Kamikaze,
+            EnemyType::HeavyBomber,
+        ];
+        enemy_types.sort_by(|a, b| threat_cost(*a).total_cmp(&threat_cost(*b)));
+
+        for pair in enemy_types.windows(2) {
+            assert!(score_value(pair[0]) < score_value(pair[1]));
+        }
+        assert!(score_value(EnemyType::Ace) >= 4 * score_value(EnemyType::Fighter));
+    }
+
+    #[test]
+    fn test_elite_selection_is_seed_stable() {
+        let template = ProceduralGenerator::new(1).wave_templates[0].clone();
+        let mut elites = 0;
+        for seed in 0..40 {
+            let wave = ProceduralGenerator::new(seed).instantiate_wave(&template, 1.0);
+            let replay = ProceduralGenerator::new(seed).instantiate_wave(&template, 1.0);
+            assert_eq!(wave.has_elite, replay.has_elite);
+            assert_eq!(wave.elite_index, replay.elite_index);
+
+            assert_eq!(wave.elite_index.is_some(), wave.has_elite);
+            if let Some(index) = wave.elite_index {
+                assert!(index < wave.enemy_composition.len());
+                elites += 1;
+            }
+        }
+        assert!(elites > 0);
+    }
+
+    #[test]
+    fn test_wave_respects_type_caps() {
+        let mut template = ProceduralGenerator::new(1).wave_templates[4].clone();
+        template.enemy_types.push(EnemyType::Fighter);
+        template.max_per_type.insert(EnemyType::Kamikaze, 3);
+
+        for seed in 0..20 {
+            let mut generator = ProceduralGenerator::new(seed);
+            for &difficulty in &[0.0, 0.5, 1.0] {
+                let wave = generator.instantiate_wave(&template, difficulty);
+                let kamikazes = wave
+                    .enemy_composition
+                    .iter()
+                    .filter(|&&e| e == EnemyType::Kamikaze)
+                    .count();
+                assert!(kamikazes <= 3, "{} kamikazes", kamikazes);
+
+                // The rest of the budget goes to fighters instead
+                let budget = wave_threat_budget(difficulty);
+                assert!(budget - wave.total_threat() < threat_cost(EnemyType::Fighter));
+            }
+        }
+    }
+
+    #[test]
+    fn test_costly_enemies_spawn_in_smaller_numbers() {
+        let mut generator = ProceduralGenerator::new(12345);
+        let aces = generator.wave_templates[3].clone();
+        let fighters = generator.wave_templates[0].clone();
+
+        let ace_wave = generator.instantiate_wave(&aces, 1.0);
+        let fighter_wave = generator.instantiate_wave(&fighters, 1.0);
+        assert!(ace_wave.enemy_composition.len() < fighter_wave.enemy_composition.len());
+    }
+
+    #[test]
+    fn test_spawn_enemy_stats_scale_with_difficulty() {
+        let mut generator = ProceduralGenerator::new(12345);
+        let mut easy = generator.create_default_wave(0.1);
+        let mut hard = generator.create_default_wave(1.0);
+        easy.enemy_composition = vec![EnemyType::Bomber; 3];
+        hard.enemy_composition = vec![EnemyType::Bomber; 3];
+
+        let easy_stats = spawn_enemy_stats(&easy, 0).unwrap();
+        let hard_stats = spawn_enemy_stats(&hard, 0).unwrap();
+        let base = EnemyStats::base(EnemyType::Bomber);
+
+        assert!(hard_stats.health.max > easy_stats.health.max);
+        assert_eq!(
+            hard_stats.health.max,
+            (base.health.max as f32 * hard.health_multiplier).round() as i32
+        );
+        assert_eq!(hard_stats.health.current, hard_stats.health.max);
+        assert!(hard_stats.damage > easy_stats.damage);
+        assert!(hard_stats.speed > easy_stats.speed);
+        assert!(spawn_enemy_stats(&hard, 3).is_none());
+    }
+
+    #[test]
+    fn test_elite_spawns_tougher_than_wave_mates() {
+        let mut wave = ProceduralGenerator::new(1).create_default_wave(0.5);
+        wave.has_elite = true;
+        wave.elite_index = Some(1);
+
+        let regular = spawn_enemy_stats(&wave, 0).unwrap();
+        let elite = spawn_enemy_stats(&wave, 1).unwrap();
+        assert!(elite.health.max > regular.health.max);
+        assert!(elite.damage > regular.damage);
+        assert_eq!(spawn_enemy_stats(&wave, 2).unwrap(), regular);
+    }
+
+    #[test]
+    fn test_elite_stats() {
+        let stats = EnemyStats::base(EnemyType::Fighter);
+        let elite = stats.clone().elite();
+
+        assert_eq!(elite.health.max, stats.health.max * 2);
+        assert!(elite.damage > stats.damage);
+    }
+
     #[test]
     fn test_difficulty_scaling() {
         let difficulty_manager = DifficultyManager::new();
@@ -551,6 +1816,70 @@ mod tests {
         assert!(diff10 <= 1.0);
     }
 
+    #[test]
+    fn test_doubled_balance_coefficients() {
+        let doubled = BalanceConfig::from_json(
+            r#"{
+                "difficulty": {"base": 0.2, "per_zone": 0.3},
+                "waves": {
+                    "health_per_difficulty": 0.4,
+                    "damage_per_difficulty": 0.3,
+                    "speed_per_difficulty": 0.2
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let default_manager = DifficultyManager::new();
+        let doubled_manager = DifficultyManager::with_balance(&doubled);
+        let difficulty = default_manager.calculate_difficulty(1);
+        assert!((doubled_manager.calculate_difficulty(1) - 2.0 * difficulty).abs() < 0.001);
+
+        let mut default_generator = ProceduralGenerator::new(5);
+        let mut doubled_generator = ProceduralGenerator::with_balance(5, &doubled);
+        let template = default_generator.wave_templates[0].clone();
+        let normal = default_generator.instantiate_wave(&template, 0.5);
+        let hard = doubled_generator.instantiate_wave(&template, 0.5);
+
+        let bonus = |multiplier: f32| multiplier - 1.0;
+        for (normal, hard) in [
+            (normal.health_multiplier, hard.health_multiplier),
+            (normal.damage_multiplier, hard.damage_multiplier),
+            (normal.speed_multiplier, hard.speed_multiplier),
+        ] {
+            assert!((bonus(hard) - 2.0 * bonus(normal)).abs() < 0.001);
+        }
+    }
+
+    #[test]
+    fn test_default_balance_matches_built_in() {
+        let mut built_in = ProceduralGenerator::new(77);
+        let mut configured = ProceduralGenerator::with_balance(77, &BalanceConfig::default());
+        for zone in 1..4 {
+            assert_eq!(
+                built_in.generate_zone(ZoneType::Sky, zone),
+                configured.generate_zone(ZoneType::Sky, zone)
+            );
+        }
+        assert_eq!(
+            DifficultyManager::new().calculate_difficulty(2),
+            0.1 + 2.0 * 0.15
+        );
+    }
+
+    #[test]
+    fn test_pacing_scales_difficulty() {
+        let mut difficulty_manager = DifficultyManager::new();
+        let neutral = difficulty_manager.calculate_difficulty(2);
+
+        difficulty_manager.set_pacing(0.5);
+        assert!((difficulty_manager.calculate_difficulty(2) - neutral * 0.5).abs() < 1e-6);
+
+        difficulty_manager.set_pacing(2.0);
+        assert!(difficulty_manager.calculate_difficulty(2) > neutral);
+        assert_eq!(difficulty_manager.calculate_difficulty(10), 1.0);
+    }
+
     #[test]
     fn test_formation_positions() {
         let mut generator = ProceduralGenerator::new(12345);
@@ -563,4 +1892,265 @@ mod tests {
         let positions = generator.generate_formation_positions(&circle_formation, 8);
         assert_eq!(positions.len(), 8);
     }
+
+    #[test]
+    fn test_formation_positions_keep_min_separation() {
+        let formations = [
+            Formation::V { spacing: 0.0 },
+            Formation::V { spacing: 20.0 },
+            Formation::Line {
+                spacing: 5.0,
+                angle: 30.0,
+            },
+            Formation::Circle { radius: 0.0 },
+            Formation::Circle { radius: 60.0 },
+            Formation::Arc {
+                radius: 80.0,
+                start_angle: 0.0,
+                sweep: 45.0,
+            },
+            Formation::Diamond,
+            Formation::Custom(vec![Vec2::new(10.0, 10.0); 6]),
+        ];
+
+        let mut generator = ProceduralGenerator::new(12345);
+        for formation in &formations {
+            for count in 1..=12 {
+                let positions = generator.generate_formation_positions(formation, count);
+                for (i, a) in positions.iter().enumerate() {
+                    for b in &positions[i + 1..] {
+                        let distance = (a - b).magnitude();
+                        assert!(
+                            distance >= MIN_SPAWN_SEPARATION,
+                            "{:?} x{}: {} apart",
+                            formation,
+                            count,
+                            distance
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_separation_keeps_spread_formations_unchanged() {
+        let mut positions = vec![Vec2::new(0.0, 0.0), Vec2::new(50.0, 0.0)];
+        separate_positions(&mut positions, MIN_SPAWN_SEPARATION);
+        assert_eq!(positions, vec![Vec2::new(0.0, 0.0), Vec2::new(50.0, 0.0)]);
+
+        // Overlapping pairs move apart symmetrically about their midpoint
+        let mut positions = vec![Vec2::new(0.0, 0.0), Vec2::new(10.0, 0.0)];
+        separate_positions(&mut positions, MIN_SPAWN_SEPARATION);
+        assert!((positions[0].x + positions[1].x - 10.0).abs() < 0.001);
+        assert!(positions[1].x - positions[0].x >= MIN_SPAWN_SEPARATION);
+    }
+
+    #[test]
+    fn test_arc_formation_positions() {
+        let mut generator = ProceduralGenerator::new(12345);
+        let arc = Formation::Arc {
+            radius: 200.0,
+            start_angle: 0.0,
+            sweep: 90.0,
+        };
+
+        let positions = generator.generate_formation_positions(&arc, 4);
+        assert_eq!(positions.len(), 4);
+
+        let center = Vec2::new(0.0, -100.0);
+        let angles: Vec<f32> = positions
+            .iter()
+            .map(|p| {
+                let offset = *p - center;
+                assert!((offset.magnitude() - 200.0).abs() < 0.01);
+                offset.y.atan2(offset.x).to_degrees()
+            })
+            .collect();
+
+        assert!((angles[0] - 0.0).abs() < 0.01);
+        assert!((angles[1] - 30.0).abs() < 0.01);
+        assert!((angles[2] - 60.0).abs() < 0.01);
+        assert!((angles[3] - 90.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_zone_progress_clears_once() {
+        let mut generator = ProceduralGenerator::new(12345);
+        let zone = generator.generate_zone(ZoneType::Sky, 1);
+        let mut progress = ZoneProgress::new(&zone);
+        let mut events = Vec::new();
+
+        for wave in &zone.waves {
+            assert!(!progress.is_cleared());
+            events.extend(progress.on_wave_spawned(wave));
+            for _ in 0..wave.enemy_composition.len() {
+                events.extend(progress.on_enemy_killed(false));
+            }
+        }
+
+        assert!(progress.is_cleared());
+        assert_eq!(
+            events,
+            vec![ZoneEvent::ZoneCleared {
+                zone_number: zone.zone_number
+            }]
+        );
+
+        // Further kills never re-emit
+        assert!(progress.on_enemy_killed(false).is_none());
+    }
+
+    #[test]
+    fn test_zone_progress_waits_for_all_waves() {
+        let mut generator = ProceduralGenerator::new(12345);
+        let zone = generator.generate_zone(ZoneType::Sky, 1);
+        let mut progress = ZoneProgress::new(&zone);
+
+        // Killing everything in the first wave is not enough
+        progress.on_wave_spawned(&zone.waves[0]);
+        for _ in 0..zone.waves[0].enemy_composition.len() {
+            assert!(progress.on_enemy_killed(false).is_none());
+        }
+        assert!(!progress.is_cleared());
+    }
+
+    #[test]
+    fn test_zone_progress_boss_gates_clear() {
+        let mut zone = Zone::new(ZoneType::Sky, 3);
+        let mut generator = ProceduralGenerator::new(1);
+        let mut boss_wave = generator.generate_wave(ZoneType::Sky, 0.2);
+        boss_wave.is_boss = true;
+        zone.waves.push(boss_wave.clone());
+
+        let mut progress = ZoneProgress::new(&zone);
+        progress.on_wave_spawned(&boss_wave);
+
+        let count = boss_wave.enemy_composition.len();
+        for _ in 0..count - 1 {
+            assert!(progress.on_enemy_killed(false).is_none());
+        }
+        assert!(!progress.is_cleared());
+
+        assert_eq!(
+            progress.on_enemy_killed(true),
+            Some(ZoneEvent::ZoneCleared { zone_number: 3 })
+        );
+    }
+
+    fn two_wave_zone() -> Zone {
+        let mut zone = Zone::new(ZoneType::Sky, 1);
+        let fighters = wave_of(vec![EnemyType::Fighter; 4], 1.0, false);
+        let bombers = wave_of(vec![EnemyType::Bomber; 2], 1.0, false);
+        zone.waves = vec![fighters, bombers];
+        zone
+    }
+
+    #[test]
+    fn test_zone_runner_holds_wave_until_mostly_cleared() {
+        let zone = two_wave_zone();
+        let mut runner = ZoneRunner::new(&zone);
+
+        assert_eq!(runner.update(0.1), Some(0));
+        runner.on_wave_spawned(&zone.waves[0]);
+
+        // 75% of four fighters must go before the bombers arrive
+        for _ in 0..2 {
+            runner.on_enemy_killed(false);
+            assert_eq!(runner.update(1.0), None);
+        }
+        runner.on_enemy_killed(false);
+        assert_eq!(runner.update(0.1), Some(1));
+        runner.on_wave_spawned(&zone.waves[1]);
+
+        // Nothing left to release
+        assert_eq!(runner.update(100.0), None);
+        assert_eq!(runner.progress().enemies_remaining(), 3);
+    }
+
+    #[test]
+    fn test_zone_runner_triggers_events_before_their_wave() {
+        let mut zone = two_wave_zone();
+        let ambush = SpecialEvent::EliteAmbush {
+            enemy_type: EnemyType::Ace,
+            count: 2,
+        };
+        zone.events.push(ScheduledEvent {
+            before_wave: 1,
+            event: ambush.clone(),
+        });
+        let mut runner = ZoneRunner::with_timing(&zone, 1.0, 5.0);
+
+        assert_eq!(runner.update(0.0), Some(0));
+        assert!(runner.take_events().is_empty());
+
+        assert_eq!(runner.update(5.0), Some(1));
+        assert_eq!(runner.take_events(), vec![ambush]);
+        assert!(runner.take_events().is_empty());
+    }
+
+    #[test]
+    fn test_zone_runner_releases_on_timeout() {
+        let zone = two_wave_zone();
+        let mut runner = ZoneRunner::with_timing(&zone, 1.0, 5.0);
+
+        assert_eq!(runner.update(0.0), Some(0));
+        runner.on_wave_spawned(&zone.waves[0]);
+
+        assert_eq!(runner.update(4.0), None);
+        assert_eq!(runner.update(0.5), None);
+        assert_eq!(runner.update(0.5), Some(1));
+    }
+
+    fn test_layer(scroll_speed: f32, parallax_factor: f32) -> TerrainLayer {
+        TerrainLayer {
+            texture_name: "test".to_string(),
+            scroll_speed,
+            parallax_factor,
+            texture_width: 1000.0,
+        }
+    }
+
+    #[test]
+    fn test_parallax_scroll_offset() {
+        let layer = test_layer(0.0, 0.5);
+
+        let offset = layer.scroll_offset(Vec2::new(100.0, 40.0), 0.0);
+        assert!((offset.x - 50.0).abs() < 0.001);
+        assert!((offset.y - 20.0).abs() < 0.001);
+
+        let layer = test_layer(20.0, 0.0);
+        let offset = layer.scroll_offset(Vec2::new(100.0, 40.0), 2.0);
+        assert!((offset.x - 0.0).abs() < 0.001);
+        assert!((offset.y - 40.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_parallax_scroll_offset_wraps() {
+        let layer = test_layer(30.0, 0.5);
+
+        for i in 0..100 {
+            let time = i as f32 * 10.0;
+            let camera = Vec2::new(i as f32 * -250.0, i as f32 * 500.0);
+            let offset = layer.scroll_offset(camera, time);
+            assert!(offset.x >= 0.0 && offset.x < layer.texture_width);
+            assert!(offset.y >= 0.0 && offset.y < layer.texture_width);
+        }
+
+        // One full tile of camera travel lands back on the same offset
+        let a = layer.scroll_offset(Vec2::new(10.0, 0.0), 0.0);
+        let b = layer.scroll_offset(Vec2::new(2010.0, 0.0), 0.0);
+        assert!((a.x - b.x).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_terrain_layer_offsets() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let terrain = TerrainGenerator::new().generate(&ZoneType::Sky, &mut rng);
+        let offsets = terrain.layer_offsets(Vec2::new(100.0, 0.0), 0.0);
+
+        assert_eq!(offsets.len(), terrain.background_layers.len());
+        // Farther layers (lower parallax factor) move less
+        assert!(offsets[0].x < offsets[1].x);
+    }
 }
@@ -0,0 +1,129 @@
+//! Warnings shown before enemies or hazards appear, so spawns are never
+//! sprung on the player unannounced
+
+use crate::game::events::{GameEvent, Telegraph};
+
+/// Seconds of warning a spawn gets when no duration is given
+pub const DEFAULT_TELEGRAPH_DURATION: f32 = 1.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ActiveTelegraph {
+    pub telegraph: Telegraph,
+    pub remaining: f32,
+}
+
+impl ActiveTelegraph {
+    /// How far through the warning this is, from 0 to 1, for the renderer
+    /// to speed up its flashing
+    pub fn progress(&self) -> f32 {
+        if self.telegraph.duration <= 0.0 {
+            return 1.0;
+        }
+        (1.0 - self.remaining / self.telegraph.duration).clamp(0.0, 1.0)
+    }
+}
+
+/// Counts down active telegraphs and releases each as `GameEvent::SpawnNow`
+/// once its warning has run
+#[derive(Debug, Clone, Default)]
+pub struct TelegraphSystem {
+    active: Vec<ActiveTelegraph>,
+}
+
+impl TelegraphSystem {
+    pub fn new() -> Self {
+        Self { active: Vec::new() }
+    }
+
+    pub fn telegraph(&mut self, telegraph: Telegraph) {
+        self.active.push(ActiveTelegraph {
+            telegraph,
+            remaining: telegraph.duration.max(0.0),
+        });
+    }
+
+    /// Warnings currently on screen
+    pub fn active(&self) -> &[ActiveTelegraph] {
+        &self.active
+    }
+
+    /// Pending spawns are dropped when the zone ends
+    pub fn on_event(&mut self, event: &GameEvent) {
+        if let GameEvent::ZoneCleared { .. } = event {
+            self.cancel_all();
+        }
+    }
+
+    /// Drops every pending telegraph without spawning anything
+    pub fn cancel_all(&mut self) {
+        self.active.clear();
+    }
+
+    /// Ticks the warnings and returns a `SpawnNow` for each that expired, in
+    /// the order they were telegraphed
+    pub fn update(&mut self, delta: f32) -> Vec<GameEvent> {
+        let mut events = Vec::new();
+        self.active.retain_mut(|active| {
+            active.remaining -= delta.max(0.0);
+            if active.remaining > 0.0 {
+                return true;
+            }
+            events.push(GameEvent::SpawnNow(active.telegraph));
+            false
+        });
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::entities::EnemyType;
+    use crate::game::events::TelegraphKind;
+    use crate::game::systems::procedural::HazardType;
+    use crate::utils::Vec2;
+
+    fn fighter_at(x: f32, duration: f32) -> Telegraph {
+        Telegraph::new(
+            Vec2::new(x, -50.0),
+            duration,
+            TelegraphKind::Enemy(EnemyType::Fighter),
+        )
+    }
+
+    #[test]
+    fn test_spawn_fires_after_duration() {
+        let mut system = TelegraphSystem::new();
+        system.telegraph(fighter_at(10.0, DEFAULT_TELEGRAPH_DURATION));
+        system.telegraph(fighter_at(20.0, 0.5));
+
+        assert!(system.update(0.25).is_empty());
+        assert!((system.active()[0].progress() - 0.25).abs() < 0.001);
+
+        assert_eq!(
+            system.update(0.25),
+            vec![GameEvent::SpawnNow(fighter_at(20.0, 0.5))]
+        );
+        assert!(system.update(0.4).is_empty());
+        assert_eq!(
+            system.update(0.1),
+            vec![GameEvent::SpawnNow(fighter_at(10.0, 1.0))]
+        );
+        assert!(system.active().is_empty());
+    }
+
+    #[test]
+    fn test_zone_end_cancels_pending() {
+        let mut system = TelegraphSystem::new();
+        system.telegraph(fighter_at(0.0, 1.0));
+        system.telegraph(Telegraph::new(
+            Vec2::new(0.0, 0.0),
+            2.0,
+            TelegraphKind::Hazard(HazardType::Lightning),
+        ));
+
+        system.on_event(&GameEvent::ZoneCleared { zone_number: 1 });
+        assert!(system.active().is_empty());
+        assert!(system.update(5.0).is_empty());
+    }
+}
@@ -1,7 +1,10 @@
+use crate::error::{Error, Result};
+use crate::game::balance::{BalanceConfig, RarityWeights};
 use crate::game::state::UpgradeId;
 use crate::game::systems::weapon::WeaponId;
 use crate::utils::WeightedRandom;
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -9,14 +12,40 @@ pub struct UpgradeSystem {
     upgrade_pool: Vec<Upgrade>,
     synergy_map: HashMap<(UpgradeId, UpgradeId), SynergyBonus>,
     player_build: PlayerBuild,
+    zone: u32,
+    rarity_weights: RarityWeights,
+    /// Offer at most one upgrade per category when the eligible pool allows
+    category_spread: bool,
+    rng: StdRng,
 }
 
 impl UpgradeSystem {
     pub fn new() -> Self {
+        Self::with_rng(StdRng::from_entropy())
+    }
+
+    /// Creates a system whose offered choices are determined by `seed`
+    /// and the picks made so far
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_rng(StdRng::seed_from_u64(seed))
+    }
+
+    /// Seeded system offering upgrades with `balance`'s rarity weights
+    pub fn with_balance(seed: u64, balance: &BalanceConfig) -> Self {
+        let mut system = Self::with_seed(seed);
+        system.set_balance(balance);
+        system
+    }
+
+    fn with_rng(rng: StdRng) -> Self {
         let mut system = Self {
             upgrade_pool: Vec::new(),
             synergy_map: HashMap::new(),
             player_build: PlayerBuild::new(),
+            zone: 1,
+            rarity_weights: RarityWeights::default(),
+            category_spread: false,
+            rng,
         };
 
         system.init_upgrades();
@@ -204,6 +233,7 @@ impl UpgradeSystem {
                 name: "Devastating Assault".to_string(),
                 description: "Rapid fire + High damage = Extra critical chance".to_string(),
                 weight_multiplier: 2.0,
+                min_zone: 1,
                 bonus_effects: vec![Effect::StatModifier {
                     stat: Stat::CritChance,
                     modifier: Modifier::Add(0.15),
@@ -218,6 +248,7 @@ impl UpgradeSystem {
                 name: "Fortress".to_string(),
                 description: "High health + High armor = Massive survivability".to_string(),
                 weight_multiplier: 1.8,
+                min_zone: 1,
                 bonus_effects: vec![Effect::StatModifier {
                     stat: Stat::MaxHealth,
                     modifier: Modifier::Multiply(1.2),
@@ -232,6 +263,7 @@ impl UpgradeSystem {
                 name: "Speed Demon".to_string(),
                 description: "Fast movement + Dash = Reduced dash cooldown".to_string(),
                 weight_multiplier: 1.5,
+                min_zone: 3,
                 bonus_effects: vec![Effect::StatModifier {
                     stat: Stat::AbilityCooldown,
                     modifier: Modifier::Multiply(0.7),
@@ -240,8 +272,21 @@ impl UpgradeSystem {
         );
     }
 
+    pub fn reseed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    /// When enabled, offers draw from categories not yet offered first and
+    /// only repeat a category once every eligible category is used
+    pub fn set_category_spread(&mut self, enabled: bool) {
+        self.category_spread = enabled;
+    }
+
     pub fn generate_upgrade_choices(&mut self, count: u32, zone: u32) -> Vec<Upgrade> {
         let weights = self.calculate_upgrade_weights(zone);
+        if self.category_spread {
+            return self.spread_choices(weights, count);
+        }
         let mut weighted_random = WeightedRandom::new();
 
         for (upgrade, weight) in weights {
@@ -252,7 +297,7 @@ impl UpgradeSystem {
         let mut selected_ids = Vec::new();
 
         for _ in 0..count {
-            if let Some(upgrade) = weighted_random.select(&mut rand::thread_rng()) {
+            if let Some(upgrade) = weighted_random.select(&mut self.rng) {
                 if !selected_ids.contains(&upgrade.id) {
                     choices.push(upgrade.clone());
                     selected_ids.push(upgrade.id);
@@ -263,6 +308,32 @@ impl UpgradeSystem {
         choices
     }
 
+    /// Draws `count` distinct upgrades, restricting each draw to categories
+    /// not yet offered while any remain
+    fn spread_choices(&mut self, mut weights: Vec<(Upgrade, f32)>, count: u32) -> Vec<Upgrade> {
+        let mut choices: Vec<Upgrade> = Vec::new();
+
+        while choices.len() < count as usize && !weights.is_empty() {
+            let is_new_category =
+                |upgrade: &Upgrade| !choices.iter().any(|c| c.category == upgrade.category);
+            let fresh = weights.iter().any(|(upgrade, _)| is_new_category(upgrade));
+
+            let mut weighted_random = WeightedRandom::new();
+            for (index, (upgrade, weight)) in weights.iter().enumerate() {
+                if !fresh || is_new_category(upgrade) {
+                    weighted_random.add(index, *weight);
+                }
+            }
+
+            match weighted_random.select(&mut self.rng) {
+                Some(&index) => choices.push(weights.swap_remove(index).0),
+                None => break,
+            }
+        }
+
+        choices
+    }
+
     fn calculate_upgrade_weights(&self, zone: u32) -> Vec<(Upgrade, f32)> {
         self.upgrade_pool
             .iter()
@@ -280,12 +351,7 @@ impl UpgradeSystem {
                     return None;
                 }
 
-                let mut weight = match upgrade.rarity {
-                    Rarity::Common => 100.0,
-                    Rarity::Rare => 25.0,
-                    Rarity::Epic => 5.0,
-                    Rarity::Legendary => 1.0,
-                };
+                let mut weight = self.rarity_weights.weight(upgrade.rarity)?;
 
                 // Increase weight for upgrades with synergies
                 for owned_upgrade in &self.player_build.upgrades {
@@ -320,20 +386,70 @@ impl UpgradeSystem {
             .collect()
     }
 
-    pub fn apply_upgrade(&mut self, upgrade_id: UpgradeId) {
-        if let Some(upgrade) = self.upgrade_pool.iter().find(|u| u.id == upgrade_id) {
-            self.player_build.add_upgrade(upgrade_id);
-
-            // Check for synergies
-            for owned_upgrade in &self.player_build.upgrades {
-                if let Some(synergy) = self
-                    .synergy_map
-                    .get(&(*owned_upgrade, upgrade_id))
-                    .or_else(|| self.synergy_map.get(&(upgrade_id, *owned_upgrade)))
-                {
-                    self.player_build.add_synergy(synergy.clone());
+    /// Adds an upgrade to the build, recording its stat modifiers the first
+    /// time it is taken. Fails on ids missing from the upgrade pool.
+    pub fn apply_upgrade(&mut self, upgrade_id: UpgradeId) -> Result<()> {
+        let upgrade = self
+            .upgrade_pool
+            .iter()
+            .find(|u| u.id == upgrade_id)
+            .ok_or(Error::UnknownUpgrade(upgrade_id))?;
+
+        if !self.player_build.has_upgrade(upgrade_id) {
+            for effect in &upgrade.effects {
+                match effect {
+                    Effect::StatModifier { stat, modifier } => {
+                        self.player_build.apply_stat_modifier(*stat, *modifier);
+                    }
+                    Effect::PassiveEffect { effect } => self.player_build.passives.push(*effect),
+                    _ => {}
                 }
             }
+            self.player_build.add_upgrade(upgrade_id);
+        }
+        self.activate_synergies();
+        Ok(())
+    }
+
+    /// Swaps in new rarity weights for future offers
+    pub fn set_balance(&mut self, balance: &BalanceConfig) {
+        self.rarity_weights = balance.rarity_weights;
+    }
+
+    /// Moves the run to `zone`, activating any owned synergies it unlocks
+    pub fn set_zone(&mut self, zone: u32) {
+        self.zone = zone;
+        self.activate_synergies();
+    }
+
+    pub fn zone(&self) -> u32 {
+        self.zone
+    }
+
+    /// Activates synergies whose components are owned and whose zone
+    /// requirement is met, skipping ones already active
+    fn activate_synergies(&mut self) {
+        let mut ready: Vec<(&(UpgradeId, UpgradeId), &SynergyBonus)> = self
+            .synergy_map
+            .iter()
+            .filter(|(&(first, second), synergy)| {
+                self.player_build.has_upgrade(first)
+                    && self.player_build.has_upgrade(second)
+                    && self.zone >= synergy.min_zone
+                    && !self
+                        .player_build
+                        .active_synergies
+                        .iter()
+                        .any(|active| active.name == synergy.name)
+            })
+            .collect();
+
+        // Map order is arbitrary; keep activation order stable
+        ready.sort_by_key(|(&(first, second), _)| (first.0, second.0));
+        let ready: Vec<SynergyBonus> = ready.into_iter().map(|(_, s)| s.clone()).collect();
+
+        for synergy in ready {
+            self.player_build.add_synergy(synergy);
         }
     }
 
@@ -344,6 +460,30 @@ impl UpgradeSystem {
     pub fn get_active_synergies(&self) -> &[SynergyBonus] {
         &self.player_build.active_synergies
     }
+
+    /// Synergies that are one upgrade away from activating, paired with the
+    /// upgrades still missing. Sorted by synergy name for stable display.
+    pub fn nearly_complete_synergies(&self) -> Vec<(SynergyBonus, Vec<UpgradeId>)> {
+        let mut hints: Vec<(SynergyBonus, Vec<UpgradeId>)> = self
+            .synergy_map
+            .iter()
+            .filter_map(|(&(first, second), synergy)| {
+                let missing: Vec<UpgradeId> = [first, second]
+                    .into_iter()
+                    .filter(|id| !self.player_build.has_upgrade(*id))
+                    .collect();
+
+                if missing.len() == 1 {
+                    Some((synergy.clone(), missing))
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        hints.sort_by(|a, b| a.0.name.cmp(&b.0.name));
+        hints
+    }
 }
 
 impl Default for UpgradeSystem {
@@ -370,6 +510,9 @@ pub enum Rarity {
     Rare,
     Epic,
     Legendary,
+    /// Rarity from newer data; never offered
+    #[serde(other)]
+    Unknown,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -401,6 +544,14 @@ pub enum Stat {
     AbilityCooldown,
 }
 
+impl Stat {
+    /// Whether upgrades raise the stat by flat amounts (`Add`) rather than
+    /// scaling it (`Multiply`)
+    pub fn is_additive(self) -> bool {
+        matches!(self, Stat::Armor | Stat::CritChance)
+    }
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum Modifier {
     Add(f32),
@@ -423,14 +574,63 @@ pub struct SynergyBonus {
     pub name: String,
     pub description: String,
     pub weight_multiplier: f32,
+    /// Earliest zone in which the synergy can activate
+    #[serde(default)]
+    pub min_zone: u32,
     pub bonus_effects: Vec<Effect>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(from = "SavedPlayerBuild")]
 pub struct PlayerBuild {
     pub upgrades: Vec<UpgradeId>,
     pub active_synergies: Vec<SynergyBonus>,
-    pub stat_modifiers: HashMap<Stat, f32>,
+    /// Product of every `Multiply` per stat
+    pub stat_multipliers: HashMap<Stat, f32>,
+    /// Sum of every `Add` per stat
+    pub stat_additions: HashMap<Stat, f32>,
+    /// Passive effects granted by owned upgrades
+    pub passives: Vec<PassiveEffectType>,
+}
+
+/// On-disk form of `PlayerBuild`, also accepting saves from before
+/// multipliers and additions were kept apart
+#[derive(Deserialize)]
+struct SavedPlayerBuild {
+    upgrades: Vec<UpgradeId>,
+    active_synergies: Vec<SynergyBonus>,
+    #[serde(default)]
+    stat_multipliers: HashMap<Stat, f32>,
+    #[serde(default)]
+    stat_additions: HashMap<Stat, f32>,
+    /// One value per stat, every modifier already resolved from a base of 1.0
+    #[serde(default)]
+    stat_modifiers: HashMap<Stat, f32>,
+    #[serde(default)]
+    passives: Vec<PassiveEffectType>,
+}
+
+impl From<SavedPlayerBuild> for PlayerBuild {
+    fn from(saved: SavedPlayerBuild) -> Self {
+        let mut build = Self {
+            upgrades: saved.upgrades,
+            active_synergies: saved.active_synergies,
+            stat_multipliers: saved.stat_multipliers,
+            stat_additions: saved.stat_additions,
+            passives: saved.passives,
+        };
+
+        // A resolved value is a multiplier for scaled stats, and 1.0 plus
+        // the bonus for flat ones
+        for (stat, value) in saved.stat_modifiers {
+            if stat.is_additive() {
+                build.stat_additions.entry(stat).or_insert(value - 1.0);
+            } else {
+                build.stat_multipliers.entry(stat).or_insert(value);
+            }
+        }
+        build
+    }
 }
 
 impl PlayerBuild {
@@ -438,7 +638,9 @@ impl PlayerBuild {
         Self {
             upgrades: Vec::new(),
             active_synergies: Vec::new(),
-            stat_modifiers: HashMap::new(),
+            stat_multipliers: HashMap::new(),
+            stat_additions: HashMap::new(),
+            passives: Vec::new(),
         }
     }
 
@@ -456,17 +658,54 @@ impl PlayerBuild {
         self.active_synergies.push(synergy);
     }
 
+    /// `effective_stat` from a base of 1.0
     pub fn get_stat_modifier(&self, stat: Stat) -> f32 {
-        *self.stat_modifiers.get(&stat).unwrap_or(&1.0)
+        self.effective_stat(stat, 1.0)
+    }
+
+    /// Folds every modifier on `stat`, from upgrades and active synergies,
+    /// onto `base`: all multipliers first, then all additions, so the result
+    /// does not depend on acquisition order
+    pub fn effective_stat(&self, stat: Stat, base: f32) -> f32 {
+        let mut multiplier = self.stat_multipliers.get(&stat).copied().unwrap_or(1.0);
+        let mut addition = self.stat_additions.get(&stat).copied().unwrap_or(0.0);
+
+        let synergy_effects = self
+            .active_synergies
+            .iter()
+            .flat_map(|synergy| &synergy.bonus_effects);
+        for effect in synergy_effects {
+            match effect {
+                Effect::StatModifier {
+                    stat: bonus_stat,
+                    modifier,
+                } if *bonus_stat == stat => match modifier {
+                    Modifier::Add(value) => addition += value,
+                    Modifier::Multiply(value) => multiplier *= value,
+                },
+                _ => {}
+            }
+        }
+
+        base * multiplier + addition
+    }
+
+    /// Product of every `PickupBonus` passive, 1.0 without any
+    pub fn pickup_bonus(&self) -> f32 {
+        self.passives
+            .iter()
+            .map(|passive| match passive {
+                PassiveEffectType::PickupBonus(bonus) => *bonus,
+                _ => 1.0,
+            })
+            .product()
     }
 
     pub fn apply_stat_modifier(&mut self, stat: Stat, modifier: Modifier) {
-        let current = self.get_stat_modifier(stat);
-        let new_value = match modifier {
-            Modifier::Add(value) => current + value,
-            Modifier::Multiply(value) => current * value,
-        };
-        self.stat_modifiers.insert(stat, new_value);
+        match modifier {
+            Modifier::Add(value) => *self.stat_additions.entry(stat).or_insert(0.0) += value,
+            Modifier::Multiply(value) => *self.stat_multipliers.entry(stat).or_insert(1.0) *= value,
+        }
     }
 }
 
@@ -492,23 +731,73 @@ mod tests {
         }
     }
 
+    fn choice_ids(system: &mut UpgradeSystem, zone: u32) -> Vec<UpgradeId> {
+        system
+            .generate_upgrade_choices(3, zone)
+            .iter()
+            .map(|u| u.id)
+            .collect()
+    }
+
+    #[test]
+    fn test_seeded_choices_are_deterministic() {
+        let mut a = UpgradeSystem::with_seed(2024);
+        let mut b = UpgradeSystem::with_seed(2024);
+
+        for zone in 1..6 {
+            let choices_a = choice_ids(&mut a, zone);
+            let choices_b = choice_ids(&mut b, zone);
+            assert_eq!(choices_a, choices_b);
+
+            // Advance both builds through the same pick
+            if let Some(pick) = choices_a.first() {
+                a.apply_upgrade(*pick).unwrap();
+                b.apply_upgrade(*pick).unwrap();
+            }
+        }
+
+        assert_eq!(choice_ids(&mut a, 5), choice_ids(&mut b, 5));
+    }
+
+    #[test]
+    fn test_reseed_restarts_sequence() {
+        let mut system = UpgradeSystem::with_seed(99);
+        let first: Vec<_> = (0..5).map(|_| choice_ids(&mut system, 5)).collect();
+
+        system.reseed(99);
+        let second: Vec<_> = (0..5).map(|_| choice_ids(&mut system, 5)).collect();
+        assert_eq!(first, second);
+    }
+
     #[test]
     fn test_upgrade_application() {
         let mut system = UpgradeSystem::new();
         let upgrade_id = UpgradeId(1);
 
-        system.apply_upgrade(upgrade_id);
+        system.apply_upgrade(upgrade_id).unwrap();
         assert!(system.player_build.has_upgrade(upgrade_id));
     }
 
+    #[test]
+    fn test_unknown_upgrade_is_an_error() {
+        let mut system = UpgradeSystem::new();
+        let bogus = UpgradeId(9999);
+
+        assert_eq!(
+            system.apply_upgrade(bogus),
+            Err(Error::UnknownUpgrade(bogus))
+        );
+        assert!(!system.player_build.has_upgrade(bogus));
+    }
+
     #[test]
     fn test_synergy_detection() {
         let mut system = UpgradeSystem::new();
 
-        system.apply_upgrade(UpgradeId(1)); // Rapid Fire
+        system.apply_upgrade(UpgradeId(1)).unwrap(); // Rapid Fire
         assert_eq!(system.get_active_synergies().len(), 0);
 
-        system.apply_upgrade(UpgradeId(2)); // Armor Piercing
+        system.apply_upgrade(UpgradeId(2)).unwrap(); // Armor Piercing
         assert_eq!(system.get_active_synergies().len(), 1);
         assert_eq!(
             system.get_active_synergies()[0].name,
@@ -516,6 +805,93 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_effective_stat_includes_synergies() {
+        let mut system = UpgradeSystem::new();
+        system.apply_upgrade(UpgradeId(1)).unwrap(); // Rapid Fire
+        system.apply_upgrade(UpgradeId(1)).unwrap();
+        let build = system.get_player_build();
+        assert!((build.effective_stat(Stat::FireRate, 2.0) - 2.6).abs() < 0.001);
+        assert_eq!(build.effective_stat(Stat::CritChance, 0.05), 0.05);
+
+        system.apply_upgrade(UpgradeId(2)).unwrap(); // Armor Piercing, completing Devastating Assault
+        let build = system.get_player_build();
+        assert!((build.effective_stat(Stat::FireRate, 2.0) - 2.6).abs() < 0.001);
+        assert!((build.effective_stat(Stat::CritChance, 0.05) - 0.2).abs() < 0.001);
+        assert!((build.effective_stat(Stat::Damage, 10.0) - 15.0).abs() < 0.001);
+        assert_eq!(build.effective_stat(Stat::Armor, 3.0), 3.0);
+    }
+
+    #[test]
+    fn test_zone_gated_synergy() {
+        let mut system = UpgradeSystem::new();
+
+        system.apply_upgrade(UpgradeId(7)).unwrap(); // Afterburner
+        system.apply_upgrade(UpgradeId(8)).unwrap(); // Evasive Maneuvers
+        assert!(system.get_active_synergies().is_empty());
+
+        system.set_zone(2);
+        assert!(system.get_active_synergies().is_empty());
+
+        system.set_zone(3);
+        assert_eq!(system.get_active_synergies().len(), 1);
+        assert_eq!(system.get_active_synergies()[0].name, "Speed Demon");
+
+        // Further zones don't activate it twice
+        system.set_zone(4);
+        assert_eq!(system.get_active_synergies().len(), 1);
+    }
+
+    #[test]
+    fn test_nearly_complete_synergies() {
+        let mut system = UpgradeSystem::new();
+        assert!(system.nearly_complete_synergies().is_empty());
+
+        system.apply_upgrade(UpgradeId(1)).unwrap(); // Rapid Fire
+        let hints = system.nearly_complete_synergies();
+        assert_eq!(hints.len(), 1);
+        assert_eq!(hints[0].0.name, "Devastating Assault");
+        assert_eq!(hints[0].1, vec![UpgradeId(2)]); // Armor Piercing
+
+        system.apply_upgrade(UpgradeId(2)).unwrap();
+        assert!(system.nearly_complete_synergies().is_empty());
+    }
+
+    #[test]
+    fn test_category_spread_offers_one_per_category() {
+        for seed in 0..50 {
+            let mut system = UpgradeSystem::with_seed(seed);
+            system.set_category_spread(true);
+
+            let choices = system.generate_upgrade_choices(3, 5);
+            assert_eq!(choices.len(), 3);
+            for (i, choice) in choices.iter().enumerate() {
+                assert!(choices[i + 1..]
+                    .iter()
+                    .all(|other| other.category != choice.category));
+            }
+        }
+    }
+
+    #[test]
+    fn test_category_spread_repeats_only_when_needed() {
+        let mut system = UpgradeSystem::with_seed(3);
+        system.set_category_spread(true);
+        let kept = [UpgradeCategory::Weapon, UpgradeCategory::Defense];
+        system.upgrade_pool.retain(|u| kept.contains(&u.category));
+
+        let choices = system.generate_upgrade_choices(3, 5);
+        assert_eq!(choices.len(), 3);
+        let weapons = choices
+            .iter()
+            .filter(|u| u.category == UpgradeCategory::Weapon)
+            .count();
+        assert!(weapons == 1 || weapons == 2);
+        assert_ne!(choices[0].id, choices[1].id);
+        assert_ne!(choices[1].id, choices[2].id);
+        assert_ne!(choices[0].id, choices[2].id);
+    }
+
     #[test]
     fn test_rarity_weights() {
         let system = UpgradeSystem::new();
@@ -536,6 +912,23 @@ mod tests {
         assert!(common_weight > rare_weight);
     }
 
+    #[test]
+    fn test_configured_rarity_weights() {
+        let balance =
+            BalanceConfig::from_json(r#"{"rarity_weights": {"common": 2.0, "rare": 50.0}}"#)
+                .unwrap();
+        let system = UpgradeSystem::with_balance(1, &balance);
+        let weights = system.calculate_upgrade_weights(1);
+
+        for (upgrade, weight) in &weights {
+            match upgrade.rarity {
+                Rarity::Common => assert_eq!(*weight, 2.0),
+                Rarity::Rare => assert_eq!(*weight, 50.0),
+                _ => {}
+            }
+        }
+    }
+
     #[test]
     fn test_player_build() {
         let mut build = PlayerBuild::new();
@@ -551,6 +944,57 @@ mod tests {
         assert_eq!(build.get_stat_modifier(Stat::Damage), 2.0);
     }
 
+    #[test]
+    fn test_stat_modifiers_ignore_acquisition_order() {
+        let modifiers = [
+            Modifier::Add(0.5),
+            Modifier::Multiply(1.5),
+            Modifier::Add(0.25),
+            Modifier::Multiply(2.0),
+        ];
+        let orders: [[usize; 4]; 4] = [[0, 1, 2, 3], [3, 2, 1, 0], [1, 3, 0, 2], [2, 0, 3, 1]];
+
+        for order in orders {
+            let mut build = PlayerBuild::new();
+            for index in order {
+                build.apply_stat_modifier(Stat::Damage, modifiers[index]);
+            }
+            // 1.0 * 1.5 * 2.0 + 0.5 + 0.25
+            assert_eq!(build.get_stat_modifier(Stat::Damage), 3.75);
+        }
+        assert_eq!(PlayerBuild::new().get_stat_modifier(Stat::Damage), 1.0);
+    }
+
+    #[test]
+    fn test_legacy_stat_modifiers_migrate() {
+        let json = r#"{
+            "upgrades": [1, 4],
+            "active_synergies": [],
+            "stat_modifiers": {"FireRate": 1.3, "CritChance": 1.1}
+        }"#;
+        let build: PlayerBuild = serde_json::from_str(json).unwrap();
+
+        assert!((build.effective_stat(Stat::FireRate, 2.0) - 2.6).abs() < 0.001);
+        assert!((build.effective_stat(Stat::CritChance, 0.05) - 0.15).abs() < 0.001);
+        assert_eq!(build.stat_multipliers.get(&Stat::CritChance), None);
+
+        // Current saves round-trip unchanged
+        let saved = serde_json::to_string(&build).unwrap();
+        let reloaded: PlayerBuild = serde_json::from_str(&saved).unwrap();
+        assert_eq!(reloaded.stat_multipliers, build.stat_multipliers);
+        assert_eq!(reloaded.stat_additions, build.stat_additions);
+    }
+
+    #[test]
+    fn test_treasure_hunter_grants_pickup_bonus() {
+        let mut system = UpgradeSystem::new();
+        assert_eq!(system.get_player_build().pickup_bonus(), 1.0);
+
+        system.apply_upgrade(UpgradeId(10)).unwrap();
+        system.apply_upgrade(UpgradeId(10)).unwrap();
+        assert_eq!(system.get_player_build().pickup_bonus(), 1.25);
+    }
+
     #[test]
     fn test_prerequisite_filtering() {
         let mut system = UpgradeSystem::new();
@@ -572,7 +1016,7 @@ mod tests {
         assert!(!weights_before.iter().any(|(u, _)| u.id == UpgradeId(99)));
 
         // Apply prerequisite
-        system.apply_upgrade(UpgradeId(1));
+        system.apply_upgrade(UpgradeId(1)).unwrap();
 
         // Should now be available
         let weights_after = system.calculate_upgrade_weights(1);
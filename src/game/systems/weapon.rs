@@ -1,5 +1,8 @@
+use crate::engine::atlas::TextureRegion;
+use crate::error::{Error, Result};
+use crate::game::components::{DamageType, Sprite};
 use crate::game::entities::{Entity, ProjectileOwner};
-use crate::utils::Vec2;
+use crate::utils::{Color, ObjectPool, Vec2};
 use cgmath::InnerSpace;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -11,6 +14,16 @@ pub struct WeaponId(pub u32);
 pub struct WeaponSystem {
     weapons: HashMap<WeaponId, WeaponDefinition>,
     upgrades: HashMap<WeaponId, Vec<WeaponUpgrade>>,
+    #[serde(skip)]
+    fire_states: HashMap<WeaponId, FireState>,
+    /// Reserve ammo for weapons that consume it
+    #[serde(default)]
+    ammo: HashMap<WeaponId, u32>,
+    /// Most projectiles one shooter of each owner may have in flight
+    #[serde(default)]
+    projectile_caps: HashMap<ProjectileOwner, u32>,
+    #[serde(skip)]
+    live_projectiles: HashMap<(ProjectileOwner, Option<Entity>), u32>,
 }
 
 impl WeaponSystem {
@@ -18,6 +31,10 @@ impl WeaponSystem {
         Self {
             weapons: HashMap::new(),
             upgrades: HashMap::new(),
+            fire_states: HashMap::new(),
+            ammo: HashMap::new(),
+            projectile_caps: HashMap::new(),
+            live_projectiles: HashMap::new(),
         }
     }
 
@@ -29,17 +46,45 @@ impl WeaponSystem {
         self.weapons.get(&id)
     }
 
-    pub fn apply_upgrade(&mut self, weapon_id: WeaponId, upgrade: WeaponUpgrade) {
-        // Apply upgrade to weapon definition first
-        if let Some(weapon) = self.weapons.get_mut(&weapon_id) {
-            weapon.apply_upgrade(&upgrade);
+    /// Reserve ammo for a weapon, or `None` if it doesn't use ammo
+    pub fn ammo(&self, weapon_id: WeaponId) -> Option<u32> {
+        let weapon = self.weapons.get(&weapon_id)?;
+        weapon.ammo_consumption?;
+        Some(self.ammo.get(&weapon_id).copied().unwrap_or(0))
+    }
+
+    /// Adds `amount` to the reserves of every weapon that consumes ammo
+    pub fn refill_ammo(&mut self, amount: u32) {
+        for weapon in self.weapons.values() {
+            if weapon.ammo_consumption.is_some() {
+                let reserve = self.ammo.entry(weapon.id).or_insert(0);
+                *reserve = reserve.saturating_add(amount);
+            }
         }
+    }
+
+    /// Adds `amount` to one weapon's reserve if it consumes ammo
+    pub fn refill_weapon_ammo(&mut self, weapon_id: WeaponId, amount: u32) {
+        if self.ammo(weapon_id).is_some() {
+            let reserve = self.ammo.entry(weapon_id).or_insert(0);
+            *reserve = reserve.saturating_add(amount);
+        }
+    }
+
+    pub fn apply_upgrade(&mut self, weapon_id: WeaponId, upgrade: WeaponUpgrade) -> Result<()> {
+        // Apply upgrade to weapon definition first
+        let weapon = self
+            .weapons
+            .get_mut(&weapon_id)
+            .ok_or(Error::UnknownWeapon(weapon_id))?;
+        weapon.apply_upgrade(&upgrade);
 
         // Store the upgrade in history
         self.upgrades
             .entry(weapon_id)
             .or_insert_with(Vec::new)
             .push(upgrade);
+        Ok(())
     }
 
     pub fn fire(
@@ -48,26 +93,258 @@ impl WeaponSystem {
         origin: Vec2,
         direction: Vec2,
         owner: ProjectileOwner,
-    ) -> Vec<Projectile> {
-        if let Some(weapon) = self.weapons.get(&weapon_id) {
-            let pattern = self.calculate_spread(&weapon.spread_pattern, direction);
-
-            pattern
-                .into_iter()
-                .map(|dir| Projectile {
-                    position: origin,
-                    velocity: dir * weapon.projectile_speed,
-                    damage: weapon.base_damage,
-                    projectile_type: weapon.projectile_type.clone(),
-                    owner,
-                    lifetime: 5.0,
-                })
-                .collect()
-        } else {
-            Vec::new()
+    ) -> Result<Vec<Projectile>> {
+        let stationary = Vec2::new(0.0, 0.0);
+        self.fire_with_velocity(weapon_id, origin, direction, owner, stationary)
+    }
+
+    /// Like `fire`, from a shooter moving at `shooter_velocity`: each
+    /// projectile gains the weapon's `inherit_velocity` share of it
+    pub fn fire_with_velocity(
+        &self,
+        weapon_id: WeaponId,
+        origin: Vec2,
+        direction: Vec2,
+        owner: ProjectileOwner,
+        shooter_velocity: Vec2,
+    ) -> Result<Vec<Projectile>> {
+        let weapon = self
+            .weapons
+            .get(&weapon_id)
+            .ok_or(Error::UnknownWeapon(weapon_id))?;
+        let pattern = self.calculate_spread(&weapon.spread_pattern, direction);
+        let inherited = shooter_velocity * weapon.inherit_velocity;
+
+        Ok(pattern
+            .into_iter()
+            .map(|dir| Projectile {
+                position: origin,
+                prev_position: origin,
+                velocity: dir * weapon.projectile_speed + inherited,
+                damage: weapon.base_damage,
+                damage_type: weapon.projectile_type.damage_type(),
+                projectile_type: weapon.projectile_type.clone(),
+                owner,
+                lifetime: 5.0,
+                max_range: weapon.max_range,
+                distance_traveled: 0.0,
+                source: None,
+            })
+            .collect())
+    }
+
+    /// Limits every shooter of `owner` to `cap` projectiles in flight: the
+    /// player counts as one shooter and each enemy entity as another
+    pub fn set_projectile_cap(&mut self, owner: ProjectileOwner, cap: u32) {
+        self.projectile_caps.insert(owner, cap);
+    }
+
+    /// Projectiles in flight from a shooter, as of the last
+    /// `track_live_projectiles` plus anything fired since
+    pub fn live_projectiles(&self, owner: ProjectileOwner, source: Option<Entity>) -> u32 {
+        self.live_projectiles
+            .get(&(owner, source))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Recounts live projectiles from those still in flight, freeing up the
+    /// caps of shooters whose projectiles expired
+    pub fn track_live_projectiles(&mut self, projectiles: &[Projectile]) {
+        self.live_projectiles.clear();
+        for projectile in projectiles.iter().filter(|p| p.is_alive()) {
+            *self
+                .live_projectiles
+                .entry((projectile.owner, projectile.source))
+                .or_insert(0) += 1;
+        }
+    }
+
+    /// Like `fire_with_velocity`, on behalf of `source`, but fires nothing if
+    /// the volley would take the shooter past its owner's projectile cap
+    pub fn fire_from(
+        &mut self,
+        weapon_id: WeaponId,
+        muzzle: Muzzle,
+        direction: Vec2,
+        owner: ProjectileOwner,
+        source: Option<Entity>,
+    ) -> Result<Vec<Projectile>> {
+        let Muzzle { position, velocity } = muzzle;
+        let mut projectiles =
+            self.fire_with_velocity(weapon_id, position, direction, owner, velocity)?;
+        let live = self.live_projectiles(owner, source) + projectiles.len() as u32;
+        if let Some(&cap) = self.projectile_caps.get(&owner) {
+            if live > cap {
+                return Ok(Vec::new());
+            }
+        }
+
+        for projectile in &mut projectiles {
+            projectile.source = source;
+        }
+        self.live_projectiles.insert((owner, source), live);
+        Ok(projectiles)
+    }
+
+    /// Advances the weapon's fire timing by `delta` and returns whatever it
+    /// fires this frame. Call every frame with the current trigger state;
+    /// bursts keep firing across frames after the trigger is released.
+    pub fn try_fire(
+        &mut self,
+        weapon_id: WeaponId,
+        trigger_held: bool,
+        delta: f32,
+        muzzle: Muzzle,
+        direction: Vec2,
+        owner: ProjectileOwner,
+    ) -> Result<Vec<Projectile>> {
+        let (fire_mode, cooldown) = match self.weapons.get(&weapon_id) {
+            Some(weapon) if weapon.fire_rate > 0.0 => (weapon.fire_mode, 1.0 / weapon.fire_rate),
+            Some(_) => return Ok(Vec::new()),
+            None => return Err(Error::UnknownWeapon(weapon_id)),
+        };
+
+        let mut state = self.fire_states.remove(&weapon_id).unwrap_or_default();
+        let pressed = trigger_held && !state.trigger_was_held;
+        state.trigger_was_held = trigger_held;
+        state.cooldown = (state.cooldown - delta).max(0.0);
+
+        let mut shots = 0;
+        if state.burst_remaining > 0 {
+            state.burst_timer -= delta;
+            while state.burst_remaining > 0 && state.burst_timer <= 0.0 {
+                shots += 1;
+                state.burst_remaining -= 1;
+                if let FireMode::Burst { interval, .. } = fire_mode {
+                    state.burst_timer += interval;
+                }
+            }
+            if state.burst_remaining == 0 {
+                state.cooldown = cooldown;
+            }
+        } else if state.cooldown <= 0.0 {
+            match fire_mode {
+                FireMode::Single if pressed => {
+                    shots = 1;
+                    state.cooldown = cooldown;
+                }
+                FireMode::Auto if trigger_held => {
+                    shots = 1;
+                    state.cooldown = cooldown;
+                }
+                FireMode::Burst { count, interval } if pressed && count > 0 => {
+                    shots = 1;
+                    state.burst_remaining = count - 1;
+                    state.burst_timer = interval;
+                    if state.burst_remaining == 0 {
+                        state.cooldown = cooldown;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        self.fire_states.insert(weapon_id, state);
+
+        let mut projectiles = Vec::new();
+        for _ in 0..shots {
+            projectiles.extend(self.fire_from(weapon_id, muzzle, direction, owner, None)?);
+        }
+        Ok(projectiles)
+    }
+
+    /// Fires every active weapon together with the trigger held, each from
+    /// its hardpoint: `hardpoints[i]` offsets the muzzle for `active[i]`, and
+    /// weapons without one fire from the muzzle itself. Each weapon keeps its
+    /// own cooldown, so ones still cooling down are skipped. An unknown id
+    /// fails the whole volley before any weapon fires.
+    pub fn fire_all(
+        &mut self,
+        active: &[WeaponId],
+        delta: f32,
+        muzzle: Muzzle,
+        direction: Vec2,
+        owner: ProjectileOwner,
+        hardpoints: &[Vec2],
+    ) -> Result<(Vec<Projectile>, Vec<WeaponFireEvent>)> {
+        if let Some(&unknown) = active.iter().find(|id| !self.weapons.contains_key(id)) {
+            return Err(Error::UnknownWeapon(unknown));
+        }
+
+        let mut projectiles = Vec::new();
+        let mut events = Vec::new();
+
+        for (i, &weapon_id) in active.iter().enumerate() {
+            let offset = hardpoints.get(i).copied().unwrap_or(Vec2::new(0.0, 0.0));
+            let hardpoint = Muzzle::moving(muzzle.position + offset, muzzle.velocity);
+            let shots = self.try_fire(weapon_id, true, delta, hardpoint, direction, owner)?;
+            if shots.is_empty() {
+                continue;
+            }
+
+            events.push(WeaponFireEvent {
+                weapon_id,
+                origin: hardpoint.position,
+                projectile_count: shots.len() as u32,
+            });
+            projectiles.extend(shots);
+        }
+
+        Ok((projectiles, events))
+    }
+
+    /// Sustained damage per second, assuming every projectile of every shot
+    /// hits. Burst weapons include the time spent between burst shots.
+    pub fn effective_dps(&self, weapon_id: WeaponId) -> f32 {
+        match self.weapons.get(&weapon_id) {
+            Some(weapon) => {
+                weapon.base_damage
+                    * weapon.spread_pattern.projectile_count() as f32
+                    * weapon.shots_per_second()
+            }
+            None => 0.0,
         }
     }
 
+    /// Seconds of sustained fire to bring `target_health` to zero through
+    /// `target_armor`. Infinite if the weapon can't damage the target.
+    pub fn time_to_kill(&self, weapon_id: WeaponId, target_health: i32, target_armor: f32) -> f32 {
+        let dps = self.effective_dps(weapon_id) * (1.0 - target_armor).max(0.0);
+        if dps <= 0.0 {
+            return f32::INFINITY;
+        }
+        target_health.max(0) as f32 / dps
+    }
+
+    /// Display state of every registered weapon, ordered by id, so the UI
+    /// can fetch it all in one call
+    pub fn hud_snapshot(&self) -> Vec<WeaponHudState> {
+        let mut snapshot: Vec<WeaponHudState> = self
+            .weapons
+            .values()
+            .map(|weapon| WeaponHudState {
+                id: weapon.id,
+                name: weapon.name.clone(),
+                ammo: self.ammo(weapon.id),
+                cooldown_remaining: self
+                    .fire_states
+                    .get(&weapon.id)
+                    .map_or(0.0, |state| state.cooldown),
+                dps: self.effective_dps(weapon.id),
+            })
+            .collect();
+        snapshot.sort_by_key(|state| state.id.0);
+        snapshot
+    }
+
+    /// Whether a burst is still in progress for this weapon
+    pub fn is_bursting(&self, weapon_id: WeaponId) -> bool {
+        self.fire_states
+            .get(&weapon_id)
+            .is_some_and(|state| state.burst_remaining > 0)
+    }
+
     fn calculate_spread(&self, pattern: &SpreadPattern, direction: Vec2) -> Vec<Vec2> {
         match pattern {
             SpreadPattern::Single => vec![direction.normalize()],
@@ -124,9 +401,23 @@ pub struct WeaponDefinition {
     pub projectile_type: ProjectileType,
     pub spread_pattern: SpreadPattern,
     pub ammo_consumption: Option<u32>,
+    #[serde(default)]
+    pub fire_mode: FireMode,
+    /// Travel distance after which projectiles expire, replacing the
+    /// time-based lifetime so range doesn't depend on projectile speed
+    #[serde(default)]
+    pub max_range: Option<f32>,
+    /// Fraction of the shooter's velocity added to each projectile, from 0
+    /// (fires at pattern velocity) to 1 (fully carries the shooter's motion)
+    #[serde(default)]
+    pub inherit_velocity: f32,
 }
 
 impl WeaponDefinition {
+    pub fn builder(id: WeaponId) -> WeaponDefinitionBuilder {
+        WeaponDefinitionBuilder::new(id)
+    }
+
     pub fn apply_upgrade(&mut self, upgrade: &WeaponUpgrade) {
         self.base_damage *= upgrade.damage_multiplier;
         self.fire_rate *= upgrade.fire_rate_multiplier;
@@ -137,6 +428,103 @@ impl WeaponDefinition {
             self.spread_pattern = new_pattern.clone();
         }
     }
+
+    /// Average shots fired per second with the trigger held (or tapped as
+    /// fast as the cooldown allows)
+    pub fn shots_per_second(&self) -> f32 {
+        if self.fire_rate <= 0.0 {
+            return 0.0;
+        }
+        match self.fire_mode {
+            FireMode::Burst { count, interval } if count > 1 => {
+                let cycle = 1.0 / self.fire_rate + (count - 1) as f32 * interval;
+                count as f32 / cycle
+            }
+            _ => self.fire_rate,
+        }
+    }
+}
+
+/// Builds a `WeaponDefinition` from defaults matching the basic machine
+/// gun: a single automatic stream of bullets, no ammo and unlimited range
+#[derive(Debug, Clone)]
+pub struct WeaponDefinitionBuilder {
+    definition: WeaponDefinition,
+}
+
+impl WeaponDefinitionBuilder {
+    pub fn new(id: WeaponId) -> Self {
+        Self {
+            definition: WeaponDefinition {
+                id,
+                name: String::new(),
+                base_damage: 10.0,
+                fire_rate: 8.0,
+                projectile_speed: 600.0,
+                projectile_type: ProjectileType::Bullet,
+                spread_pattern: SpreadPattern::Single,
+                ammo_consumption: None,
+                fire_mode: FireMode::Auto,
+                max_range: None,
+                inherit_velocity: 0.0,
+            },
+        }
+    }
+
+    pub fn name(mut self, name: &str) -> Self {
+        self.definition.name = name.to_string();
+        self
+    }
+
+    pub fn damage(mut self, damage: f32) -> Self {
+        self.definition.base_damage = damage;
+        self
+    }
+
+    pub fn fire_rate(mut self, fire_rate: f32) -> Self {
+        self.definition.fire_rate = fire_rate;
+        self
+    }
+
+    pub fn projectile_speed(mut self, speed: f32) -> Self {
+        self.definition.projectile_speed = speed;
+        self
+    }
+
+    pub fn projectile_type(mut self, projectile_type: ProjectileType) -> Self {
+        self.definition.projectile_type = projectile_type;
+        self
+    }
+
+    pub fn spread(mut self, spread_pattern: SpreadPattern) -> Self {
+        self.definition.spread_pattern = spread_pattern;
+        self
+    }
+
+    /// Ammo used per shot; weapons without it fire freely
+    pub fn ammo_consumption(mut self, per_shot: u32) -> Self {
+        self.definition.ammo_consumption = Some(per_shot);
+        self
+    }
+
+    pub fn fire_mode(mut self, fire_mode: FireMode) -> Self {
+        self.definition.fire_mode = fire_mode;
+        self
+    }
+
+    pub fn max_range(mut self, range: f32) -> Self {
+        self.definition.max_range = Some(range);
+        self
+    }
+
+    pub fn inherit_velocity(mut self, factor: f32) -> Self {
+        self.definition.inherit_velocity = factor;
+        self
+    }
+
+    pub fn build(self) -> WeaponDefinition {
+        self.definition
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -149,6 +537,78 @@ pub enum SpreadPattern {
     Custom(fn(Vec2) -> Vec<Vec2>),
 }
 
+impl SpreadPattern {
+    /// Projectiles spawned by a single shot
+    pub fn projectile_count(&self) -> u32 {
+        match self {
+            SpreadPattern::Single => 1,
+            SpreadPattern::Twin { .. } => 2,
+            SpreadPattern::Spread { count, .. } | SpreadPattern::Circle { count } => *count,
+            SpreadPattern::Custom(func) => func(Vec2::new(0.0, 1.0)).len() as u32,
+        }
+    }
+}
+
+/// How holding the trigger translates into shots
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum FireMode {
+    /// One shot per trigger press
+    Single,
+    /// `count` shots per press, `interval` seconds apart
+    Burst { count: u32, interval: f32 },
+    /// Fires continuously at `fire_rate` while held
+    #[default]
+    Auto,
+}
+
+/// Where shots leave from, and how fast the shooter is moving there
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Muzzle {
+    pub position: Vec2,
+    pub velocity: Vec2,
+}
+
+impl Muzzle {
+    /// A muzzle on a stationary shooter
+    pub fn at(position: Vec2) -> Self {
+        Self::moving(position, Vec2::new(0.0, 0.0))
+    }
+
+    pub fn moving(position: Vec2, velocity: Vec2) -> Self {
+        Self { position, velocity }
+    }
+}
+
+/// One weapon firing during `WeaponSystem::fire_all`, for muzzle flashes
+/// and sound
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeaponFireEvent {
+    pub weapon_id: WeaponId,
+    pub origin: Vec2,
+    pub projectile_count: u32,
+}
+
+/// Per-weapon data shown on the HUD
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeaponHudState {
+    pub id: WeaponId,
+    pub name: String,
+    /// Reserve ammo, or `None` for weapons that don't use it
+    pub ammo: Option<u32>,
+    /// Seconds until the weapon can fire again
+    pub cooldown_remaining: f32,
+    pub dps: f32,
+}
+
+/// Per-weapon trigger and burst timing
+#[derive(Debug, Clone, Copy, Default)]
+struct FireState {
+    cooldown: f32,
+    burst_remaining: u32,
+    burst_timer: f32,
+    trigger_was_held: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WeaponUpgrade {
     pub name: String,
@@ -158,7 +618,7 @@ pub struct WeaponUpgrade {
     pub new_spread_pattern: Option<SpreadPattern>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ProjectileType {
     Bullet,
     Missile,
@@ -167,27 +627,224 @@ pub enum ProjectileType {
     Rocket,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl ProjectileType {
+    pub fn damage_type(&self) -> DamageType {
+        match self {
+            ProjectileType::Bullet => DamageType::Kinetic,
+            ProjectileType::Laser => DamageType::Energy,
+            ProjectileType::Missile | ProjectileType::Rocket | ProjectileType::Bomb => {
+                DamageType::Explosive
+            }
+        }
+    }
+
+    /// Blast produced when a projectile of this type hits or expires
+    pub fn explosion(&self) -> Option<Explosion> {
+        match self {
+            ProjectileType::Missile => Some(Explosion::new(40.0, Falloff::Linear)),
+            ProjectileType::Rocket => Some(Explosion::new(60.0, Falloff::Linear)),
+            ProjectileType::Bomb => Some(Explosion::new(100.0, Falloff::Quadratic)),
+            ProjectileType::Bullet | ProjectileType::Laser => None,
+        }
+    }
+}
+
+/// Tint for a projectile. The damage type picks the hue so the screen reads
+/// at a glance (yellow kinetic, red explosive, cyan energy, orange fire) and
+/// the projectile type shades it. `None` uses the type's own damage type.
+pub fn projectile_color(
+    projectile_type: &ProjectileType,
+    damage_type: Option<DamageType>,
+) -> Color {
+    let base = match damage_type.unwrap_or_else(|| projectile_type.damage_type()) {
+        DamageType::Kinetic => Color::rgb(1.0, 0.9, 0.3),
+        DamageType::Explosive => Color::rgb(0.9, 0.25, 0.2),
+        DamageType::Energy => Color::rgb(0.3, 0.8, 1.0),
+        DamageType::Fire => Color::rgb(1.0, 0.55, 0.0),
+    };
+
+    match projectile_type {
+        ProjectileType::Bullet => base,
+        ProjectileType::Rocket => base.lerp(&Color::white(), 0.15),
+        ProjectileType::Missile => base.lerp(&Color::white(), 0.3),
+        // Beams get a hot, bright core
+        ProjectileType::Laser => base.lerp(&Color::white(), 0.5),
+        ProjectileType::Bomb => base.lerp(&Color::black(), 0.3),
+    }
+}
+
+/// How explosion damage drops off from the center to the edge of the blast
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Falloff {
+    Linear,
+    Quadratic,
+}
+
+impl Falloff {
+    /// Damage multiplier at `distance` from the center: 1 at the center,
+    /// 0 at and beyond `radius`
+    pub fn scale(&self, distance: f32, radius: f32) -> f32 {
+        if radius <= 0.0 || distance >= radius {
+            return 0.0;
+        }
+        let remaining = 1.0 - distance.max(0.0) / radius;
+        match self {
+            Falloff::Linear => remaining,
+            Falloff::Quadratic => remaining * remaining,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Explosion {
+    pub radius: f32,
+    pub falloff: Falloff,
+}
+
+impl Explosion {
+    pub fn new(radius: f32, falloff: Falloff) -> Self {
+        Self { radius, falloff }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "SavedProjectile")]
 pub struct Projectile {
     pub position: Vec2,
+    /// Position before the last `update`; with `position` this gives the
+    /// segment covered in the last step, for swept hits and trails. Older
+    /// saves load it at `position`, an empty segment.
+    pub prev_position: Vec2,
     pub velocity: Vec2,
     pub damage: f32,
+    #[serde(default)]
+    pub damage_type: DamageType,
     pub projectile_type: ProjectileType,
     pub owner: ProjectileOwner,
     pub lifetime: f32,
+    /// When set, the projectile expires by distance instead of `lifetime`
+    #[serde(default)]
+    pub max_range: Option<f32>,
+    #[serde(default)]
+    pub distance_traveled: f32,
+    /// Entity that fired it, if any, for per-shooter projectile caps
+    #[serde(default)]
+    pub source: Option<Entity>,
+}
+
+/// On-disk form of `Projectile`, tolerating saves from before
+/// `prev_position` existed
+#[derive(Deserialize)]
+struct SavedProjectile {
+    position: Vec2,
+    #[serde(default)]
+    prev_position: Option<Vec2>,
+    velocity: Vec2,
+    damage: f32,
+    #[serde(default)]
+    damage_type: DamageType,
+    projectile_type: ProjectileType,
+    owner: ProjectileOwner,
+    lifetime: f32,
+    #[serde(default)]
+    max_range: Option<f32>,
+    #[serde(default)]
+    distance_traveled: f32,
+    #[serde(default)]
+    source: Option<Entity>,
+}
+
+impl From<SavedProjectile> for Projectile {
+    fn from(saved: SavedProjectile) -> Self {
+        Self {
+            position: saved.position,
+            prev_position: saved.prev_position.unwrap_or(saved.position),
+            velocity: saved.velocity,
+            damage: saved.damage,
+            damage_type: saved.damage_type,
+            projectile_type: saved.projectile_type,
+            owner: saved.owner,
+            lifetime: saved.lifetime,
+            max_range: saved.max_range,
+            distance_traveled: saved.distance_traveled,
+            source: saved.source,
+        }
+    }
 }
 
 impl Projectile {
     pub fn update(&mut self, delta: f32) {
-        self.position += self.velocity * delta;
+        let step = self.velocity * delta;
+        self.prev_position = self.position;
+        self.position += step;
+        self.distance_traveled += step.magnitude();
         self.lifetime -= delta;
     }
 
     pub fn is_alive(&self) -> bool {
-        self.lifetime > 0.0
+        match self.max_range {
+            Some(range) => self.distance_traveled < range,
+            None => self.lifetime > 0.0,
+        }
+    }
+
+    pub fn explosion(&self) -> Option<Explosion> {
+        self.projectile_type.explosion()
+    }
+
+    pub fn color(&self) -> Color {
+        projectile_color(&self.projectile_type, Some(self.damage_type))
+    }
+
+    /// Sprite for the projectile, tinted by `projectile_color`
+    pub fn sprite(&self, region: TextureRegion) -> Sprite {
+        let mut sprite = Sprite::from_region(region);
+        sprite.color = self.color();
+        sprite
+    }
+
+    /// Restores every field to its default so a recycled projectile carries
+    /// no state from its previous use
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+impl Default for Projectile {
+    fn default() -> Self {
+        Self {
+            position: Vec2::new(0.0, 0.0),
+            prev_position: Vec2::new(0.0, 0.0),
+            velocity: Vec2::new(0.0, 0.0),
+            damage: 0.0,
+            damage_type: DamageType::Kinetic,
+            projectile_type: ProjectileType::Bullet,
+            owner: ProjectileOwner::Player,
+            lifetime: 0.0,
+            max_range: None,
+            distance_traveled: 0.0,
+            source: None,
+        }
     }
 }
 
+/// Pool of projectiles recycled through `Projectile::reset`
+pub fn projectile_pool(max_size: usize) -> ObjectPool<Projectile> {
+    ObjectPool::new(Projectile::default, Projectile::reset, max_size)
+}
+
+/// Acquires a projectile from the pool, checking in debug builds that the
+/// reset left no stale state behind
+pub fn acquire_projectile(pool: &mut ObjectPool<Projectile>) -> Result<Projectile> {
+    let projectile = pool.acquire().ok_or(Error::PoolExhausted)?;
+    debug_assert_eq!(
+        projectile,
+        Projectile::default(),
+        "pooled projectile was not fully reset"
+    );
+    Ok(projectile)
+}
+
 fn rotate_vector(v: Vec2, angle: f32) -> Vec2 {
     let cos = angle.cos();
     let sin = angle.sin();
@@ -197,6 +854,7 @@ fn rotate_vector(v: Vec2, angle: f32) -> Vec2 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::engine::webgl::TextureHandle;
 
     #[test]
     fn test_weapon_system_creation() {
@@ -210,6 +868,9 @@ mod tests {
             projectile_type: ProjectileType::Bullet,
             spread_pattern: SpreadPattern::Single,
             ammo_consumption: None,
+            fire_mode: FireMode::Auto,
+            max_range: None,
+            inherit_velocity: 0.0,
         };
 
         system.register_weapon(weapon);
@@ -228,16 +889,21 @@ mod tests {
             projectile_type: ProjectileType::Bullet,
             spread_pattern: SpreadPattern::Single,
             ammo_consumption: None,
+            fire_mode: FireMode::Auto,
+            max_range: None,
+            inherit_velocity: 0.0,
         };
 
         system.register_weapon(weapon);
 
-        let projectiles = system.fire(
-            WeaponId(1),
-            Vec2::new(0.0, 0.0),
-            Vec2::new(0.0, 1.0),
-            ProjectileOwner::Player,
-        );
+        let projectiles = system
+            .fire(
+                WeaponId(1),
+                Vec2::new(0.0, 0.0),
+                Vec2::new(0.0, 1.0),
+                ProjectileOwner::Player,
+            )
+            .unwrap();
 
         assert_eq!(projectiles.len(), 1);
         assert_eq!(projectiles[0].damage, 10.0);
@@ -258,20 +924,58 @@ mod tests {
                 angle: 30.0,
             },
             ammo_consumption: None,
+            fire_mode: FireMode::Auto,
+            max_range: None,
+            inherit_velocity: 0.0,
         };
 
         system.register_weapon(weapon);
 
-        let projectiles = system.fire(
-            WeaponId(1),
-            Vec2::new(0.0, 0.0),
-            Vec2::new(0.0, 1.0),
-            ProjectileOwner::Player,
-        );
+        let projectiles = system
+            .fire(
+                WeaponId(1),
+                Vec2::new(0.0, 0.0),
+                Vec2::new(0.0, 1.0),
+                ProjectileOwner::Player,
+            )
+            .unwrap();
 
         assert_eq!(projectiles.len(), 3);
     }
 
+    #[test]
+    fn test_projectiles_inherit_shooter_velocity() {
+        let mut system = WeaponSystem::new();
+        system.register_weapon(gun(1, SpreadPattern::Twin { spacing: 8.0 }));
+        let carried = WeaponDefinition::builder(WeaponId(2))
+            .projectile_speed(500.0)
+            .inherit_velocity(1.0)
+            .build();
+        system.register_weapon(carried);
+
+        system.register_weapon(gun(3, SpreadPattern::Twin { spacing: 8.0 }));
+
+        let up = Vec2::new(0.0, -1.0);
+        let owner = ProjectileOwner::Player;
+        let moving = Muzzle::moving(Vec2::new(0.0, 0.0), Vec2::new(120.0, -80.0));
+        let mut fire = |id: u32, muzzle: Muzzle| {
+            system
+                .try_fire(WeaponId(id), true, 0.016, muzzle, up, owner)
+                .unwrap()
+        };
+
+        let full = fire(2, moving);
+        assert_eq!(full[0].velocity, up * 500.0 + moving.velocity);
+
+        // No inheritance matches a stationary shot
+        let ignored = fire(1, moving);
+        let stationary = fire(3, Muzzle::at(moving.position));
+        let velocities =
+            |shots: &[Projectile]| -> Vec<Vec2> { shots.iter().map(|p| p.velocity).collect() };
+        assert_eq!(velocities(&ignored), velocities(&stationary));
+        assert_eq!(ignored.len(), 2);
+    }
+
     #[test]
     fn test_weapon_upgrade() {
         let mut system = WeaponSystem::new();
@@ -284,6 +988,9 @@ mod tests {
             projectile_type: ProjectileType::Bullet,
             spread_pattern: SpreadPattern::Single,
             ammo_consumption: None,
+            fire_mode: FireMode::Auto,
+            max_range: None,
+            inherit_velocity: 0.0,
         };
 
         system.register_weapon(weapon);
@@ -296,21 +1003,445 @@ mod tests {
             new_spread_pattern: None,
         };
 
-        system.apply_upgrade(WeaponId(1), upgrade);
+        system.apply_upgrade(WeaponId(1), upgrade.clone()).unwrap();
 
         let weapon = system.get_weapon(WeaponId(1)).unwrap();
         assert_eq!(weapon.base_damage, 15.0);
+
+        assert_eq!(
+            system.apply_upgrade(WeaponId(9), upgrade),
+            Err(Error::UnknownWeapon(WeaponId(9)))
+        );
+    }
+
+    #[test]
+    fn test_unknown_weapon_is_an_error() {
+        let mut system = WeaponSystem::new();
+        let origin = Vec2::new(0.0, 0.0);
+        let up = Vec2::new(0.0, 1.0);
+        let owner = ProjectileOwner::Player;
+
+        let missing = Err(Error::UnknownWeapon(WeaponId(4)));
+        assert_eq!(system.fire(WeaponId(4), origin, up, owner), missing);
+        assert_eq!(
+            system.try_fire(WeaponId(4), true, 0.016, Muzzle::at(origin), up, owner),
+            missing
+        );
+    }
+
+    fn burst_weapon() -> WeaponDefinition {
+        WeaponDefinition {
+            id: WeaponId(1),
+            name: "Burst Rifle".to_string(),
+            base_damage: 10.0,
+            fire_rate: 2.0,
+            projectile_speed: 100.0,
+            projectile_type: ProjectileType::Bullet,
+            spread_pattern: SpreadPattern::Single,
+            ammo_consumption: None,
+            fire_mode: FireMode::Burst {
+                count: 3,
+                interval: 0.1,
+            },
+            max_range: None,
+            inherit_velocity: 0.0,
+        }
+    }
+
+    fn gun(id: u32, spread_pattern: SpreadPattern) -> WeaponDefinition {
+        WeaponDefinition::builder(WeaponId(id))
+            .name("Test Gun")
+            .fire_rate(5.0)
+            .projectile_speed(500.0)
+            .spread(spread_pattern)
+            .build()
+    }
+
+    #[test]
+    fn test_builder_defaults() {
+        let weapon = WeaponDefinition::builder(WeaponId(4)).damage(25.0).build();
+        assert_eq!(weapon.id, WeaponId(4));
+        assert_eq!(weapon.base_damage, 25.0);
+        assert!(weapon.fire_rate > 0.0);
+        assert!(weapon.projectile_speed > 0.0);
+        assert_eq!(weapon.projectile_type, ProjectileType::Bullet);
+        assert_eq!(weapon.spread_pattern.projectile_count(), 1);
+        assert_eq!(weapon.ammo_consumption, None);
+        assert_eq!(weapon.fire_mode, FireMode::Auto);
+        assert_eq!(weapon.max_range, None);
+        assert_eq!(weapon.inherit_velocity, 0.0);
+
+        // Fires as-is
+        let mut system = WeaponSystem::new();
+        system.register_weapon(weapon);
+        let (origin, up) = (Vec2::new(0.0, 0.0), Vec2::new(0.0, 1.0));
+        let owner = ProjectileOwner::Player;
+        let shots = system
+            .try_fire(WeaponId(4), true, 0.016, Muzzle::at(origin), up, owner)
+            .unwrap();
+        assert_eq!(shots.len(), 1);
+        assert_eq!(shots[0].damage, 25.0);
+    }
+
+    #[test]
+    fn test_builder_overrides() {
+        let weapon = WeaponDefinition::builder(WeaponId(9))
+            .name("Flak")
+            .damage(12.0)
+            .fire_rate(2.0)
+            .projectile_speed(300.0)
+            .projectile_type(ProjectileType::Missile)
+            .spread(SpreadPattern::Spread {
+                count: 3,
+                angle: 20.0,
+            })
+            .ammo_consumption(2)
+            .fire_mode(FireMode::Single)
+            .max_range(450.0)
+            .build();
+
+        assert_eq!(weapon.name, "Flak");
+        assert_eq!(weapon.fire_rate, 2.0);
+        assert_eq!(weapon.projectile_speed, 300.0);
+        assert_eq!(weapon.projectile_type, ProjectileType::Missile);
+        assert_eq!(weapon.spread_pattern.projectile_count(), 3);
+        assert_eq!(weapon.ammo_consumption, Some(2));
+        assert_eq!(weapon.fire_mode, FireMode::Single);
+        assert_eq!(weapon.max_range, Some(450.0));
+    }
+
+    #[test]
+    fn test_effective_dps_counts_projectiles() {
+        let mut system = WeaponSystem::new();
+        system.register_weapon(gun(1, SpreadPattern::Single));
+        system.register_weapon(gun(2, SpreadPattern::Twin { spacing: 0.2 }));
+        system.register_weapon(gun(3, SpreadPattern::Circle { count: 8 }));
+
+        let single = system.effective_dps(WeaponId(1));
+        assert_eq!(single, 50.0);
+        assert!((system.effective_dps(WeaponId(2)) - 2.0 * single).abs() < 1e-4);
+        assert!((system.effective_dps(WeaponId(3)) - 8.0 * single).abs() < 1e-4);
+        assert_eq!(system.effective_dps(WeaponId(99)), 0.0);
+    }
+
+    #[test]
+    fn test_burst_dps_includes_burst_interval() {
+        let mut system = WeaponSystem::new();
+        system.register_weapon(burst_weapon());
+        let weapon = system.get_weapon(WeaponId(1)).unwrap();
+
+        // Three shots per 0.5s cooldown + 2 * 0.1s of burst spacing
+        assert!((weapon.shots_per_second() - 3.0 / 0.7).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_time_to_kill_with_armor() {
+        let mut system = WeaponSystem::new();
+        system.register_weapon(gun(1, SpreadPattern::Single));
+
+        let unarmored = system.time_to_kill(WeaponId(1), 100, 0.0);
+        let armored = system.time_to_kill(WeaponId(1), 100, 0.5);
+        assert!((unarmored - 2.0).abs() < 1e-4);
+        assert!((armored - 2.0 * unarmored).abs() < 1e-4);
+        assert_eq!(system.time_to_kill(WeaponId(1), 100, 1.0), f32::INFINITY);
+    }
+
+    #[test]
+    fn test_burst_fire_spacing() {
+        let mut system = WeaponSystem::new();
+        system.register_weapon(burst_weapon());
+
+        let mut shot_times = Vec::new();
+        let mut time: f32 = 0.0;
+        for _ in 0..20 {
+            let projectiles = system
+                .try_fire(
+                    WeaponId(1),
+                    true,
+                    0.05,
+                    Muzzle::at(Vec2::new(0.0, 0.0)),
+                    Vec2::new(0.0, 1.0),
+                    ProjectileOwner::Player,
+                )
+                .unwrap();
+            for _ in projectiles {
+                shot_times.push(time);
+            }
+            time += 0.05;
+        }
+
+        // Holding the trigger never re-triggers the burst
+        assert_eq!(shot_times.len(), 3);
+        assert!((shot_times[1] - shot_times[0] - 0.1).abs() < 0.001);
+        assert!((shot_times[2] - shot_times[1] - 0.1).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_burst_no_retrigger_until_complete() {
+        let mut system = WeaponSystem::new();
+        system.register_weapon(burst_weapon());
+        let fire = |system: &mut WeaponSystem, trigger: bool, delta: f32| {
+            system
+                .try_fire(
+                    WeaponId(1),
+                    trigger,
+                    delta,
+                    Muzzle::at(Vec2::new(0.0, 0.0)),
+                    Vec2::new(0.0, 1.0),
+                    ProjectileOwner::Player,
+                )
+                .unwrap()
+                .len()
+        };
+
+        assert_eq!(fire(&mut system, true, 0.0), 1);
+        assert!(system.is_bursting(WeaponId(1)));
+
+        // Re-pressing mid-burst only continues the current burst
+        assert_eq!(fire(&mut system, false, 0.05), 0);
+        assert_eq!(fire(&mut system, true, 0.05), 1);
+        assert_eq!(fire(&mut system, false, 0.1), 1);
+        assert!(!system.is_bursting(WeaponId(1)));
+
+        // Cooldown (1 / fire_rate) must elapse before the next burst
+        assert_eq!(fire(&mut system, true, 0.1), 0);
+        assert_eq!(fire(&mut system, false, 0.5), 0);
+        assert_eq!(fire(&mut system, true, 0.0), 1);
+    }
+
+    #[test]
+    fn test_auto_and_single_fire() {
+        let mut system = WeaponSystem::new();
+        let mut weapon = burst_weapon();
+        weapon.fire_mode = FireMode::Auto;
+        system.register_weapon(weapon);
+
+        let mut auto_shots = 0;
+        for _ in 0..10 {
+            auto_shots += system
+                .try_fire(
+                    WeaponId(1),
+                    true,
+                    0.25,
+                    Muzzle::at(Vec2::new(0.0, 0.0)),
+                    Vec2::new(0.0, 1.0),
+                    ProjectileOwner::Player,
+                )
+                .unwrap()
+                .len();
+        }
+        assert_eq!(auto_shots, 5);
+
+        let mut system = WeaponSystem::new();
+        let mut weapon = burst_weapon();
+        weapon.fire_mode = FireMode::Single;
+        system.register_weapon(weapon);
+
+        let mut single_shots = 0;
+        for _ in 0..10 {
+            single_shots += system
+                .try_fire(
+                    WeaponId(1),
+                    true,
+                    0.25,
+                    Muzzle::at(Vec2::new(0.0, 0.0)),
+                    Vec2::new(0.0, 1.0),
+                    ProjectileOwner::Player,
+                )
+                .unwrap()
+                .len();
+        }
+        assert_eq!(single_shots, 1);
+    }
+
+    #[test]
+    fn test_fire_all_uses_hardpoints() {
+        let mut system = WeaponSystem::new();
+        system.register_weapon(gun(1, SpreadPattern::Single));
+        system.register_weapon(gun(2, SpreadPattern::Twin { spacing: 4.0 }));
+
+        let left = Vec2::new(-10.0, 0.0);
+        let right = Vec2::new(10.0, 0.0);
+        let origin = Vec2::new(100.0, 100.0);
+        let (projectiles, events) = system
+            .fire_all(
+                &[WeaponId(1), WeaponId(2)],
+                0.016,
+                Muzzle::at(origin),
+                Vec2::new(0.0, 1.0),
+                ProjectileOwner::Player,
+                &[left, right],
+            )
+            .unwrap();
+
+        assert_eq!(projectiles.len(), 3);
+        assert_eq!(projectiles[0].position, origin + left);
+        assert!(projectiles[1..]
+            .iter()
+            .all(|p| p.position == origin + right));
+        assert_eq!(
+            events,
+            vec![
+                WeaponFireEvent {
+                    weapon_id: WeaponId(1),
+                    origin: origin + left,
+                    projectile_count: 1,
+                },
+                WeaponFireEvent {
+                    weapon_id: WeaponId(2),
+                    origin: origin + right,
+                    projectile_count: 2,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_projectile_cap_per_shooter() {
+        let mut system = WeaponSystem::new();
+        system.register_weapon(gun(1, SpreadPattern::Twin { spacing: 4.0 }));
+        system.set_projectile_cap(ProjectileOwner::Player, 3);
+        system.set_projectile_cap(ProjectileOwner::Enemy, 2);
+
+        let volley = |system: &mut WeaponSystem, owner, source| {
+            system
+                .fire_from(
+                    WeaponId(1),
+                    Muzzle::at(Vec2::new(0.0, 0.0)),
+                    Vec2::new(0.0, 1.0),
+                    owner,
+                    source,
+                )
+                .unwrap()
+        };
+        let player = ProjectileOwner::Player;
+        let mut live = volley(&mut system, player, None);
+        assert_eq!(live.len(), 2);
+        // A second volley would put four in flight
+        assert!(volley(&mut system, player, None).is_empty());
+
+        // Each enemy has its own allowance
+        let enemy = ProjectileOwner::Enemy;
+        for id in [7, 8] {
+            let source = Some(Entity::new(id));
+            let shots = volley(&mut system, enemy, source);
+            assert!(shots.iter().all(|p| p.source == source));
+            assert_eq!(shots.len(), 2);
+            assert!(volley(&mut system, enemy, source).is_empty());
+        }
+
+        // Still capped while the first volley is in flight
+        live.iter_mut().for_each(|p| p.update(1.0));
+        system.track_live_projectiles(&live);
+        assert_eq!(system.live_projectiles(player, None), 2);
+        assert!(volley(&mut system, player, None).is_empty());
+
+        live.iter_mut().for_each(|p| p.update(5.0));
+        live.retain(|p| p.is_alive());
+        system.track_live_projectiles(&live);
+        assert_eq!(system.live_projectiles(player, None), 0);
+        assert_eq!(volley(&mut system, player, None).len(), 2);
+    }
+
+    #[test]
+    fn test_fire_all_skips_weapons_on_cooldown() {
+        let mut system = WeaponSystem::new();
+        system.register_weapon(gun(1, SpreadPattern::Single));
+        system.register_weapon(gun(2, SpreadPattern::Single));
+
+        let origin = Vec2::new(0.0, 0.0);
+        let up = Vec2::new(0.0, 1.0);
+        let owner = ProjectileOwner::Player;
+        system
+            .try_fire(WeaponId(1), true, 0.016, Muzzle::at(origin), up, owner)
+            .unwrap();
+
+        let active = [WeaponId(1), WeaponId(2)];
+        let (projectiles, events) = system
+            .fire_all(&active, 0.016, Muzzle::at(origin), up, owner, &[])
+            .unwrap();
+        assert_eq!(projectiles.len(), 1);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].weapon_id, WeaponId(2));
+        assert_eq!(events[0].origin, origin);
+    }
+
+    #[test]
+    fn test_fire_all_rejects_unknown_weapon_before_firing() {
+        let mut system = WeaponSystem::new();
+        system.register_weapon(gun(1, SpreadPattern::Single));
+
+        let origin = Vec2::new(0.0, 0.0);
+        let up = Vec2::new(0.0, 1.0);
+        let owner = ProjectileOwner::Player;
+        let volley = [WeaponId(1), WeaponId(9)];
+        assert_eq!(
+            system.fire_all(&volley, 0.016, Muzzle::at(origin), up, owner, &[]),
+            Err(Error::UnknownWeapon(WeaponId(9)))
+        );
+
+        // The known weapon didn't fire, so it isn't cooling down
+        let (projectiles, _) = system
+            .fire_all(&[WeaponId(1)], 0.016, Muzzle::at(origin), up, owner, &[])
+            .unwrap();
+        assert_eq!(projectiles.len(), 1);
+    }
+
+    #[test]
+    fn test_hud_snapshot_reflects_state() {
+        let mut system = WeaponSystem::new();
+        system.register_weapon(gun(3, SpreadPattern::Single));
+        system.register_weapon(WeaponDefinition {
+            ammo_consumption: Some(1),
+            ..gun(1, SpreadPattern::Twin { spacing: 4.0 })
+        });
+        system.refill_ammo(12);
+
+        let origin = Vec2::new(0.0, 0.0);
+        let up = Vec2::new(0.0, 1.0);
+        let owner = ProjectileOwner::Player;
+        system
+            .try_fire(WeaponId(3), true, 0.016, Muzzle::at(origin), up, owner)
+            .unwrap();
+
+        let snapshot = system.hud_snapshot();
+        let ids: Vec<u32> = snapshot.iter().map(|state| state.id.0).collect();
+        assert_eq!(ids, vec![1, 3]);
+
+        assert_eq!(snapshot[0].ammo, Some(12));
+        assert_eq!(snapshot[0].cooldown_remaining, 0.0);
+        assert_eq!(snapshot[0].dps, system.effective_dps(WeaponId(1)));
+
+        assert_eq!(snapshot[1].ammo, None);
+        assert_eq!(snapshot[1].cooldown_remaining, 0.2);
+    }
+
+    #[test]
+    fn test_explosive_projectile_types() {
+        assert!(ProjectileType::Bullet.explosion().is_none());
+        let bomb = ProjectileType::Bomb.explosion().unwrap();
+        assert_eq!(bomb.falloff, Falloff::Quadratic);
+
+        assert_eq!(Falloff::Linear.scale(0.0, 100.0), 1.0);
+        assert_eq!(Falloff::Linear.scale(50.0, 100.0), 0.5);
+        assert_eq!(Falloff::Quadratic.scale(50.0, 100.0), 0.25);
+        assert_eq!(Falloff::Quadratic.scale(150.0, 100.0), 0.0);
     }
 
     #[test]
     fn test_projectile_update() {
         let mut projectile = Projectile {
             position: Vec2::new(0.0, 0.0),
+            prev_position: Vec2::new(0.0, 0.0),
             velocity: Vec2::new(10.0, 0.0),
             damage: 10.0,
+            damage_type: DamageType::Kinetic,
             projectile_type: ProjectileType::Bullet,
             owner: ProjectileOwner::Player,
             lifetime: 1.0,
+            max_range: None,
+            distance_traveled: 0.0,
+            source: None,
         };
 
         projectile.update(0.1);
@@ -322,4 +1453,174 @@ mod tests {
         projectile.update(1.0);
         assert!(!projectile.is_alive());
     }
+
+    #[test]
+    fn test_projectile_tracks_previous_position() {
+        let mut projectile = Projectile {
+            position: Vec2::new(5.0, 5.0),
+            velocity: Vec2::new(0.0, 100.0),
+            lifetime: 1.0,
+            ..Projectile::default()
+        };
+
+        projectile.update(0.1);
+        assert_eq!(projectile.prev_position, Vec2::new(5.0, 5.0));
+        assert_eq!(projectile.position, Vec2::new(5.0, 15.0));
+
+        projectile.update(0.1);
+        assert_eq!(projectile.prev_position, Vec2::new(5.0, 15.0));
+        assert_eq!(projectile.position, Vec2::new(5.0, 25.0));
+    }
+
+    #[test]
+    fn test_projectile_loads_without_prev_position() {
+        let projectile = Projectile {
+            position: Vec2::new(5.0, 5.0),
+            prev_position: Vec2::new(5.0, 0.0),
+            ..Projectile::default()
+        };
+        let mut json = serde_json::to_value(&projectile).unwrap();
+        json.as_object_mut().unwrap().remove("prev_position");
+
+        let loaded: Projectile = serde_json::from_value(json).unwrap();
+        assert_eq!(loaded.position, projectile.position);
+        assert_eq!((loaded.position - loaded.prev_position).magnitude(), 0.0);
+
+        let round_trip: Projectile =
+            serde_json::from_str(&serde_json::to_string(&projectile).unwrap()).unwrap();
+        assert_eq!(round_trip, projectile);
+    }
+
+    #[test]
+    fn test_projectiles_carry_damage_type() {
+        let mut system = WeaponSystem::new();
+        let mut laser = gun(1, SpreadPattern::Single);
+        laser.projectile_type = ProjectileType::Laser;
+        system.register_weapon(laser);
+
+        let projectiles = system
+            .fire(
+                WeaponId(1),
+                Vec2::new(0.0, 0.0),
+                Vec2::new(0.0, 1.0),
+                ProjectileOwner::Player,
+            )
+            .unwrap();
+        assert_eq!(projectiles[0].damage_type, DamageType::Energy);
+    }
+
+    #[test]
+    fn test_projectile_colors_are_distinct() {
+        let projectile_types = [
+            ProjectileType::Bullet,
+            ProjectileType::Missile,
+            ProjectileType::Laser,
+            ProjectileType::Bomb,
+            ProjectileType::Rocket,
+        ];
+        let damage_types = [
+            DamageType::Kinetic,
+            DamageType::Explosive,
+            DamageType::Energy,
+            DamageType::Fire,
+        ];
+
+        let mut colors = Vec::new();
+        for projectile_type in &projectile_types {
+            for &damage_type in &damage_types {
+                let color = projectile_color(projectile_type, Some(damage_type));
+                assert!(
+                    !colors.contains(&color),
+                    "{:?} {:?}",
+                    projectile_type,
+                    damage_type
+                );
+                colors.push(color);
+            }
+
+            // No override means the projectile's natural damage type
+            assert_eq!(
+                projectile_color(projectile_type, None),
+                projectile_color(projectile_type, Some(projectile_type.damage_type()))
+            );
+        }
+    }
+
+    #[test]
+    fn test_projectile_sprite_is_tinted() {
+        let projectile = Projectile {
+            damage_type: DamageType::Fire,
+            ..Projectile::default()
+        };
+        let sprite = projectile.sprite(TextureRegion::full(TextureHandle(1)));
+        assert_eq!(sprite.color, projectile.color());
+        assert_ne!(sprite.color, Color::white());
+    }
+
+    #[test]
+    fn test_projectile_expires_at_max_range() {
+        let mut system = WeaponSystem::new();
+        for (id, speed) in [(1, 100.0), (2, 800.0)] {
+            let mut weapon = gun(id, SpreadPattern::Single);
+            weapon.projectile_speed = speed;
+            weapon.max_range = Some(400.0);
+            system.register_weapon(weapon);
+        }
+
+        for id in [1, 2] {
+            let mut projectile = system
+                .fire(
+                    WeaponId(id),
+                    Vec2::new(0.0, 0.0),
+                    Vec2::new(0.0, 1.0),
+                    ProjectileOwner::Player,
+                )
+                .unwrap()
+                .remove(0);
+
+            let mut elapsed = 0.0;
+            while projectile.is_alive() {
+                projectile.update(0.01);
+                elapsed += 0.01;
+                assert!(elapsed < 10.0, "projectile never expired");
+            }
+
+            // Both expire within one step of 400 units, whatever their speed
+            let step = projectile.velocity.magnitude() * 0.01;
+            assert!(projectile.distance_traveled >= 400.0);
+            assert!(projectile.distance_traveled < 400.0 + step + 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_pooled_projectile_is_fully_reset() {
+        let mut pool = projectile_pool(1);
+
+        let mut projectile = acquire_projectile(&mut pool).unwrap();
+        projectile.position = Vec2::new(5.0, -3.0);
+        projectile.velocity = Vec2::new(0.0, 400.0);
+        projectile.damage = 25.0;
+        projectile.damage_type = DamageType::Explosive;
+        projectile.projectile_type = ProjectileType::Missile;
+        projectile.owner = ProjectileOwner::Enemy;
+        projectile.lifetime = 2.5;
+        projectile.max_range = Some(300.0);
+        projectile.distance_traveled = 120.0;
+        pool.release(projectile);
+
+        let recycled = acquire_projectile(&mut pool).unwrap();
+        assert_eq!(recycled, Projectile::default());
+        assert_eq!(recycled.position, Vec2::new(0.0, 0.0));
+        assert_eq!(recycled.velocity, Vec2::new(0.0, 0.0));
+        assert_eq!(recycled.damage, 0.0);
+        assert_eq!(recycled.damage_type, DamageType::Kinetic);
+        assert_eq!(recycled.projectile_type, ProjectileType::Bullet);
+        assert_eq!(recycled.owner, ProjectileOwner::Player);
+        assert_eq!(recycled.lifetime, 0.0);
+        assert_eq!(recycled.max_range, None);
+        assert_eq!(recycled.distance_traveled, 0.0);
+
+        // The single pooled projectile is still out
+        assert_eq!(acquire_projectile(&mut pool), Err(Error::PoolExhausted));
+    }
 }
@@ -0,0 +1,336 @@
+//! Central component storage keyed by entity
+
+use crate::game::components::{Collider, EntityFlags, Health, Position, Sprite, Velocity};
+use crate::game::entities::Entity;
+use std::collections::{HashMap, HashSet};
+
+/// Storage for a single component type
+#[derive(Debug, Clone)]
+pub struct ComponentStorage<T> {
+    components: HashMap<Entity, T>,
+}
+
+impl<T> ComponentStorage<T> {
+    pub fn new() -> Self {
+        Self {
+            components: HashMap::new(),
+        }
+    }
+
+    pub fn insert(&mut self, entity: Entity, component: T) -> Option<T> {
+        self.components.insert(entity, component)
+    }
+
+    pub fn remove(&mut self, entity: Entity) -> Option<T> {
+        self.components.remove(&entity)
+    }
+
+    pub fn get(&self, entity: Entity) -> Option<&T> {
+        self.components.get(&entity)
+    }
+
+    pub fn get_mut(&mut self, entity: Entity) -> Option<&mut T> {
+        self.components.get_mut(&entity)
+    }
+
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.components.contains_key(&entity)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, &T)> {
+        self.components
+            .iter()
+            .map(|(entity, component)| (*entity, component))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Entity, &mut T)> {
+        self.components
+            .iter_mut()
+            .map(|(entity, component)| (*entity, component))
+    }
+
+    pub fn len(&self) -> usize {
+        self.components.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.components.clear();
+    }
+}
+
+impl<T> Default for ComponentStorage<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Component types stored by the `World`
+pub trait Component: Sized {
+    fn storage(world: &World) -> &ComponentStorage<Self>;
+    fn storage_mut(world: &mut World) -> &mut ComponentStorage<Self>;
+}
+
+macro_rules! impl_component {
+    ($type:ty, $field:ident) => {
+        impl Component for $type {
+            fn storage(world: &World) -> &ComponentStorage<Self> {
+                &world.$field
+            }
+
+            fn storage_mut(world: &mut World) -> &mut ComponentStorage<Self> {
+                &mut world.$field
+            }
+        }
+    };
+}
+
+impl_component!(Position, positions);
+impl_component!(Velocity, velocities);
+impl_component!(Health, healths);
+impl_component!(Sprite, sprites);
+impl_component!(Collider, colliders);
+impl_component!(EntityFlags, flags);
+
+/// Entity registry owning every component store
+pub struct World {
+    generations: Vec<u32>,
+    free_ids: Vec<u32>,
+    alive: HashSet<Entity>,
+    positions: ComponentStorage<Position>,
+    velocities: ComponentStorage<Velocity>,
+    healths: ComponentStorage<Health>,
+    sprites: ComponentStorage<Sprite>,
+    colliders: ComponentStorage<Collider>,
+    flags: ComponentStorage<EntityFlags>,
+}
+
+impl World {
+    pub fn new() -> Self {
+        Self {
+            generations: Vec::new(),
+            free_ids: Vec::new(),
+            alive: HashSet::new(),
+            positions: ComponentStorage::new(),
+            velocities: ComponentStorage::new(),
+            healths: ComponentStorage::new(),
+            sprites: ComponentStorage::new(),
+            colliders: ComponentStorage::new(),
+            flags: ComponentStorage::new(),
+        }
+    }
+
+    pub fn spawn(&mut self) -> Entity {
+        // Reuse freed ids with a bumped generation so stale handles never alias
+        let entity = if let Some(id) = self.free_ids.pop() {
+            Entity {
+                id,
+                generation: self.generations[id as usize],
+            }
+        } else {
+            let id = self.generations.len() as u32;
+            self.generations.push(0);
+            Entity::new(id)
+        };
+
+        self.alive.insert(entity);
+        entity
+    }
+
+    pub fn despawn(&mut self, entity: Entity) -> bool {
+        if !self.alive.remove(&entity) {
+            return false;
+        }
+
+        self.positions.remove(entity);
+        self.velocities.remove(entity);
+        self.healths.remove(entity);
+        self.sprites.remove(entity);
+        self.colliders.remove(entity);
+        self.flags.remove(entity);
+
+        self.generations[entity.id as usize] += 1;
+        self.free_ids.push(entity.id);
+        true
+    }
+
+    pub fn is_alive(&self, entity: Entity) -> bool {
+        self.alive.contains(&entity)
+    }
+
+    pub fn entity_count(&self) -> usize {
+        self.alive.len()
+    }
+
+    pub fn entities(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.alive.iter().copied()
+    }
+
+    /// Attaches a component, returning false if the entity is not alive
+    pub fn insert<T: Component>(&mut self, entity: Entity, component: T) -> bool {
+        if !self.is_alive(entity) {
+            return false;
+        }
+        T::storage_mut(self).insert(entity, component);
+        true
+    }
+
+    pub fn remove<T: Component>(&mut self, entity: Entity) -> Option<T> {
+        T::storage_mut(self).remove(entity)
+    }
+
+    pub fn get<T: Component>(&self, entity: Entity) -> Option<&T> {
+        T::storage(self).get(entity)
+    }
+
+    pub fn get_mut<T: Component>(&mut self, entity: Entity) -> Option<&mut T> {
+        T::storage_mut(self).get_mut(entity)
+    }
+
+    pub fn has<T: Component>(&self, entity: Entity) -> bool {
+        T::storage(self).contains(entity)
+    }
+
+    pub fn storage<T: Component>(&self) -> &ComponentStorage<T> {
+        T::storage(self)
+    }
+
+    pub fn storage_mut<T: Component>(&mut self) -> &mut ComponentStorage<T> {
+        T::storage_mut(self)
+    }
+
+    /// Returns true if the entity carries all of the given flags
+    pub fn has_flags(&self, entity: Entity, flags: EntityFlags) -> bool {
+        self.flags.get(entity).is_some_and(|f| f.has(flags))
+    }
+
+    /// Despawns every entity, keeping generations so handles taken before
+    /// the clear stay stale
+    pub fn clear(&mut self) {
+        // Sorted so ids are reused in the same order regardless of hash order
+        let mut ids: Vec<u32> = self.alive.drain().map(|entity| entity.id).collect();
+        ids.sort_unstable_by(|a, b| b.cmp(a));
+        for id in ids {
+            self.generations[id as usize] += 1;
+            self.free_ids.push(id);
+        }
+
+        self.positions.clear();
+        self.velocities.clear();
+        self.healths.clear();
+        self.sprites.clear();
+        self.colliders.clear();
+        self.flags.clear();
+    }
+}
+
+impl Default for World {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::components::DamageType;
+
+    #[test]
+    fn test_spawn_and_get() {
+        let mut world = World::new();
+        let entity = world.spawn();
+
+        assert!(world.insert(entity, Position::new(1.0, 2.0)));
+        assert!(world.insert(entity, Health::new(50)));
+
+        assert_eq!(
+            world.get::<Position>(entity),
+            Some(&Position::new(1.0, 2.0))
+        );
+        assert!(world.get::<Velocity>(entity).is_none());
+
+        world
+            .get_mut::<Health>(entity)
+            .unwrap()
+            .take_damage(20.0, DamageType::Kinetic);
+        assert_eq!(world.get::<Health>(entity).unwrap().current, 30);
+    }
+
+    #[test]
+    fn test_despawn_removes_from_all_stores() {
+        let mut world = World::new();
+        let entity = world.spawn();
+
+        world.insert(entity, Position::new(0.0, 0.0));
+        world.insert(entity, Velocity::new(1.0, 1.0));
+        world.insert(entity, Health::new(100));
+        world.insert(entity, Collider::circle(5.0));
+        world.insert(entity, EntityFlags::ENEMY);
+
+        assert!(world.despawn(entity));
+        assert!(!world.is_alive(entity));
+        assert!(!world.has::<Position>(entity));
+        assert!(!world.has::<Velocity>(entity));
+        assert!(!world.has::<Health>(entity));
+        assert!(!world.has::<Sprite>(entity));
+        assert!(!world.has::<Collider>(entity));
+        assert!(!world.has::<EntityFlags>(entity));
+
+        // Despawning twice is a no-op
+        assert!(!world.despawn(entity));
+    }
+
+    #[test]
+    fn test_iteration_yields_only_live_entities() {
+        let mut world = World::new();
+        let a = world.spawn();
+        let b = world.spawn();
+        let c = world.spawn();
+
+        for entity in [a, b, c] {
+            world.insert(entity, Position::new(entity.id as f32, 0.0));
+        }
+
+        world.despawn(b);
+
+        let mut live: Vec<Entity> = world.storage::<Position>().iter().map(|(e, _)| e).collect();
+        live.sort_by_key(|e| e.id);
+        assert_eq!(live, vec![a, c]);
+        assert!(live.iter().all(|e| world.is_alive(*e)));
+    }
+
+    #[test]
+    fn test_stale_handle_rejected() {
+        let mut world = World::new();
+        let old = world.spawn();
+        world.despawn(old);
+
+        let new = world.spawn();
+        assert_eq!(new.id, old.id);
+        assert_ne!(new.generation, old.generation);
+
+        assert!(!world.insert(old, Position::new(0.0, 0.0)));
+        assert!(world.insert(new, Position::new(0.0, 0.0)));
+        assert!(world.get::<Position>(old).is_none());
+    }
+
+    #[test]
+    fn test_clear_invalidates_handles() {
+        let mut world = World::new();
+        let old = world.spawn();
+        world.insert(old, Position::new(1.0, 1.0));
+
+        world.clear();
+        assert_eq!(world.entity_count(), 0);
+
+        let new = world.spawn();
+        assert_eq!(new.id, old.id);
+        assert!(!world.is_alive(old));
+        assert!(!world.insert(old, Position::new(0.0, 0.0)));
+        assert!(world.insert(new, Position::new(2.0, 2.0)));
+        assert!(world.get::<Position>(old).is_none());
+    }
+}
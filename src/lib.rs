@@ -6,6 +6,7 @@
 use wasm_bindgen::prelude::*;
 
 pub mod engine;
+pub mod error;
 pub mod utils;
 pub mod game;
 pub mod web;
@@ -13,6 +14,7 @@ pub mod web;
 // Re-exports for convenience
 // pub use engine::renderer::Renderer;
 pub use game::components::*;
+pub use error::Error;
 
 #[wasm_bindgen]
 extern "C" {
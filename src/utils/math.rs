@@ -1,5 +1,433 @@
 //! Math utilities
 
+use cgmath::InnerSpace;
+use serde::{Deserialize, Serialize};
+
 pub fn lerp(a: f32, b: f32, t: f32) -> f32 {
     a + (b - a) * t
 }
+
+/// RGBA color with components in [0, 1]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Color {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+}
+
+impl Color {
+    pub fn new(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self { r, g, b, a }
+    }
+
+    pub fn rgb(r: f32, g: f32, b: f32) -> Self {
+        Self::new(r, g, b, 1.0)
+    }
+
+    pub fn white() -> Self {
+        Self::rgb(1.0, 1.0, 1.0)
+    }
+
+    pub fn black() -> Self {
+        Self::rgb(0.0, 0.0, 0.0)
+    }
+
+    pub fn transparent() -> Self {
+        Self::new(0.0, 0.0, 0.0, 0.0)
+    }
+
+    pub fn lerp(&self, other: &Color, t: f32) -> Color {
+        Color {
+            r: lerp(self.r, other.r, t),
+            g: lerp(self.g, other.g, t),
+            b: lerp(self.b, other.b, t),
+            a: lerp(self.a, other.a, t),
+        }
+    }
+}
+
+/// 2D placement of a drawable: translation, rotation in radians and scale
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub position: Vec2,
+    pub rotation: f32,
+    pub scale: Vec2,
+}
+
+impl Transform {
+    pub fn identity() -> Self {
+        Self {
+            position: Vec2::new(0.0, 0.0),
+            rotation: 0.0,
+            scale: Vec2::new(1.0, 1.0),
+        }
+    }
+
+    pub fn from_position(position: Vec2) -> Self {
+        Self {
+            position,
+            ..Self::identity()
+        }
+    }
+}
+
+/// Multi-stop color gradient sampled over [0, 1]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Gradient {
+    stops: Vec<(f32, Color)>,
+}
+
+impl Gradient {
+    /// Stops may be given in any order; they are sorted by position. Stops
+    /// sharing a position keep their input order, producing a hard edge.
+    pub fn new(mut stops: Vec<(f32, Color)>) -> Self {
+        stops.retain(|(t, _)| !t.is_nan());
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self { stops }
+    }
+
+    pub fn stops(&self) -> &[(f32, Color)] {
+        &self.stops
+    }
+
+    pub fn evaluate(&self, t: f32) -> Color {
+        let (first, last) = match (self.stops.first(), self.stops.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return Color::white(),
+        };
+
+        let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+        if t < first.0 {
+            return first.1;
+        }
+        if t >= last.0 {
+            return last.1;
+        }
+
+        // First stop strictly past t; the bracketing pair is (upper - 1, upper).
+        // At a duplicated position this resolves to the last stop of the run.
+        let upper = self.stops.partition_point(|(stop_t, _)| *stop_t <= t);
+        let (t0, c0) = self.stops[upper - 1];
+        let (t1, c1) = self.stops[upper];
+
+        let span = t1 - t0;
+        if span <= f32::EPSILON {
+            return c1;
+        }
+        c0.lerp(&c1, (t - t0) / span)
+    }
+}
+
+/// Color for a health bar filled to `fraction` (1.0 is full health)
+pub fn health_bar_color(fraction: f32, gradient: &Gradient) -> Color {
+    gradient.evaluate(fraction)
+}
+
+/// Point where a projectile fired from `shooter` at `projectile_speed` meets a
+/// target moving at constant `target_vel`, or `None` if it can never catch up.
+/// Shared by AI lead aiming and the player's lead indicator.
+pub fn intercept_point(
+    shooter: Vec2,
+    projectile_speed: f32,
+    target_pos: Vec2,
+    target_vel: Vec2,
+) -> Option<Vec2> {
+    let offset = target_pos - shooter;
+
+    // |offset + target_vel * t| = projectile_speed * t, as a*t^2 + b*t + c = 0
+    let a = target_vel.magnitude2() - projectile_speed * projectile_speed;
+    let b = 2.0 * offset.dot(target_vel);
+    let c = offset.magnitude2();
+
+    if c == 0.0 {
+        return Some(target_pos);
+    }
+
+    let time = if a.abs() < f32::EPSILON {
+        // Equal speeds: only a closing target can be caught
+        if b >= 0.0 {
+            return None;
+        }
+        -c / b
+    } else {
+        let discriminant = b * b - 4.0 * a * c;
+        if discriminant < 0.0 {
+            return None;
+        }
+        let root = discriminant.sqrt();
+        let t1 = (-b - root) / (2.0 * a);
+        let t2 = (-b + root) / (2.0 * a);
+        match (t1 > 0.0, t2 > 0.0) {
+            (true, true) => t1.min(t2),
+            (true, false) => t1,
+            (false, true) => t2,
+            (false, false) => return None,
+        }
+    };
+
+    Some(target_pos + target_vel * time)
+}
+
+/// Volume of a sound at `source` as heard from `listener`, falling off
+/// linearly from `base` to silence at `max_distance`
+pub fn attenuated_volume(base: f32, source: Vec2, listener: Vec2, max_distance: f32) -> f32 {
+    if max_distance <= 0.0 {
+        return 0.0;
+    }
+    let falloff = 1.0 - (source - listener).magnitude() / max_distance;
+    base.max(0.0) * falloff.clamp(0.0, 1.0)
+}
+
+/// Stereo pan in [-1, 1] (left to right) from the horizontal offset of
+/// `source`, reaching hard left or right at `max_distance`
+pub fn stereo_pan(source: Vec2, listener: Vec2, max_distance: f32) -> f32 {
+    if max_distance <= 0.0 {
+        return 0.0;
+    }
+    ((source.x - listener.x) / max_distance).clamp(-1.0, 1.0)
+}
+
+/// Hashes a lattice point to a value in [-1, 1]
+fn lattice_value(x: i32, y: i32, seed: u64) -> f32 {
+    // SplitMix64 finalizer over the packed coordinates
+    let mut h = seed ^ ((x as u32 as u64) << 32 | y as u32 as u64);
+    h = h.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    h = (h ^ (h >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    h = (h ^ (h >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    h ^= h >> 31;
+    (h >> 40) as f32 / (1u64 << 23) as f32 - 1.0
+}
+
+fn smoothstep(t: f32) -> f32 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// Smooth, repeatable 2D noise in [-1, 1]. Random values on the integer
+/// lattice are blended with a smoothstep, so the same inputs and seed always
+/// give the same output and nearby inputs give nearby outputs.
+pub fn value_noise(x: f32, y: f32, seed: u64) -> f32 {
+    let (x0, y0) = (x.floor(), y.floor());
+    let (tx, ty) = (smoothstep(x - x0), smoothstep(y - y0));
+    let (ix, iy) = (x0 as i32, y0 as i32);
+
+    let top = lerp(
+        lattice_value(ix, iy, seed),
+        lattice_value(ix.wrapping_add(1), iy, seed),
+        tx,
+    );
+    let bottom = lerp(
+        lattice_value(ix, iy.wrapping_add(1), seed),
+        lattice_value(ix.wrapping_add(1), iy.wrapping_add(1), seed),
+        tx,
+    );
+    lerp(top, bottom, ty).clamp(-1.0, 1.0)
+}
+
+/// One-dimensional `value_noise`, e.g. sampled over time for jitter
+pub fn value_noise_1d(x: f32, seed: u64) -> f32 {
+    value_noise(x, 0.0, seed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_color(actual: Color, expected: Color) {
+        let close = |a: f32, b: f32| (a - b).abs() < 0.001;
+        assert!(
+            close(actual.r, expected.r)
+                && close(actual.g, expected.g)
+                && close(actual.b, expected.b)
+                && close(actual.a, expected.a),
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_gradient_three_stops() {
+        let red = Color::rgb(1.0, 0.0, 0.0);
+        let green = Color::rgb(0.0, 1.0, 0.0);
+        let blue = Color::rgb(0.0, 0.0, 1.0);
+        let gradient = Gradient::new(vec![(0.0, red), (0.5, green), (1.0, blue)]);
+
+        assert_color(gradient.evaluate(0.25), Color::rgb(0.5, 0.5, 0.0));
+        assert_color(gradient.evaluate(0.5), green);
+        assert_color(gradient.evaluate(0.75), Color::rgb(0.0, 0.5, 0.5));
+    }
+
+    #[test]
+    fn test_health_bar_color() {
+        let red = Color::rgb(1.0, 0.0, 0.0);
+        let yellow = Color::rgb(1.0, 1.0, 0.0);
+        let green = Color::rgb(0.0, 1.0, 0.0);
+        let gradient = Gradient::new(vec![(0.0, red), (0.5, yellow), (1.0, green)]);
+
+        assert_color(health_bar_color(1.0, &gradient), green);
+        assert_color(health_bar_color(0.5, &gradient), yellow);
+        assert_color(health_bar_color(0.0, &gradient), red);
+    }
+
+    #[test]
+    fn test_gradient_clamps_out_of_range() {
+        let red = Color::rgb(1.0, 0.0, 0.0);
+        let blue = Color::rgb(0.0, 0.0, 1.0);
+        let gradient = Gradient::new(vec![(0.2, red), (0.8, blue)]);
+
+        assert_color(gradient.evaluate(-1.0), red);
+        assert_color(gradient.evaluate(0.1), red);
+        assert_color(gradient.evaluate(0.9), blue);
+        assert_color(gradient.evaluate(5.0), blue);
+    }
+
+    #[test]
+    fn test_gradient_unsorted_input() {
+        let red = Color::rgb(1.0, 0.0, 0.0);
+        let green = Color::rgb(0.0, 1.0, 0.0);
+        let blue = Color::rgb(0.0, 0.0, 1.0);
+        let sorted = Gradient::new(vec![(0.0, red), (0.5, green), (1.0, blue)]);
+        let unsorted = Gradient::new(vec![(1.0, blue), (0.0, red), (0.5, green)]);
+
+        assert_eq!(sorted, unsorted);
+        for i in 0..=10 {
+            let t = i as f32 / 10.0;
+            assert_color(unsorted.evaluate(t), sorted.evaluate(t));
+        }
+    }
+
+    #[test]
+    fn test_gradient_duplicate_stops() {
+        let red = Color::rgb(1.0, 0.0, 0.0);
+        let green = Color::rgb(0.0, 1.0, 0.0);
+        let blue = Color::rgb(0.0, 0.0, 1.0);
+        let gradient = Gradient::new(vec![(0.0, red), (0.5, green), (0.5, blue), (1.0, blue)]);
+
+        // Approaching from below blends toward the first duplicate
+        assert_color(gradient.evaluate(0.25), Color::rgb(0.5, 0.5, 0.0));
+        // At and past the shared position the last duplicate wins
+        assert_color(gradient.evaluate(0.5), blue);
+        assert_color(gradient.evaluate(0.75), blue);
+    }
+
+    fn assert_vec(actual: Vec2, expected: Vec2) {
+        assert!(
+            (actual - expected).magnitude() < 0.01,
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_intercept_head_on() {
+        // Target closes at 50 while the shot travels at 150: they meet after 0.5s
+        let point = intercept_point(
+            Vec2::new(0.0, 0.0),
+            150.0,
+            Vec2::new(0.0, 100.0),
+            Vec2::new(0.0, -50.0),
+        )
+        .unwrap();
+
+        assert_vec(point, Vec2::new(0.0, 75.0));
+    }
+
+    #[test]
+    fn test_intercept_crossing() {
+        // 3-4-5 triangle: target 300 away crossing at 400, shot at 500 meets it after 1s
+        let point = intercept_point(
+            Vec2::new(0.0, 0.0),
+            500.0,
+            Vec2::new(0.0, 300.0),
+            Vec2::new(400.0, 0.0),
+        )
+        .unwrap();
+
+        assert_vec(point, Vec2::new(400.0, 300.0));
+    }
+
+    #[test]
+    fn test_intercept_unreachable() {
+        // Target flees faster than the projectile
+        assert_eq!(
+            intercept_point(
+                Vec2::new(0.0, 0.0),
+                100.0,
+                Vec2::new(0.0, 100.0),
+                Vec2::new(0.0, 200.0),
+            ),
+            None
+        );
+
+        // Same speed, moving directly away: never closes the gap
+        assert_eq!(
+            intercept_point(
+                Vec2::new(0.0, 0.0),
+                100.0,
+                Vec2::new(0.0, 100.0),
+                Vec2::new(0.0, 100.0),
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_value_noise_is_deterministic() {
+        for i in 0..50 {
+            let (x, y) = (i as f32 * 0.37, i as f32 * -1.13);
+            assert_eq!(value_noise(x, y, 9), value_noise(x, y, 9));
+            assert_eq!(value_noise_1d(x, 9), value_noise_1d(x, 9));
+        }
+        let differs = (0..50).any(|i| {
+            let x = i as f32 * 0.37;
+            value_noise(x, x, 1) != value_noise(x, x, 2)
+        });
+        assert!(differs);
+    }
+
+    #[test]
+    fn test_value_noise_in_range() {
+        for i in 0..2000 {
+            let (x, y) = (i as f32 * 0.173 - 150.0, i as f32 * 0.071 - 50.0);
+            let n = value_noise(x, y, 42);
+            assert!((-1.0..=1.0).contains(&n), "{} at ({}, {})", n, x, y);
+            assert!((-1.0..=1.0).contains(&value_noise_1d(x, 42)));
+        }
+    }
+
+    #[test]
+    fn test_value_noise_is_continuous() {
+        let step = 0.001;
+        for i in 0..1000 {
+            let (x, y) = (i as f32 * 0.0311 - 10.0, i as f32 * 0.0173);
+            let n = value_noise(x, y, 5);
+            assert!((value_noise(x + step, y, 5) - n).abs() < 0.01);
+            assert!((value_noise(x, y + step, 5) - n).abs() < 0.01);
+            assert!((value_noise_1d(x + step, 5) - value_noise_1d(x, 5)).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn test_volume_falls_off_with_distance() {
+        let listener = Vec2::new(100.0, 50.0);
+        assert_eq!(attenuated_volume(0.8, listener, listener, 400.0), 0.8);
+
+        let halfway = Vec2::new(100.0, 250.0);
+        assert!((attenuated_volume(0.8, halfway, listener, 400.0) - 0.4).abs() < 0.001);
+
+        let far = Vec2::new(100.0, 500.0);
+        assert_eq!(attenuated_volume(0.8, far, listener, 400.0), 0.0);
+        assert_eq!(attenuated_volume(0.8, listener, listener, 0.0), 0.0);
+    }
+
+    #[test]
+    fn test_stereo_pan_follows_side() {
+        let listener = Vec2::new(0.0, 0.0);
+        assert_eq!(stereo_pan(Vec2::new(0.0, 200.0), listener, 400.0), 0.0);
+        assert!((stereo_pan(Vec2::new(-200.0, 0.0), listener, 400.0) + 0.5).abs() < 0.001);
+        assert!((stereo_pan(Vec2::new(200.0, 0.0), listener, 400.0) - 0.5).abs() < 0.001);
+        assert_eq!(stereo_pan(Vec2::new(900.0, 0.0), listener, 400.0), 1.0);
+    }
+}
@@ -3,5 +3,5 @@ pub mod pool;
 pub mod performance;
 
 pub use math::*;
-pub use pool::ObjectPool;
-pub use performance::{PerformanceMetrics, PerformanceMonitor};
+pub use pool::{GrowthPolicy, HandlePool, ObjectPool, PoolHandle};
+pub use performance::{FrameBudget, PerformanceMetrics, PerformanceMonitor};
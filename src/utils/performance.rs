@@ -5,6 +5,9 @@
 
 use std::collections::VecDeque;
 
+/// Frame time for 60 FPS, in milliseconds
+pub const TARGET_FRAME_TIME_MS: f32 = 16.67;
+
 /// Ring buffer for storing a fixed number of recent values
 pub struct RingBuffer<T> {
     data: VecDeque<T>,
@@ -43,29 +46,30 @@ impl<T> RingBuffer<T> {
     }
 }
 
+/// Statistics skip NaN samples (e.g. from a bad frame-time computation) so a
+/// single glitch can't poison or crash the profiler. With no valid samples
+/// they return 0.0.
 impl RingBuffer<f32> {
+    fn samples(&self) -> impl Iterator<Item = f32> + '_ {
+        self.data.iter().copied().filter(|v| !v.is_nan())
+    }
+
     pub fn average(&self) -> f32 {
-        if self.data.is_empty() {
+        let (sum, count) = self
+            .samples()
+            .fold((0.0, 0usize), |(sum, count), v| (sum + v, count + 1));
+        if count == 0 {
             return 0.0;
         }
-        let sum: f32 = self.data.iter().sum();
-        sum / self.data.len() as f32
+        sum / count as f32
     }
 
     pub fn min(&self) -> f32 {
-        self.data
-            .iter()
-            .copied()
-            .min_by(|a, b| a.partial_cmp(b).unwrap())
-            .unwrap_or(0.0)
+        self.samples().min_by(f32::total_cmp).unwrap_or(0.0)
     }
 
     pub fn max(&self) -> f32 {
-        self.data
-            .iter()
-            .copied()
-            .max_by(|a, b| a.partial_cmp(b).unwrap())
-            .unwrap_or(0.0)
+        self.samples().max_by(f32::total_cmp).unwrap_or(0.0)
     }
 }
 
@@ -104,6 +108,57 @@ pub struct PerformanceMetrics {
     pub entities: u32,
 }
 
+/// Time left in the current frame, so low-priority work (particles, LOD
+/// updates) can be skipped or spread over several frames when a frame runs
+/// long
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameBudget {
+    target_ms: f32,
+    elapsed_ms: f32,
+}
+
+impl FrameBudget {
+    pub fn new(target_ms: f32) -> Self {
+        Self {
+            target_ms,
+            elapsed_ms: 0.0,
+        }
+    }
+
+    pub fn target_ms(&self) -> f32 {
+        self.target_ms
+    }
+
+    pub fn elapsed_ms(&self) -> f32 {
+        self.elapsed_ms
+    }
+
+    pub fn remaining_ms(&self) -> f32 {
+        (self.target_ms - self.elapsed_ms).max(0.0)
+    }
+
+    /// Whether work estimated at `estimated_ms` fits in what is left of the
+    /// frame
+    pub fn has_time_for(&self, estimated_ms: f32) -> bool {
+        self.elapsed_ms + estimated_ms.max(0.0) <= self.target_ms
+    }
+
+    pub fn set_elapsed(&mut self, elapsed_ms: f32) {
+        self.elapsed_ms = elapsed_ms.max(0.0);
+    }
+
+    /// Starts a new frame with the whole budget available
+    pub fn reset(&mut self) {
+        self.elapsed_ms = 0.0;
+    }
+}
+
+impl Default for FrameBudget {
+    fn default() -> Self {
+        Self::new(TARGET_FRAME_TIME_MS)
+    }
+}
+
 /// Performance monitor that tracks various metrics over time
 pub struct PerformanceMonitor {
     frame_times: RingBuffer<f32>,
@@ -119,6 +174,7 @@ pub struct PerformanceMonitor {
     pub triangles_drawn: u32,
     pub entity_count: u32,
     
+    budget: FrameBudget,
     sample_count: usize,
 }
 
@@ -135,12 +191,14 @@ impl PerformanceMonitor {
             draw_calls: 0,
             triangles_drawn: 0,
             entity_count: 0,
+            budget: FrameBudget::default(),
             sample_count,
         }
     }
 
     pub fn begin_frame(&mut self, current_time: f64) {
         self.frame_start = current_time;
+        self.budget.reset();
         self.draw_calls = 0;
         self.triangles_drawn = 0;
     }
@@ -168,12 +226,24 @@ impl PerformanceMonitor {
         self.frame_times.push(frame_time);
 
         // Check for performance issues
-        if frame_time > 16.67 {
+        if frame_time > TARGET_FRAME_TIME_MS {
             // Below 60 FPS
             self.log_performance_warning(frame_time);
         }
     }
 
+    /// The current frame's budget, with time elapsed since `begin_frame`
+    /// measured at `current_time`
+    pub fn frame_budget(&mut self, current_time: f64) -> FrameBudget {
+        let elapsed = (current_time - self.frame_start) as f32 * 1000.0;
+        self.budget.set_elapsed(elapsed);
+        self.budget
+    }
+
+    pub fn set_target_frame_time(&mut self, target_ms: f32) {
+        self.budget = FrameBudget::new(target_ms);
+    }
+
     pub fn get_average_fps(&self) -> f32 {
         let avg_frame_time = self.frame_times.average();
         if avg_frame_time > 0.0 {
@@ -329,6 +399,25 @@ mod tests {
         assert_eq!(buffer.max(), 20.0);
     }
 
+    #[test]
+    fn test_ring_buffer_ignores_nan() {
+        let mut buffer = RingBuffer::new(4);
+        buffer.push(4.0);
+        buffer.push(f32::NAN);
+        buffer.push(2.0);
+        buffer.push(6.0);
+
+        assert_eq!(buffer.min(), 2.0);
+        assert_eq!(buffer.max(), 6.0);
+        assert_eq!(buffer.average(), 4.0);
+
+        let mut only_nan = RingBuffer::new(2);
+        only_nan.push(f32::NAN);
+        assert_eq!(only_nan.min(), 0.0);
+        assert_eq!(only_nan.max(), 0.0);
+        assert_eq!(only_nan.average(), 0.0);
+    }
+
     #[test]
     fn test_performance_monitor() {
         let mut monitor = PerformanceMonitor::new(60);
@@ -344,6 +433,41 @@ mod tests {
         assert!(monitor.get_average_fps() > 0.0);
     }
 
+    #[test]
+    fn test_frame_budget() {
+        let mut budget = FrameBudget::new(16.0);
+        assert!(budget.has_time_for(16.0));
+
+        budget.set_elapsed(12.0);
+        assert!(budget.has_time_for(4.0));
+        assert!(!budget.has_time_for(5.0));
+        assert_eq!(budget.remaining_ms(), 4.0);
+
+        budget.set_elapsed(20.0);
+        assert!(!budget.has_time_for(0.0));
+        assert_eq!(budget.remaining_ms(), 0.0);
+
+        budget.reset();
+        assert!(budget.has_time_for(10.0));
+    }
+
+    #[test]
+    fn test_frame_budget_resets_each_frame() {
+        let mut monitor = PerformanceMonitor::new(60);
+        monitor.set_target_frame_time(16.0);
+
+        monitor.begin_frame(0.0);
+        assert!(monitor.frame_budget(0.010).has_time_for(5.0));
+        assert!(!monitor.frame_budget(0.014).has_time_for(5.0));
+        assert!(!monitor.frame_budget(0.020).has_time_for(0.0));
+        monitor.end_frame(0.020);
+
+        monitor.begin_frame(0.020);
+        let budget = monitor.frame_budget(0.021);
+        assert!(budget.has_time_for(10.0));
+        assert!(!budget.has_time_for(16.0));
+    }
+
     #[test]
     fn test_timer() {
         let timer = Timer::new();
@@ -2,6 +2,17 @@
 
 use std::marker::PhantomData;
 
+/// How a pool behaves once `max_size` objects are in use
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GrowthPolicy {
+    /// Never exceed `max_size`; `acquire` returns `None` when exhausted
+    Fixed,
+    /// Raise the limit by `increment` objects each time the pool runs dry
+    Grow { increment: usize },
+    /// Always allocate when no pooled object is available
+    Unbounded,
+}
+
 /// Object pool for reusing objects
 pub struct ObjectPool<T> {
     available: Vec<T>,
@@ -9,6 +20,9 @@ pub struct ObjectPool<T> {
     factory: Box<dyn Fn() -> T>,
     reset: Box<dyn Fn(&mut T)>,
     max_size: usize,
+    limit: usize,
+    allocated: usize,
+    growth_policy: GrowthPolicy,
 }
 
 impl<T> ObjectPool<T> {
@@ -23,19 +37,46 @@ impl<T> ObjectPool<T> {
             factory: Box::new(factory),
             reset: Box::new(reset),
             max_size,
+            limit: max_size,
+            allocated: 0,
+            growth_policy: GrowthPolicy::Fixed,
         }
     }
     
+    pub fn with_growth_policy(mut self, policy: GrowthPolicy) -> Self {
+        self.growth_policy = policy;
+        self
+    }
+    
     pub fn acquire(&mut self) -> Option<T> {
         if let Some(obj) = self.available.pop() {
             self.in_use_count += 1;
-            Some(obj)
-        } else if self.in_use_count < self.max_size {
-            self.in_use_count += 1;
-            Some((self.factory)())
-        } else {
-            None
+            return Some(obj);
+        }
+        
+        if self.in_use_count >= self.limit {
+            match self.growth_policy {
+                GrowthPolicy::Fixed => return None,
+                GrowthPolicy::Grow { increment } => {
+                    // Allocate a whole batch so the next few acquires are cheap
+                    let increment = increment.max(1);
+                    self.limit += increment;
+                    for _ in 1..increment {
+                        let obj = self.create();
+                        self.available.push(obj);
+                    }
+                }
+                GrowthPolicy::Unbounded => {}
+            }
         }
+        
+        self.in_use_count += 1;
+        Some(self.create())
+    }
+    
+    fn create(&mut self) -> T {
+        self.allocated += 1;
+        (self.factory)()
     }
     
     pub fn release(&mut self, mut obj: T) {
@@ -55,6 +96,189 @@ impl<T> ObjectPool<T> {
     pub fn capacity(&self) -> usize {
         self.max_size
     }
+    
+    pub fn growth_policy(&self) -> GrowthPolicy {
+        self.growth_policy
+    }
+    
+    /// Whether the pool ever allocated beyond its configured `max_size`
+    pub fn exceeded_soft_cap(&self) -> bool {
+        self.overage() > 0
+    }
+    
+    /// Number of objects allocated beyond `max_size`
+    pub fn overage(&self) -> usize {
+        self.allocated.saturating_sub(self.max_size)
+    }
+}
+
+/// Handle to an object living inside a `HandlePool`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PoolHandle {
+    index: u32,
+    generation: u32,
+}
+
+struct Slot<T> {
+    value: T,
+    generation: u32,
+    in_use: bool,
+    /// Acquisition order, used to pick the oldest object to recycle
+    acquired_at: u64,
+}
+
+/// Pool that keeps objects in place and hands out handles, so systems can
+/// iterate and update every active object without owning them
+pub struct HandlePool<T> {
+    slots: Vec<Slot<T>>,
+    free: Vec<usize>,
+    in_use_count: usize,
+    next_acquire: u64,
+    factory: Box<dyn Fn() -> T>,
+    reset: Box<dyn Fn(&mut T)>,
+    max_size: usize,
+}
+
+impl<T> HandlePool<T> {
+    pub fn new<F, R>(factory: F, reset: R, max_size: usize) -> Self
+    where
+        F: Fn() -> T + 'static,
+        R: Fn(&mut T) + 'static,
+    {
+        HandlePool {
+            slots: Vec::with_capacity(max_size / 2),
+            free: Vec::new(),
+            in_use_count: 0,
+            next_acquire: 0,
+            factory: Box::new(factory),
+            reset: Box::new(reset),
+            max_size,
+        }
+    }
+    
+    pub fn acquire(&mut self) -> Option<PoolHandle> {
+        let index = if let Some(index) = self.free.pop() {
+            index
+        } else if self.slots.len() < self.max_size {
+            self.slots.push(Slot {
+                value: (self.factory)(),
+                generation: 0,
+                in_use: false,
+                acquired_at: 0,
+            });
+            self.slots.len() - 1
+        } else {
+            return None;
+        };
+        
+        let slot = &mut self.slots[index];
+        slot.in_use = true;
+        slot.acquired_at = self.next_acquire;
+        self.next_acquire += 1;
+        self.in_use_count += 1;
+        Some(PoolHandle {
+            index: index as u32,
+            generation: slot.generation,
+        })
+    }
+    
+    /// Like `acquire`, but at capacity resets and hands back the oldest
+    /// in-use object instead of failing, invalidating its previous handle.
+    /// Only returns `None` for a zero-capacity pool.
+    pub fn acquire_recycle(&mut self) -> Option<PoolHandle> {
+        if let Some(handle) = self.acquire() {
+            return Some(handle);
+        }
+        
+        let (index, slot) = self
+            .slots
+            .iter_mut()
+            .enumerate()
+            .filter(|(_, slot)| slot.in_use)
+            .min_by_key(|(_, slot)| slot.acquired_at)?;
+        (self.reset)(&mut slot.value);
+        slot.generation = slot.generation.wrapping_add(1);
+        slot.acquired_at = self.next_acquire;
+        self.next_acquire += 1;
+        Some(PoolHandle {
+            index: index as u32,
+            generation: slot.generation,
+        })
+    }
+    
+    /// Resets the object and invalidates every copy of the handle
+    pub fn release(&mut self, handle: PoolHandle) -> bool {
+        let reset = &self.reset;
+        match self.slots.get_mut(handle.index as usize) {
+            Some(slot) if slot.in_use && slot.generation == handle.generation => {
+                reset(&mut slot.value);
+                slot.in_use = false;
+                slot.generation = slot.generation.wrapping_add(1);
+                self.free.push(handle.index as usize);
+                self.in_use_count -= 1;
+                true
+            }
+            _ => false,
+        }
+    }
+    
+    pub fn get(&self, handle: PoolHandle) -> Option<&T> {
+        self.slots
+            .get(handle.index as usize)
+            .filter(|slot| slot.in_use && slot.generation == handle.generation)
+            .map(|slot| &slot.value)
+    }
+    
+    pub fn get_mut(&mut self, handle: PoolHandle) -> Option<&mut T> {
+        self.slots
+            .get_mut(handle.index as usize)
+            .filter(|slot| slot.in_use && slot.generation == handle.generation)
+            .map(|slot| &mut slot.value)
+    }
+    
+    pub fn is_valid(&self, handle: PoolHandle) -> bool {
+        self.get(handle).is_some()
+    }
+    
+    pub fn iter_in_use(&self) -> impl Iterator<Item = (PoolHandle, &T)> {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter(|(_, slot)| slot.in_use)
+            .map(|(index, slot)| {
+                let handle = PoolHandle {
+                    index: index as u32,
+                    generation: slot.generation,
+                };
+                (handle, &slot.value)
+            })
+    }
+    
+    pub fn iter_in_use_mut(&mut self) -> impl Iterator<Item = (PoolHandle, &mut T)> {
+        self.slots
+            .iter_mut()
+            .enumerate()
+            .filter(|(_, slot)| slot.in_use)
+            .map(|(index, slot)| {
+                let handle = PoolHandle {
+                    index: index as u32,
+                    generation: slot.generation,
+                };
+                (handle, &mut slot.value)
+            })
+    }
+    
+    pub fn available_count(&self) -> usize {
+        self.free.len()
+    }
+    
+    pub fn in_use_count(&self) -> usize {
+        self.in_use_count
+    }
+    
+    pub fn capacity(&self) -> usize {
+        self.max_size
+    }
 }
 
 #[cfg(test)]
@@ -230,4 +454,151 @@ mod tests {
         let obj2 = pool.acquire().unwrap();
         assert_eq!(pool.in_use_count(), 1);
     }
+    
+    #[test]
+    fn test_object_pool_fixed_policy() {
+        let mut pool = ObjectPool::new(
+            || TestObject::new(),
+            |obj| obj.reset(),
+            2
+        ).with_growth_policy(GrowthPolicy::Fixed);
+        
+        assert!(pool.acquire().is_some());
+        assert!(pool.acquire().is_some());
+        assert!(pool.acquire().is_none());
+        assert!(!pool.exceeded_soft_cap());
+        assert_eq!(pool.overage(), 0);
+    }
+    
+    #[test]
+    fn test_object_pool_grow_policy() {
+        let mut pool = ObjectPool::new(
+            || TestObject::new(),
+            |obj| obj.reset(),
+            2
+        ).with_growth_policy(GrowthPolicy::Grow { increment: 3 });
+        
+        let _obj1 = pool.acquire().unwrap();
+        let _obj2 = pool.acquire().unwrap();
+        assert!(!pool.exceeded_soft_cap());
+        
+        // Exhausted: a batch of 3 is allocated, one handed out
+        let _obj3 = pool.acquire().unwrap();
+        assert_eq!(pool.in_use_count(), 3);
+        assert_eq!(pool.available_count(), 2);
+        assert!(pool.exceeded_soft_cap());
+        assert_eq!(pool.overage(), 3);
+        
+        // Remainder of the batch is served without further allocation
+        let _obj4 = pool.acquire().unwrap();
+        let _obj5 = pool.acquire().unwrap();
+        assert_eq!(pool.overage(), 3);
+    }
+    
+    #[test]
+    fn test_object_pool_unbounded_policy() {
+        let mut pool = ObjectPool::new(
+            || TestObject::new(),
+            |obj| obj.reset(),
+            1
+        ).with_growth_policy(GrowthPolicy::Unbounded);
+        
+        for _ in 0..5 {
+            assert!(pool.acquire().is_some());
+        }
+        assert_eq!(pool.overage(), 4);
+    }
+    
+    #[test]
+    fn test_handle_pool_acquire_and_iterate() {
+        let mut pool = HandlePool::new(
+            || TestObject::new(),
+            |obj| obj.reset(),
+            10
+        );
+        
+        let a = pool.acquire().unwrap();
+        let b = pool.acquire().unwrap();
+        let c = pool.acquire().unwrap();
+        assert_eq!(pool.in_use_count(), 3);
+        
+        for (_, obj) in pool.iter_in_use_mut() {
+            obj.value += 5;
+        }
+        pool.get_mut(b).unwrap().value = 42;
+        
+        assert_eq!(pool.get(a).unwrap().value, 5);
+        assert_eq!(pool.get(b).unwrap().value, 42);
+        assert_eq!(pool.get(c).unwrap().value, 5);
+        
+        pool.release(b);
+        let active: Vec<PoolHandle> = pool.iter_in_use().map(|(h, _)| h).collect();
+        assert_eq!(active, vec![a, c]);
+    }
+    
+    #[test]
+    fn test_handle_pool_release_invalidates_handle() {
+        let mut pool = HandlePool::new(
+            || TestObject::new(),
+            |obj| obj.reset(),
+            1
+        );
+        
+        let handle = pool.acquire().unwrap();
+        pool.get_mut(handle).unwrap().value = 7;
+        assert!(pool.acquire().is_none());
+        
+        assert!(pool.release(handle));
+        assert!(!pool.is_valid(handle));
+        assert!(pool.get(handle).is_none());
+        assert!(!pool.release(handle));
+        assert_eq!(pool.in_use_count(), 0);
+        
+        // The slot is reused, reset, and the stale handle stays dead
+        let reused = pool.acquire().unwrap();
+        assert_ne!(reused, handle);
+        assert_eq!(pool.get(reused).unwrap().value, 0);
+        assert!(pool.get_mut(handle).is_none());
+    }
+    
+    #[test]
+    fn test_handle_pool_acquire_recycle_evicts_oldest() {
+        let mut pool = HandlePool::new(
+            || TestObject::new(),
+            |obj| obj.reset(),
+            3
+        );
+        
+        let handles: Vec<PoolHandle> = (1..=3)
+            .map(|value| {
+                let handle = pool.acquire_recycle().unwrap();
+                pool.get_mut(handle).unwrap().value = value;
+                handle
+            })
+            .collect();
+        assert!(pool.acquire().is_none());
+        
+        // Oldest first, even after a newer slot was released and refilled
+        pool.release(handles[1]);
+        let refilled = pool.acquire().unwrap();
+        pool.get_mut(refilled).unwrap().value = 4;
+        
+        let recycled = pool.acquire_recycle().unwrap();
+        assert!(!pool.is_valid(handles[0]));
+        assert_eq!(pool.get(recycled).unwrap().value, 0);
+        assert_eq!(pool.in_use_count(), 3);
+        
+        let recycled_again = pool.acquire_recycle().unwrap();
+        assert!(!pool.is_valid(handles[2]));
+        assert!(pool.is_valid(refilled));
+        assert!(pool.is_valid(recycled));
+        assert_ne!(recycled_again, recycled);
+        assert!(pool.acquire().is_none());
+    }
+    
+    #[test]
+    fn test_handle_pool_acquire_recycle_zero_capacity() {
+        let mut pool = HandlePool::new(|| TestObject::new(), |obj| obj.reset(), 0);
+        assert!(pool.acquire_recycle().is_none());
+    }
 }
@@ -19,21 +19,26 @@ impl<T> WeightedRandom<T> {
     }
 
     pub fn select<R: Rng>(&self, rng: &mut R) -> Option<&T> {
+        self.select_index(rng).map(|index| &self.items[index].0)
+    }
+
+    /// Position of the chosen item, for callers keeping parallel data
+    pub fn select_index<R: Rng>(&self, rng: &mut R) -> Option<usize> {
         if self.items.is_empty() {
             return None;
         }
 
         let mut random = rng.gen::<f32>() * self.total_weight;
 
-        for (item, weight) in &self.items {
+        for (index, (_, weight)) in self.items.iter().enumerate() {
             if random < *weight {
-                return Some(item);
+                return Some(index);
             }
             random -= weight;
         }
 
         // Fallback to last item (handles floating point precision issues)
-        self.items.last().map(|(item, _)| item)
+        Some(self.items.len() - 1)
     }
 
     pub fn items(&self) -> &[(T, f32)] {
@@ -78,4 +83,37 @@ mod tests {
         assert!(counts["common"] > counts["rare"]);
         assert!(counts["rare"] > counts["legendary"]);
     }
+
+    #[test]
+    fn test_select_index_matches_weights() {
+        let mut weighted = WeightedRandom::new();
+        weighted.add("a", 1.0);
+        weighted.add("b", 3.0);
+        weighted.add("c", 6.0);
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut counts = [0u32; 3];
+        let draws = 10_000;
+        for _ in 0..draws {
+            counts[weighted.select_index(&mut rng).unwrap()] += 1;
+        }
+
+        for (count, expected) in counts.iter().zip([0.1, 0.3, 0.6]) {
+            let observed = *count as f32 / draws as f32;
+            assert!(
+                (observed - expected).abs() < 0.02,
+                "{} vs {}",
+                observed,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_select_index_empty() {
+        let weighted: WeightedRandom<u32> = WeightedRandom::new();
+        let mut rng = StdRng::seed_from_u64(1);
+        assert_eq!(weighted.select_index(&mut rng), None);
+        assert_eq!(weighted.select(&mut rng), None);
+    }
 }